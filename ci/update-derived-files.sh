@@ -36,7 +36,9 @@ for cmd in \
     activate-pane \
     activate-pane-direction \
     adjust-pane-size \
+    context-menu \
     activate-tab \
+    dump-modal \
     get-pane-direction \
     get-text \
     kill-pane \
@@ -47,6 +49,7 @@ for cmd in \
     send-text \
     set-tab-title \
     set-window-title \
+    show-context-menu-items \
     spawn \
     split-pane \
     zoom-pane \
@@ -441,7 +441,7 @@ macro_rules! pdu {
 /// The overall version of the codec.
 /// This must be bumped when backwards incompatible changes
 /// are made to the types and protocol.
-pub const CODEC_VERSION: usize = 45;
+pub const CODEC_VERSION: usize = 48;
 
 // Defines the Pdu enum.
 // Each struct has an explicit identifying number.
@@ -502,9 +502,34 @@ pdu! {
     GetPaneDirection: 60,
     GetPaneDirectionResponse: 61,
     AdjustPaneSize: 62,
+    ShowContextMenu: 63,
+    GetContextMenuItems: 64,
+    GetContextMenuItemsResponse: 65,
+    DumpModal: 66,
+    DumpModalResponse: 67,
 }
 
 impl Pdu {
+    /// Returns true if this type of Pdu only observes the state of
+    /// the mux or of its panes.
+    /// Read-only sessions refuse everything else, so that newly
+    /// added types of Pdu are refused until they are listed here.
+    pub fn is_observation(&self) -> bool {
+        match self {
+            Self::Ping(_)
+            | Self::GetCodecVersion(_)
+            | Self::ListPanes(_)
+            | Self::GetLines(_)
+            | Self::GetPaneRenderChanges(_)
+            | Self::GetPaneRenderableDimensions(_)
+            | Self::GetImageCell(_)
+            | Self::SearchScrollbackRequest(_)
+            | Self::GetContextMenuItems(_)
+            | Self::DumpModal(_) => true,
+            _ => false,
+        }
+    }
+
     /// Returns true if this type of Pdu represents action taken
     /// directly by a user, rather than background traffic on
     /// a live connection
@@ -876,6 +901,42 @@ pub struct AdjustPaneSize {
     pub amount: usize,
 }
 
+/// Asks the gui to show a context menu over `pane_id`
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct ShowContextMenu {
+    pub pane_id: PaneId,
+    /// The menu items, as a JSON array of MenuItemDef.
+    /// KeyAssignment has no serde support, so the items are
+    /// passed as text and parsed on the receiving side.
+    pub items_json: String,
+}
+
+/// Asks the gui for the items of the context menu that it
+/// would show over `pane_id`
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct GetContextMenuItems {
+    pub pane_id: PaneId,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct GetContextMenuItemsResponse {
+    /// The menu items as a JSON array, nested menus included
+    pub items_json: String,
+}
+
+/// Asks the gui for the state of the topmost modal of `window_id`
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct DumpModal {
+    pub window_id: WindowId,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct DumpModalResponse {
+    /// The state of the modal as JSON, or None if
+    /// the window has no modal open
+    pub modal_json: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
 pub struct GetPaneDirectionResponse {
     pub pane_id: Option<PaneId>,
@@ -1173,6 +1234,19 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_pdu_is_observation() {
+        assert!(Pdu::Ping(Ping {}).is_observation());
+        assert!(Pdu::ListPanes(ListPanes {}).is_observation());
+        assert!(!Pdu::GetTlsCreds(GetTlsCreds {}).is_observation());
+        assert!(!Pdu::KillPane(KillPane { pane_id: 0 }).is_observation());
+        assert!(!Pdu::SetClientId(SetClientId {
+            client_id: ClientId::new(),
+            is_proxy: false,
+        })
+        .is_observation());
+    }
+
     #[test]
     fn test_pdu_ping() {
         let mut encoded = Vec::new();
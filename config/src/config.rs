@@ -11,11 +11,13 @@ use crate::font::{
 };
 use crate::frontend::FrontEndSelection;
 use crate::keyassignment::{
-    KeyAssignment, KeyTable, KeyTableEntry, KeyTables, MouseEventTrigger, SpawnCommand,
+    ContextMenuConfig, KeyAssignment, KeyTable, KeyTableEntry, KeyTables, MenuItemDef,
+    MouseEventTrigger, SpawnCommand,
 };
 use crate::keys::{Key, LeaderKey, Mouse};
 use crate::lua::make_lua_context;
 use crate::ssh::{SshBackend, SshDomain};
+use crate::tab_template::TabTemplate;
 use crate::tls::{TlsDomainClient, TlsDomainServer};
 use crate::units::Dimension;
 use crate::unix::UnixDomain;
@@ -163,6 +165,79 @@ pub struct Config {
     pub command_palette_font_size: f64,
 
     pub command_palette_rows: Option<usize>,
+
+    /// Font size to use for the context menu.
+    /// Defaults to command_palette_font_size.
+    #[dynamic(default)]
+    pub context_menu_font_size: Option<f64>,
+
+    /// Colors of the context menu when its theme is Default.
+    /// The background and text default to the command palette colors,
+    /// and the border defaults to the background color.
+    #[dynamic(default)]
+    pub context_menu_bg_color: Option<RgbaColor>,
+    #[dynamic(default)]
+    pub context_menu_fg_color: Option<RgbaColor>,
+    #[dynamic(default)]
+    pub context_menu_border_color: Option<RgbaColor>,
+
+    /// The background of the count badges shown on some context menu items
+    #[dynamic(default = "default_context_menu_badge_bg_color")]
+    pub context_menu_badge_bg_color: RgbaColor,
+
+    /// The radius of the corners of the context menu, in cells.
+    /// 0 gives square corners.
+    #[dynamic(default = "default_context_menu_corner_radius")]
+    pub context_menu_corner_radius: f64,
+
+    /// The most columns that the context menu may use to show items
+    /// that don't fit in the height of the window; items beyond what
+    /// fits in that many columns are reached by scrolling
+    #[dynamic(default = "default_context_menu_max_columns")]
+    pub context_menu_max_columns: usize,
+
+    /// The space above and below each context menu item, in cells.
+    /// Twice as much is left to the left and right of the item.
+    #[dynamic(default = "default_context_menu_item_padding_cells")]
+    pub context_menu_item_padding_cells: f64,
+
+    /// How long the highlight of the selected context menu item takes
+    /// to slide to the newly selected item, in milliseconds.
+    /// 0 moves it at once.
+    #[dynamic(default = "default_context_menu_selection_animation_ms")]
+    pub context_menu_selection_animation_ms: u64,
+
+    /// The standard deviation of the gaussian blur applied to the
+    /// context menu shadow, in pixels at 96 DPI
+    #[dynamic(default = "default_context_menu_shadow_blur_sigma")]
+    pub context_menu_shadow_blur_sigma: f64,
+
+    /// Whether to blur the part of the window that is behind the
+    /// context menu, which shows through when `context_menu_bg_color`
+    /// is translucent. Only supported with `front_end = "WebGpu"`,
+    /// and only applies to windows opened after it is enabled.
+    #[dynamic(default)]
+    pub context_menu_blur_background: bool,
+
+    /// The standard deviation of the gaussian blur applied behind the
+    /// context menu, in pixels at 96 DPI
+    #[dynamic(default = "default_context_menu_background_blur_sigma")]
+    pub context_menu_background_blur_sigma: f64,
+
+    /// A command that is run via the shell each time the default
+    /// context menu is opened. It is passed a JSON object describing
+    /// the pane on its stdin, and prints a JSON array of items, in the
+    /// same form as `context_menus` entries, to add to the top of the
+    /// menu. The menu is shown without waiting for it; its items are
+    /// added when it finishes.
+    #[dynamic(default)]
+    pub context_menu_external_command: Option<String>,
+
+    /// How long to wait for `context_menu_external_command` to print
+    /// its items, in milliseconds, before giving up on them
+    #[dynamic(default = "default_context_menu_external_command_timeout_ms")]
+    pub context_menu_external_command_timeout_ms: u64,
+
     #[dynamic(default = "default_command_palette_fg_color")]
     pub command_palette_fg_color: RgbaColor,
 
@@ -419,6 +494,11 @@ pub struct Config {
     #[dynamic(default)]
     pub debug_key_events: bool,
 
+    /// Shows additional diagnostic entries, such as the font
+    /// metrics overlay, in the context menu
+    #[dynamic(default)]
+    pub debug_mode: bool,
+
     #[dynamic(default)]
     pub normalize_output_to_unicode_nfc: bool,
 
@@ -588,6 +668,12 @@ pub struct Config {
     #[dynamic(default = "default_one_point_oh")]
     pub window_background_opacity: f32,
 
+    /// The background opacity to use instead of window_background_opacity
+    /// while the window does not have the focus, after ToggleDimOnUnfocus
+    /// has been used to turn on dimming for that window.
+    #[dynamic(default = "default_dim_when_unfocused_opacity")]
+    pub dim_when_unfocused_opacity: f32,
+
     /// inactive_pane_hue, inactive_pane_saturation and
     /// inactive_pane_brightness allow for transforming the color
     /// of inactive panes.
@@ -718,6 +804,20 @@ pub struct Config {
     #[dynamic(default)]
     pub launch_menu: Vec<SpawnCommand>,
 
+    /// Named menus that can be shown using ShowNamedContextMenu
+    #[dynamic(default)]
+    pub context_menus: HashMap<String, Vec<MenuItemDef>>,
+
+    /// Extra sections for the default context menu, placed among
+    /// the built-in sections according to their priority
+    #[dynamic(default)]
+    pub context_menu: ContextMenuConfig,
+
+    /// Layouts offered by the "New Tab from Template" menu,
+    /// created using wezterm.tab_template
+    #[dynamic(default)]
+    pub tab_templates: Vec<TabTemplate>,
+
     #[dynamic(default)]
     pub use_box_model_render: bool,
 
@@ -810,6 +910,21 @@ pub struct Config {
     #[dynamic(default = "default_swallow_mouse_click_on_window_focus")]
     pub swallow_mouse_click_on_window_focus: bool,
 
+    /// How long, in milliseconds, the left button (or a finger on
+    /// a touch display) must be held in place over the terminal
+    /// before the context menu is shown.
+    /// The default of 0 disables long-press, because otherwise an
+    /// ordinary held left click on a desktop would open the menu.
+    #[dynamic(default = "default_context_menu_long_press_ms")]
+    pub context_menu_long_press_ms: u64,
+
+    /// When enabled, keep a local count of the actions that are
+    /// activated through the context menu. The counts are stored
+    /// in the data directory and can be shown using
+    /// `wezterm show-context-menu-stats`.
+    #[dynamic(default)]
+    pub context_menu_usage_stats: bool,
+
     #[dynamic(default)]
     pub pane_focus_follows_mouse: bool,
 
@@ -819,6 +934,10 @@ pub struct Config {
     #[dynamic(default = "default_max_fps")]
     pub max_fps: u64,
 
+    /// Used instead of max_fps while TogglePerformanceMode is in effect
+    #[dynamic(default = "default_performance_mode_max_fps")]
+    pub performance_mode_max_fps: u64,
+
     #[dynamic(default = "default_shape_cache_size")]
     pub shape_cache_size: usize,
     #[dynamic(default = "default_line_state_cache_size")]
@@ -839,6 +958,11 @@ pub struct Config {
     #[dynamic(default)]
     pub canonicalize_pasted_newlines: Option<NewlineCanon>,
 
+    /// How long to pause between each pane when performing
+    /// PasteToAllPanes, so that slow processes are not overwhelmed
+    #[dynamic(default = "default_paste_to_all_delay_ms")]
+    pub paste_to_all_delay_ms: u64,
+
     #[dynamic(default = "default_unicode_version")]
     pub unicode_version: u8,
 
@@ -1649,6 +1773,38 @@ fn default_command_palette_bg_color() -> RgbaColor {
     (0x33, 0x33, 0x33).into()
 }
 
+fn default_context_menu_badge_bg_color() -> RgbaColor {
+    (0xe0, 0x36, 0x2c).into()
+}
+
+fn default_context_menu_corner_radius() -> f64 {
+    0.25
+}
+
+fn default_context_menu_max_columns() -> usize {
+    1
+}
+
+fn default_context_menu_item_padding_cells() -> f64 {
+    0.1
+}
+
+fn default_context_menu_selection_animation_ms() -> u64 {
+    80
+}
+
+fn default_context_menu_shadow_blur_sigma() -> f64 {
+    4.0
+}
+
+fn default_context_menu_background_blur_sigma() -> f64 {
+    8.0
+}
+
+fn default_context_menu_external_command_timeout_ms() -> u64 {
+    500
+}
+
 fn default_swallow_mouse_click_on_window_focus() -> bool {
     cfg!(target_os = "macos")
 }
@@ -1665,6 +1821,14 @@ fn default_ratelimit_line_prefetches_per_second() -> u32 {
     50
 }
 
+fn default_context_menu_long_press_ms() -> u64 {
+    0
+}
+
+fn default_paste_to_all_delay_ms() -> u64 {
+    50
+}
+
 fn default_cursor_blink_rate() -> u64 {
     800
 }
@@ -1687,7 +1851,7 @@ fn default_scrollback_lines() -> usize {
     3500
 }
 
-const MAX_SCROLLBACK_LINES: usize = 999_999_999;
+pub const MAX_SCROLLBACK_LINES: usize = 999_999_999;
 fn validate_scrollback_lines(value: &usize) -> Result<(), String> {
     if *value > MAX_SCROLLBACK_LINES {
         return Err(format!(
@@ -1806,6 +1970,10 @@ fn default_max_fps() -> u64 {
     60
 }
 
+fn default_performance_mode_max_fps() -> u64 {
+    20
+}
+
 fn default_tiling_desktop_environments() -> Vec<String> {
     [
         "X11 LG3D",
@@ -1931,6 +2099,10 @@ const fn default_reverse_video_cursor_min_contrast() -> f32 {
     2.5
 }
 
+const fn default_dim_when_unfocused_opacity() -> f32 {
+    0.7
+}
+
 #[derive(FromDynamic, ToDynamic, Clone, Copy, Debug)]
 pub struct WindowPadding {
     #[dynamic(try_from = "crate::units::PixelUnit", default = "default_one_cell")]
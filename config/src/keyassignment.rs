@@ -531,15 +531,69 @@ fn default_message() -> String {
     "🛑 Really continue?".to_string()
 }
 
+/// An item in a custom context menu, either one of those defined by
+/// `context_menus` or one passed to `TermWindow::open_context_menu`
+#[derive(Debug, Clone, PartialEq, FromDynamic, ToDynamic)]
+pub struct MenuItemDef {
+    pub label: String,
+    /// The name of a nerdfont glyph to show alongside the label,
+    /// or a single character to show as-is
+    #[dynamic(default)]
+    pub icon: Option<String>,
+    pub action: KeyAssignment,
+    /// A count to show in a badge alongside the label
+    #[dynamic(default)]
+    pub badge: Option<u32>,
+}
+
+impl MenuItemDef {
+    /// Parses a JSON array of items, as accepted by `wezterm cli context-menu`
+    pub fn list_from_json_str(s: &str) -> anyhow::Result<Vec<Self>> {
+        let value: serde_json::Value = serde_json::from_str(s)?;
+        Vec::<Self>::from_dynamic(&crate::json_to_dynamic(&value), Default::default())
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+}
+
+/// A group of items added to the default context menu.
+/// Sections are placed by `priority` among the built-in sections,
+/// which are spaced 100 apart, starting with the pane actions at 100
+/// and the new tab actions at 200.
+#[derive(Debug, Clone, PartialEq, FromDynamic, ToDynamic)]
+pub struct MenuSection {
+    #[dynamic(default)]
+    pub priority: i32,
+    pub items: Vec<MenuItemDef>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, FromDynamic, ToDynamic)]
+pub struct ContextMenuConfig {
+    #[dynamic(default)]
+    pub section: Vec<MenuSection>,
+}
+
+impl ContextMenuConfig {
+    /// Returns the sections ordered by priority; sections with the
+    /// same priority keep the order in which they were defined
+    pub fn sorted_sections(&self) -> Vec<MenuSection> {
+        let mut sections = self.section.clone();
+        sections.sort_by_key(|section| section.priority);
+        sections
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, FromDynamic, ToDynamic)]
 pub enum KeyAssignment {
     SpawnTab(SpawnTabDomain),
     SpawnWindow,
+    PromptSshHost,
     ToggleFullScreen,
     ToggleAlwaysOnTop,
     ToggleAlwaysOnBottom,
     SetWindowLevel(WindowLevel),
     CopyTo(ClipboardCopyDestination),
+    CopyAsHtmlTo(ClipboardCopyDestination),
+    CopyAsHighlightedHtmlTo(ClipboardCopyDestination),
     CopyTextTo {
         text: String,
         destination: ClipboardCopyDestination,
@@ -598,6 +652,10 @@ pub enum KeyAssignment {
     ActivatePaneByIndex(usize),
     TogglePaneZoomState,
     SetPaneZoomState(bool),
+    MaximizePaneInDirection {
+        direction: Axis,
+    },
+    ApplyPaneLayout(PaneLayout),
     CloseCurrentPane {
         confirm: bool,
     },
@@ -641,6 +699,61 @@ pub enum KeyAssignment {
     OpenUri(String),
     ActivateCommandPalette,
     ShowContextMenu,
+    ShowNamedContextMenu(String),
+    ShowScrollbackFilter,
+    FocusPreviousPaneInHistory,
+    ExportEffectiveConfig,
+    SetScrollbackLines {
+        lines: usize,
+    },
+    PipeSelectionToCommand,
+    ToggleDimOnUnfocus,
+    ShowFontMetricsOverlay,
+    FlagCurrentLine,
+    ScrollToFlag(isize),
+    ToggleColumnRuler,
+    SetWindowOpacity(NotNan<f64>),
+    SnapshotPane,
+    RestorePaneSnapshot(usize),
+    ToggleSoftwareRenderer,
+    TogglePerformanceMode,
+    ToggleWindowPositionLock,
+    ScrollToSemanticZone {
+        zone_type: SemanticType,
+        amount: isize,
+    },
+    ShowGlyphAtlas,
+    CloseAllOtherPanes,
+    ShowTabGallery,
+    SpawnTabFromTemplate {
+        name: String,
+    },
+    CopyVisibleText,
+    PasteWithPrefix,
+    SetContextMenuTheme(String),
+    CompleteCommand,
+    RunHealthCheck,
+    SetPaneMouseReporting {
+        enabled: bool,
+    },
+    ActivatePaneById {
+        pane_id: usize,
+    },
+    PromptRenameTab,
+    ToggleAutoNameTab,
+    PromptRenameWorkspace,
+    PasteToAllPanes,
+    SendInputToPane {
+        pane_id: usize,
+    },
+    SwapActivePaneWithIndex(usize),
+    StartSplitDrag(usize),
+    StartReadOnlyShare,
+    StopReadOnlyShare,
+    ToggleSecureInput,
+    SetCursorBlinkRate {
+        rate_ms: u32,
+    },
     ActivateWindow(usize),
     ActivateWindowRelative(isize),
     ActivateWindowRelativeNoWrap(isize),
@@ -673,6 +786,30 @@ impl Default for SplitSize {
     }
 }
 
+/// The axis along which a pane is grown by MaximizePaneInDirection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromDynamic, ToDynamic)]
+pub enum Axis {
+    /// Grow the width of the pane
+    Horizontal,
+    /// Grow the height of the pane
+    Vertical,
+}
+
+/// The arrangement applied to the panes of a tab by ApplyPaneLayout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromDynamic, ToDynamic)]
+pub enum PaneLayout {
+    /// Spread the panes evenly from left to right
+    EvenHorizontal,
+    /// Spread the panes evenly from top to bottom
+    EvenVertical,
+    /// The active pane across the top, with the others below it
+    MainHorizontal,
+    /// The active pane on the left, with the others to its right
+    MainVertical,
+    /// Arrange the panes in a grid with roughly equal rows and columns
+    Tiled,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, FromDynamic, ToDynamic)]
 pub enum RotationDirection {
     Clockwise,
@@ -36,6 +36,7 @@ pub mod meta;
 mod scheme_data;
 mod serial;
 mod ssh;
+mod tab_template;
 mod terminal;
 mod tls;
 mod units;
@@ -56,6 +57,7 @@ pub use frontend::*;
 pub use keys::*;
 pub use serial::*;
 pub use ssh::*;
+pub use tab_template::*;
 pub use terminal::*;
 pub use tls::*;
 pub use units::*;
@@ -121,7 +123,9 @@ fn toml_to_dynamic(value: &toml::Value) -> Value {
     }
 }
 
-fn json_to_dynamic(value: &serde_json::Value) -> Value {
+/// Converts JSON to a dynamic value, so that it can be
+/// used with `FromDynamic`
+pub fn json_to_dynamic(value: &serde_json::Value) -> Value {
     match value {
         serde_json::Value::Null => Value::Null,
         serde_json::Value::Bool(b) => b.to_dynamic(),
@@ -158,6 +162,33 @@ fn json_to_dynamic(value: &serde_json::Value) -> Value {
     }
 }
 
+/// Converts a dynamic value, such as that produced by `Config::to_dynamic`,
+/// to JSON. Non-string object keys are stringified.
+pub fn dynamic_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::String(s) => serde_json::Value::String(s.to_string()),
+        Value::U64(n) => (*n).into(),
+        Value::I64(n) => (*n).into(),
+        Value::F64(n) => serde_json::Number::from_f64(n.into_inner())
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Value::Array(a) => serde_json::Value::Array(a.iter().map(dynamic_to_json).collect()),
+        Value::Object(o) => serde_json::Value::Object(
+            o.iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        Value::String(s) => s.to_string(),
+                        other => dynamic_to_json(other).to_string(),
+                    };
+                    (key, dynamic_to_json(v))
+                })
+                .collect(),
+        ),
+    }
+}
+
 pub fn build_default_schemes() -> HashMap<String, Palette> {
     let mut color_schemes = HashMap::new();
     for (scheme_name, data) in scheme_data::SCHEMES.iter() {
@@ -415,6 +446,11 @@ pub fn set_config_overrides(items: &[(String, String)]) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Returns the overrides that were passed to set_config_overrides
+pub fn get_config_overrides() -> Vec<(String, String)> {
+    CONFIG_OVERRIDES.lock().unwrap().clone()
+}
+
 pub fn is_config_overridden() -> bool {
     CONFIG_SKIP.load(Ordering::Relaxed)
         || !CONFIG_OVERRIDES.lock().unwrap().is_empty()
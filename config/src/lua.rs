@@ -1,5 +1,6 @@
 use crate::exec_domain::{ExecDomain, ValueOrFunc};
 use crate::keyassignment::KeyAssignment;
+use crate::tab_template::TabTemplate;
 use crate::{
     Config, FontAttributes, FontStretch, FontStyle, FontWeight, FreeTypeLoadTarget, RgbaColor,
     TextStyle,
@@ -353,6 +354,7 @@ end
         lua.set_named_registry_value(LUA_REGISTRY_USER_CALLBACK_COUNT, 0)?;
         wezterm_mod.set("action_callback", lua.create_function(action_callback)?)?;
         wezterm_mod.set("exec_domain", lua.create_function(exec_domain)?)?;
+        wezterm_mod.set("tab_template", lua.create_function(tab_template)?)?;
 
         wezterm_mod.set("utf16_to_utf8", lua.create_function(utf16_to_utf8)?)?;
         wezterm_mod.set("split_by_newlines", lua.create_function(split_by_newlines)?)?;
@@ -653,6 +655,18 @@ fn action_callback<'lua>(lua: &'lua Lua, callback: mlua::Function) -> mlua::Resu
     Ok(KeyAssignment::EmitEvent(user_event_id))
 }
 
+fn tab_template<'lua>(
+    lua: &'lua Lua,
+    (name, build): (String, mlua::Function),
+) -> mlua::Result<TabTemplate> {
+    let event_name = format!("tab-template-{name}");
+    register_event(lua, (event_name.clone(), build))?;
+    Ok(TabTemplate {
+        name,
+        build: event_name,
+    })
+}
+
 fn exec_domain<'lua>(
     lua: &'lua Lua,
     (name, fixup_command, label): (String, mlua::Function, Option<mlua::Value>),
@@ -0,0 +1,11 @@
+use luahelper::impl_lua_conversion_dynamic;
+use wezterm_dynamic::{FromDynamic, ToDynamic};
+
+/// A named tab layout, created by `wezterm.tab_template`
+#[derive(Debug, Clone, FromDynamic, ToDynamic)]
+pub struct TabTemplate {
+    pub name: String,
+    /// The event whose handler builds the layout
+    pub build: String,
+}
+impl_lua_conversion_dynamic!(TabTemplate);
@@ -10,6 +10,7 @@ use wezterm_term::config::BidiMode;
 pub struct TermConfig {
     config: Mutex<Option<ConfigHandle>>,
     client_palette: Mutex<Option<ColorPalette>>,
+    scrollback_lines: Mutex<Option<usize>>,
 }
 
 impl TermConfig {
@@ -17,6 +18,7 @@ impl TermConfig {
         Self {
             config: Mutex::new(None),
             client_palette: Mutex::new(None),
+            scrollback_lines: Mutex::new(None),
         }
     }
 
@@ -24,6 +26,7 @@ impl TermConfig {
         Self {
             config: Mutex::new(Some(config)),
             client_palette: Mutex::new(None),
+            scrollback_lines: Mutex::new(None),
         }
     }
 
@@ -35,6 +38,11 @@ impl TermConfig {
         self.client_palette.lock().unwrap().replace(palette);
     }
 
+    /// Use `lines` rather than the configured scrollback_lines
+    pub fn set_scrollback_lines(&self, lines: usize) {
+        self.scrollback_lines.lock().unwrap().replace(lines);
+    }
+
     fn configuration(&self) -> ConfigHandle {
         match self.config.lock().unwrap().as_ref() {
             Some(h) => h.clone(),
@@ -49,7 +57,10 @@ impl wezterm_term::TerminalConfiguration for TermConfig {
     }
 
     fn scrollback_size(&self) -> usize {
-        self.configuration().scrollback_lines
+        match *self.scrollback_lines.lock().unwrap() {
+            Some(lines) => lines,
+            None => self.configuration().scrollback_lines,
+        }
     }
 
     fn enable_csi_u_key_encoding(&self) -> bool {
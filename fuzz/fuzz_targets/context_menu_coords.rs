@@ -0,0 +1,82 @@
+//! Checks that hit testing a context menu never yields a row that
+//! isn't an item. Run it with:
+//!
+//! `cargo +nightly fuzz run context_menu_coords`
+#![no_main]
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../wezterm-gui/src/termwindow/menuhit.rs"]
+mod menuhit;
+
+use menuhit::{row_at_coords, MenuBounds, RowBounds};
+
+/// The kinds of item that a menu can hold; only the number of them
+/// matters to the hit testing, but each takes up a row
+#[derive(Arbitrary, Debug)]
+enum Item {
+    Entry,
+    Checkmark,
+    SubMenu,
+    Separator,
+    Breadcrumb,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Row {
+    x: f32,
+    y: f32,
+    height: f32,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    items: Vec<Item>,
+    menu_x: f32,
+    menu_y: f32,
+    menu_width: f32,
+    menu_height: f32,
+    scrollbar_left: Option<f32>,
+    scroll_offset: usize,
+    rows: Vec<Row>,
+    x: f32,
+    y: f32,
+}
+
+fuzz_target!(|input: Input| {
+    let num_items = input.items.len();
+    // compute only records bounds for the rows that are shown, which
+    // start at scroll_offset and end at the last item at the latest
+    let scroll_offset = input.scroll_offset % (num_items + 1);
+    let rows: Vec<RowBounds> = input
+        .rows
+        .iter()
+        .take(num_items - scroll_offset)
+        .map(|row| RowBounds {
+            x: row.x,
+            y: row.y,
+            height: row.height,
+        })
+        .collect();
+
+    let row = row_at_coords(
+        &MenuBounds {
+            x: input.menu_x,
+            y: input.menu_y,
+            width: input.menu_width,
+            height: input.menu_height,
+            scrollbar_left: input.scrollbar_left,
+            scroll_offset,
+            rows: &rows,
+        },
+        input.x,
+        input.y,
+    );
+    assert!(
+        row >= -1 && (row as i64) < num_items as i64,
+        "row {} is outside [-1, {}) for {:?}",
+        row,
+        num_items,
+        input
+    );
+});
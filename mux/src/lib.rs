@@ -4,7 +4,7 @@ use crate::ssh_agent::AgentProxy;
 use crate::tab::{SplitRequest, Tab, TabId};
 use crate::window::{Window, WindowId};
 use anyhow::{anyhow, Context, Error};
-use config::keyassignment::SpawnTabDomain;
+use config::keyassignment::{MenuItemDef, SpawnTabDomain};
 use config::{configuration, ExitBehavior, GuiPosition};
 use domain::{Domain, DomainId, DomainState, SplitSource};
 use filedescriptor::{poll, pollfd, socketpair, AsRawSocketDescriptor, FileDescriptor, POLLIN};
@@ -95,6 +95,26 @@ pub enum MuxNotification {
         old_workspace: String,
         new_workspace: String,
     },
+    /// Asks the gui to show a context menu made of `items` over `pane_id`
+    ShowContextMenu {
+        pane_id: PaneId,
+        items: Vec<MenuItemDef>,
+    },
+    /// Asks the gui window that contains `pane_id` to send the items
+    /// of the context menu for that pane, as JSON, through `reply`.
+    /// `reply` is closed without a response if no window has the pane.
+    DescribeContextMenu {
+        pane_id: PaneId,
+        reply: smol::channel::Sender<String>,
+    },
+    /// Asks the gui window for `window_id` to send the state of its
+    /// topmost modal, as JSON, through `reply`, or None if it has no
+    /// modal open. `reply` is closed without a response if there is
+    /// no such gui window.
+    DumpModal {
+        window_id: WindowId,
+        reply: smol::channel::Sender<Option<String>>,
+    },
 }
 
 static SUB_ID: AtomicUsize = AtomicUsize::new(0);
@@ -485,6 +485,10 @@ impl Pane for LocalPane {
         }
     }
 
+    fn set_retain_scrollback(&self, retain: bool) {
+        self.terminal.lock().set_retain_scrollback(retain);
+    }
+
     fn focus_changed(&self, focused: bool) {
         self.terminal.lock().focus_changed(focused);
     }
@@ -264,6 +264,10 @@ pub trait Pane: Downcast + Send + Sync {
 
     fn erase_scrollback(&self, _erase_mode: ScrollbackEraseMode) {}
 
+    /// Sets whether lines that scroll off the top of the screen are
+    /// kept in the scrollback, or discarded
+    fn set_retain_scrollback(&self, _retain: bool) {}
+
     /// Called to advise on whether this tab has focus
     fn focus_changed(&self, _focused: bool) {}
 
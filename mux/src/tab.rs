@@ -4,7 +4,7 @@ use crate::renderable::StableCursorPosition;
 use crate::{Mux, MuxNotification, WindowId};
 use bintree::PathBranch;
 use config::configuration;
-use config::keyassignment::PaneDirection;
+use config::keyassignment::{PaneDirection, PaneLayout};
 use parking_lot::Mutex;
 use rangeset::intersects_range;
 use serde::{Deserialize, Serialize};
@@ -45,6 +45,9 @@ struct TabInner {
     active: usize,
     zoomed: Option<Arc<dyn Pane>>,
     title: String,
+    /// When auto-naming is enabled, holds the title to restore
+    /// once it is disabled again
+    title_before_auto_name: Option<String>,
     recency: Recency,
 }
 
@@ -496,6 +499,89 @@ fn apply_sizes_from_splits(tree: &Tree, size: &TerminalSize) {
     }
 }
 
+/// Builds a tree that evenly distributes `items` along `direction`
+/// within `size`.  `leaf` is called to produce the subtree for each
+/// item, which allows for nesting layouts, such as rows of panes.
+fn even_layout<T>(
+    items: &[T],
+    direction: SplitDirection,
+    size: TerminalSize,
+    dims: &TerminalSize,
+    leaf: &mut dyn FnMut(&T, TerminalSize) -> Tree,
+) -> Tree {
+    if items.len() == 1 {
+        return leaf(&items[0], size);
+    }
+    // Each of the n items gets an equal share of the space that
+    // remains after accounting for the n-1 dividers between them
+    let n = items.len();
+    let along = match direction {
+        SplitDirection::Horizontal => size.cols,
+        SplitDirection::Vertical => size.rows,
+    };
+    let first = (along.saturating_sub(n - 1) / n).max(1);
+    let split = layout_split(size, direction, first, dims);
+    Tree::Node {
+        left: Box::new(leaf(&items[0], split.first)),
+        right: Box::new(even_layout(
+            &items[1..],
+            direction,
+            split.second,
+            dims,
+            leaf,
+        )),
+        data: Some(split),
+    }
+}
+
+/// Divides `size` along `direction` so that the first child has
+/// `first` cells and the second has the remainder, less the divider
+fn layout_split(
+    size: TerminalSize,
+    direction: SplitDirection,
+    first: usize,
+    dims: &TerminalSize,
+) -> SplitDirectionAndSize {
+    let sized = |rows: usize, cols: usize| TerminalSize {
+        rows,
+        cols,
+        pixel_width: cols * dims.pixel_width,
+        pixel_height: rows * dims.pixel_height,
+        dpi: dims.dpi,
+    };
+    match direction {
+        SplitDirection::Horizontal => SplitDirectionAndSize {
+            direction,
+            first: sized(size.rows, first),
+            second: sized(size.rows, size.cols.saturating_sub(first + 1)),
+        },
+        SplitDirection::Vertical => SplitDirectionAndSize {
+            direction,
+            first: sized(first, size.cols),
+            second: sized(size.rows.saturating_sub(first + 1), size.cols),
+        },
+    }
+}
+
+/// Returns true if every pane in the tree has a non-zero size
+fn layout_fits(tree: &Tree) -> bool {
+    match tree {
+        Tree::Node {
+            left,
+            right,
+            data: Some(data),
+        } => {
+            data.first.rows > 0
+                && data.first.cols > 0
+                && data.second.rows > 0
+                && data.second.cols > 0
+                && layout_fits(&*left)
+                && layout_fits(&*right)
+        }
+        _ => true,
+    }
+}
+
 fn cell_dimensions(size: &TerminalSize) -> TerminalSize {
     TerminalSize {
         rows: 1,
@@ -533,6 +619,26 @@ impl Tab {
         }
     }
 
+    /// Returns true if the title of this tab should follow the
+    /// foreground process of its active pane
+    pub fn get_auto_name(&self) -> bool {
+        self.inner.lock().title_before_auto_name.is_some()
+    }
+
+    /// Enables or disables auto-naming.  Disabling it restores the
+    /// title that the tab had before it was enabled.
+    pub fn set_auto_name(&self, auto_name: bool) {
+        let mut inner = self.inner.lock();
+        if auto_name {
+            if inner.title_before_auto_name.is_none() {
+                inner.title_before_auto_name = Some(inner.title.clone());
+            }
+        } else if let Some(title) = inner.title_before_auto_name.take() {
+            drop(inner);
+            self.set_title(&title);
+        }
+    }
+
     /// Called by the multiplexer client when building a local tab to
     /// mirror a remote tab.  The supplied `root` is the information
     /// about our counterpart in the the remote server.
@@ -646,6 +752,21 @@ impl Tab {
         self.inner.lock().adjust_pane_size(direction, amount)
     }
 
+    /// Grows the active pane to take up as much of the tab as possible
+    /// along the specified direction, by moving each enclosing split
+    /// of that direction so that the sibling keeps only a single cell.
+    /// Unlike zooming, the other panes remain visible.
+    pub fn maximize_pane_in_direction(&self, direction: SplitDirection) {
+        self.inner.lock().maximize_pane_in_direction(direction)
+    }
+
+    /// Rearranges all of the panes in the tab according to the
+    /// specified layout, replacing the existing split structure.
+    /// For the main layouts, the active pane becomes the main pane.
+    pub fn apply_pane_layout(&self, layout: PaneLayout) {
+        self.inner.lock().apply_pane_layout(layout)
+    }
+
     /// Activate an adjacent pane in the specified direction.
     /// In cases where there are multiple adjacent panes in the
     /// intended direction, we take the pane that has the largest
@@ -763,6 +884,7 @@ impl TabInner {
             active: 0,
             zoomed: None,
             title: String::new(),
+            title_before_auto_name: None,
             recency: Recency::default(),
         }
     }
@@ -1436,6 +1558,162 @@ impl TabInner {
         }
     }
 
+    fn maximize_pane_in_direction(&mut self, direction: SplitDirection) {
+        if self.zoomed.is_some() {
+            return;
+        }
+        let active_index = self.active;
+
+        // Walk up from the active leaf, recording the splits that are
+        // aligned with the desired direction as the number of steps
+        // above the leaf, and whether the active pane is in the first
+        // (left/top) side of that split.
+        let cursor = self.pane.take().unwrap().cursor();
+        let mut cursor = match cursor.go_to_nth_leaf(active_index) {
+            Ok(c) => c,
+            Err(c) => {
+                self.pane.replace(c.tree());
+                return;
+            }
+        };
+        let mut splits = vec![];
+        let mut depth = 0;
+        loop {
+            let active_is_first = cursor.is_left();
+            match cursor.go_up() {
+                Ok(mut c) => {
+                    depth += 1;
+                    if let Ok(Some(node)) = c.node_mut() {
+                        if node.direction == direction {
+                            splits.push((depth, active_is_first));
+                        }
+                    }
+                    cursor = c;
+                }
+                Err(c) => {
+                    self.pane.replace(c.tree());
+                    break;
+                }
+            }
+        }
+
+        // Adjust the outermost split first, so that the space it frees
+        // up has been cascaded down by the time we adjust the inner splits
+        for (depth, active_is_first) in splits.into_iter().rev() {
+            let cursor = self.pane.take().unwrap().cursor();
+            let mut cursor = match cursor.go_to_nth_leaf(active_index) {
+                Ok(c) => c,
+                Err(c) => {
+                    self.pane.replace(c.tree());
+                    return;
+                }
+            };
+            for _ in 0..depth {
+                cursor = match cursor.go_up() {
+                    Ok(c) => c,
+                    Err(c) => {
+                        self.pane.replace(c.tree());
+                        return;
+                    }
+                };
+            }
+            // adjust_node_at_cursor clamps the delta so that the
+            // other side of the split retains a single cell
+            let delta = if active_is_first {
+                isize::MAX
+            } else {
+                isize::MIN
+            };
+            self.adjust_node_at_cursor(&mut cursor, delta);
+            self.cascade_size_from_cursor(cursor);
+        }
+    }
+
+    fn apply_pane_layout(&mut self, layout: PaneLayout) {
+        self.set_zoomed(false);
+
+        let active = match self.get_active_pane() {
+            Some(pane) => pane,
+            None => return,
+        };
+        let mut panes: Vec<Arc<dyn Pane>> = self
+            .iter_panes_ignoring_zoom()
+            .into_iter()
+            .map(|pos| pos.pane)
+            .collect();
+        if panes.len() < 2 {
+            return;
+        }
+
+        let size = self.size;
+        let dims = cell_dimensions(&size);
+        let mut leaf = |pane: &Arc<dyn Pane>, _size: TerminalSize| Tree::Leaf(Arc::clone(pane));
+
+        let tree = match layout {
+            PaneLayout::EvenHorizontal => {
+                even_layout(&panes, SplitDirection::Horizontal, size, &dims, &mut leaf)
+            }
+            PaneLayout::EvenVertical => {
+                even_layout(&panes, SplitDirection::Vertical, size, &dims, &mut leaf)
+            }
+            PaneLayout::MainHorizontal | PaneLayout::MainVertical => {
+                // The main pane takes half of the tab, and the others
+                // share the remainder
+                let (direction, others) = match layout {
+                    PaneLayout::MainHorizontal => {
+                        (SplitDirection::Vertical, SplitDirection::Horizontal)
+                    }
+                    _ => (SplitDirection::Horizontal, SplitDirection::Vertical),
+                };
+                panes.retain(|pane| pane.pane_id() != active.pane_id());
+                let along = match direction {
+                    SplitDirection::Horizontal => size.cols,
+                    SplitDirection::Vertical => size.rows,
+                };
+                let split =
+                    layout_split(size, direction, (along.saturating_sub(1) / 2).max(1), &dims);
+                Tree::Node {
+                    left: Box::new(Tree::Leaf(Arc::clone(&active))),
+                    right: Box::new(even_layout(&panes, others, split.second, &dims, &mut leaf)),
+                    data: Some(split),
+                }
+            }
+            PaneLayout::Tiled => {
+                // Arrange the panes into rows of a roughly square grid
+                let cols = (panes.len() as f64).sqrt().ceil() as usize;
+                let rows: Vec<&[Arc<dyn Pane>]> = panes.chunks(cols).collect();
+                even_layout(
+                    &rows,
+                    SplitDirection::Vertical,
+                    size,
+                    &dims,
+                    &mut |row, size| {
+                        even_layout(row, SplitDirection::Horizontal, size, &dims, &mut leaf)
+                    },
+                )
+            }
+        };
+
+        if !layout_fits(&tree) {
+            log::error!("No space to apply {:?} layout to tab {}", layout, self.id);
+            return;
+        }
+
+        apply_sizes_from_splits(&tree, &size);
+        self.pane.replace(tree);
+
+        // The active pane is unchanged, but its index may have moved
+        if let Some(idx) = self
+            .iter_panes_ignoring_zoom()
+            .iter()
+            .position(|pos| pos.pane.pane_id() == active.pane_id())
+        {
+            self.active = idx;
+        }
+
+        Mux::try_get().map(|mux| mux.notify(MuxNotification::TabResized(self.id)));
+    }
+
     fn activate_pane_direction(&mut self, direction: PaneDirection) {
         if self.zoomed.is_some() {
             if !configuration().unzoom_on_switch_pane {
@@ -2517,6 +2795,187 @@ mod test {
         assert_eq!(600, panes[2].pixel_height);
     }
 
+    #[test]
+    fn maximize_pane() {
+        let size = TerminalSize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 800,
+            pixel_height: 600,
+            dpi: 96,
+        };
+
+        let tab = Tab::new(&size);
+        tab.assign_pane(&FakePane::new(1, size));
+
+        let horz_size = tab
+            .compute_split_size(
+                0,
+                SplitRequest {
+                    direction: SplitDirection::Horizontal,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        tab.split_and_insert(
+            0,
+            SplitRequest {
+                direction: SplitDirection::Horizontal,
+                ..Default::default()
+            },
+            FakePane::new(2, horz_size.second),
+        )
+        .unwrap();
+
+        let vert_size = tab
+            .compute_split_size(
+                0,
+                SplitRequest {
+                    direction: SplitDirection::Vertical,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        tab.split_and_insert(
+            0,
+            SplitRequest {
+                direction: SplitDirection::Vertical,
+                ..Default::default()
+            },
+            FakePane::new(3, vert_size.second),
+        )
+        .unwrap();
+
+        // Pane 3 is active, in the bottom left corner
+        let panes = tab.iter_panes();
+        assert_eq!(3, panes[1].pane.pane_id());
+        assert_eq!(true, panes[1].is_active);
+
+        tab.maximize_pane_in_direction(SplitDirection::Vertical);
+        let panes = tab.iter_panes();
+        assert_eq!(1, panes[0].height);
+        assert_eq!(39, panes[0].width);
+
+        assert_eq!(2, panes[1].top);
+        assert_eq!(22, panes[1].height);
+        assert_eq!(550, panes[1].pixel_height);
+        assert_eq!(39, panes[1].width);
+
+        assert_eq!(24, panes[2].height);
+        assert_eq!(40, panes[2].width);
+
+        tab.maximize_pane_in_direction(SplitDirection::Horizontal);
+        let panes = tab.iter_panes();
+        assert_eq!(78, panes[0].width);
+        assert_eq!(1, panes[0].height);
+
+        assert_eq!(78, panes[1].width);
+        assert_eq!(780, panes[1].pixel_width);
+        assert_eq!(22, panes[1].height);
+
+        assert_eq!(79, panes[2].left);
+        assert_eq!(1, panes[2].width);
+        assert_eq!(24, panes[2].height);
+    }
+
+    #[test]
+    fn apply_pane_layout() {
+        let size = TerminalSize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 800,
+            pixel_height: 600,
+            dpi: 96,
+        };
+
+        let tab = Tab::new(&size);
+        tab.assign_pane(&FakePane::new(1, size));
+
+        let horz_size = tab
+            .compute_split_size(
+                0,
+                SplitRequest {
+                    direction: SplitDirection::Horizontal,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        tab.split_and_insert(
+            0,
+            SplitRequest {
+                direction: SplitDirection::Horizontal,
+                ..Default::default()
+            },
+            FakePane::new(2, horz_size.second),
+        )
+        .unwrap();
+
+        let vert_size = tab
+            .compute_split_size(
+                0,
+                SplitRequest {
+                    direction: SplitDirection::Vertical,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        tab.split_and_insert(
+            0,
+            SplitRequest {
+                direction: SplitDirection::Vertical,
+                ..Default::default()
+            },
+            FakePane::new(3, vert_size.second),
+        )
+        .unwrap();
+
+        tab.apply_pane_layout(PaneLayout::EvenHorizontal);
+        let panes = tab.iter_panes();
+        assert_eq!(3, panes.len());
+        assert_eq!(1, panes[0].pane.pane_id());
+        assert_eq!(3, panes[1].pane.pane_id());
+        assert_eq!(true, panes[1].is_active);
+        assert_eq!(2, panes[2].pane.pane_id());
+        for (idx, pane) in panes.iter().enumerate() {
+            assert_eq!(idx * 27, pane.left);
+            assert_eq!(26, pane.width);
+            assert_eq!(260, pane.pixel_width);
+            assert_eq!(24, pane.height);
+        }
+
+        // The active pane becomes the main pane
+        tab.apply_pane_layout(PaneLayout::MainVertical);
+        let panes = tab.iter_panes();
+        assert_eq!(3, panes[0].pane.pane_id());
+        assert_eq!(true, panes[0].is_active);
+        assert_eq!(39, panes[0].width);
+        assert_eq!(24, panes[0].height);
+
+        assert_eq!(1, panes[1].pane.pane_id());
+        assert_eq!(40, panes[1].left);
+        assert_eq!(40, panes[1].width);
+        assert_eq!(11, panes[1].height);
+
+        assert_eq!(2, panes[2].pane.pane_id());
+        assert_eq!(12, panes[2].top);
+        assert_eq!(12, panes[2].height);
+
+        tab.apply_pane_layout(PaneLayout::Tiled);
+        let panes = tab.iter_panes();
+        assert_eq!(0, panes[0].top);
+        assert_eq!(39, panes[0].width);
+        assert_eq!(11, panes[0].height);
+
+        assert_eq!(0, panes[1].top);
+        assert_eq!(40, panes[1].left);
+        assert_eq!(40, panes[1].width);
+
+        assert_eq!(12, panes[2].top);
+        assert_eq!(0, panes[2].left);
+        assert_eq!(80, panes[2].width);
+        assert_eq!(12, panes[2].height);
+    }
+
     fn is_send_and_sync<T: Send + Sync>() -> bool {
         true
     }
@@ -35,6 +35,11 @@ pub struct Screen {
     /// that we're the primary rather than the alternate screen.
     allow_scrollback: bool,
 
+    /// When false, lines that scroll off the top are discarded as
+    /// they are on the alternate screen, rather than being added to
+    /// the scrollback
+    retain_scrollback: bool,
+
     pub(crate) keyboard_stack: Vec<KeyboardEncoding>,
 
     /// Physical, visible height of the screen (not including scrollback)
@@ -80,6 +85,7 @@ impl Screen {
             lines,
             config: Arc::clone(config),
             allow_scrollback,
+            retain_scrollback: true,
             physical_rows,
             physical_cols,
             stable_row_index_offset: 0,
@@ -652,7 +658,8 @@ impl Screen {
     ) {
         let phys_scroll = self.phys_range(scroll_region);
         let num_rows = num_rows.min(phys_scroll.end - phys_scroll.start);
-        let scrollback_ok = scroll_region.start == 0 && self.allow_scrollback;
+        let scrollback_ok =
+            scroll_region.start == 0 && self.allow_scrollback && self.retain_scrollback;
         let insert_at_end = scroll_region.end as usize == self.physical_rows;
 
         debug!(
@@ -692,11 +699,9 @@ impl Screen {
             }
         }
 
-        let remove_idx = if scroll_region.start == 0 {
-            0
-        } else {
-            phys_scroll.start
-        };
+        // Lines that go into the scrollback push out the oldest line of
+        // it, if it is full; otherwise the scrolled lines themselves go
+        let remove_idx = if scrollback_ok { 0 } else { phys_scroll.start };
 
         let default_blank = CellAttributes::blank();
         // To avoid thrashing the heap, prefer to move lines that were
@@ -759,6 +764,13 @@ impl Screen {
         }
     }
 
+    /// Sets whether lines that scroll off the top of the screen are
+    /// kept in the scrollback.  The scrollback that has already been
+    /// recorded is left as it is.
+    pub fn set_retain_scrollback(&mut self, retain: bool) {
+        self.retain_scrollback = retain;
+    }
+
     pub fn erase_scrollback(&mut self) {
         let len = self.lines.len();
         let to_clear = len - self.physical_rows;
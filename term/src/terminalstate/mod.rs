@@ -744,6 +744,12 @@ impl TerminalState {
         self.screen_mut().erase_scrollback();
     }
 
+    /// Sets whether lines that scroll off the top of the primary screen
+    /// are kept in the scrollback, or discarded.
+    pub fn set_retain_scrollback(&mut self, retain: bool) {
+        self.screen.screen.set_retain_scrollback(retain);
+    }
+
     /// Returns true if the associated application has enabled any of the
     /// supported mouse reporting modes.
     /// This is useful for the hosting GUI application to decide how best
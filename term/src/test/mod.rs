@@ -1114,6 +1114,23 @@ fn test_scrollup() {
     assert_eq!(term.screen().visible_row_to_stable_row(0), 7);
 }
 
+#[test]
+fn test_scrollup_without_retaining_scrollback() {
+    let mut term = TestTerm::new(2, 1, 4);
+    term.print("1\n2\n");
+    assert_all_contents(&term, file!(), line!(), &["1", "2", ""]);
+
+    term.set_retain_scrollback(false);
+    term.print("3\n4\n");
+    assert_all_contents(&term, file!(), line!(), &["1", "4", ""]);
+    assert_eq!(term.screen().visible_row_to_stable_row(0), 1);
+
+    term.set_retain_scrollback(true);
+    term.print("5\n");
+    assert_all_contents(&term, file!(), line!(), &["1", "4", "5", ""]);
+    assert_eq!(term.screen().visible_row_to_stable_row(0), 2);
+}
+
 #[test]
 fn test_ri() {
     let mut term = TestTerm::new(3, 1, 10);
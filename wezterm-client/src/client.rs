@@ -1389,4 +1389,11 @@ impl Client {
         GetPaneDirectionResponse
     );
     rpc!(adjust_pane_size, AdjustPaneSize, UnitResponse);
+    rpc!(show_context_menu, ShowContextMenu, UnitResponse);
+    rpc!(
+        get_context_menu_items,
+        GetContextMenuItems,
+        GetContextMenuItemsResponse
+    );
+    rpc!(dump_modal, DumpModal, DumpModalResponse);
 }
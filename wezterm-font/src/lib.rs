@@ -456,6 +456,8 @@ impl FallbackResolveInfo {
 enum Entity {
     Title,
     CommandPalette,
+    ContextMenu,
+    ContextMenuBreadcrumb,
     CharSelect,
     PaneSelect,
 }
@@ -473,6 +475,8 @@ struct FontConfigInner {
     pane_select_font: RefCell<Option<Rc<LoadedFont>>>,
     char_select_font: RefCell<Option<Rc<LoadedFont>>>,
     command_palette_font: RefCell<Option<Rc<LoadedFont>>>,
+    context_menu_font: RefCell<Option<Rc<LoadedFont>>>,
+    context_menu_breadcrumb_font: RefCell<Option<Rc<LoadedFont>>>,
     fallback_channel: RefCell<Option<Sender<FallbackResolveInfo>>>,
 }
 
@@ -494,6 +498,8 @@ impl FontConfigInner {
             pane_select_font: RefCell::new(None),
             char_select_font: RefCell::new(None),
             command_palette_font: RefCell::new(None),
+            context_menu_font: RefCell::new(None),
+            context_menu_breadcrumb_font: RefCell::new(None),
             font_scale: RefCell::new(1.0),
             dpi: RefCell::new(dpi),
             config: RefCell::new(config.clone()),
@@ -512,6 +518,8 @@ impl FontConfigInner {
         self.pane_select_font.borrow_mut().take();
         self.char_select_font.borrow_mut().take();
         self.command_palette_font.borrow_mut().take();
+        self.context_menu_font.borrow_mut().take();
+        self.context_menu_breadcrumb_font.borrow_mut().take();
         self.metrics.borrow_mut().take();
         *self.font_dirs.borrow_mut() = Arc::new(FontDatabase::with_font_dirs(config)?);
         Ok(())
@@ -598,7 +606,10 @@ impl FontConfigInner {
         entity: Entity,
     ) -> anyhow::Result<Rc<LoadedFont>> {
         let config = self.config.borrow();
-        let make_bold = entity != Entity::CommandPalette;
+        let make_bold = !matches!(
+            entity,
+            Entity::CommandPalette | Entity::ContextMenu | Entity::ContextMenuBreadcrumb
+        );
         let (sys_font, sys_size) = self.compute_title_font(&config, make_bold);
 
         let (font_size, text_style) = match entity {
@@ -607,6 +618,20 @@ impl FontConfigInner {
                 config.command_palette_font_size,
                 config.command_palette_font.as_ref(),
             ),
+            Entity::ContextMenu => (
+                config
+                    .context_menu_font_size
+                    .unwrap_or(config.command_palette_font_size),
+                config.command_palette_font.as_ref(),
+            ),
+            // The path to a sub-menu is a little smaller than its items
+            Entity::ContextMenuBreadcrumb => (
+                config
+                    .context_menu_font_size
+                    .unwrap_or(config.command_palette_font_size)
+                    * 0.8,
+                config.command_palette_font.as_ref(),
+            ),
             Entity::CharSelect => (
                 config.char_select_font_size,
                 config.char_select_font.as_ref(),
@@ -681,6 +706,34 @@ impl FontConfigInner {
         Ok(loaded)
     }
 
+    fn context_menu_font(&self, myself: &Rc<Self>) -> anyhow::Result<Rc<LoadedFont>> {
+        let mut context_menu_font = self.context_menu_font.borrow_mut();
+
+        if let Some(entry) = context_menu_font.as_ref() {
+            return Ok(Rc::clone(entry));
+        }
+
+        let loaded = self.make_entity_font_impl(myself, Entity::ContextMenu)?;
+
+        context_menu_font.replace(Rc::clone(&loaded));
+
+        Ok(loaded)
+    }
+
+    fn context_menu_breadcrumb_font(&self, myself: &Rc<Self>) -> anyhow::Result<Rc<LoadedFont>> {
+        let mut context_menu_breadcrumb_font = self.context_menu_breadcrumb_font.borrow_mut();
+
+        if let Some(entry) = context_menu_breadcrumb_font.as_ref() {
+            return Ok(Rc::clone(entry));
+        }
+
+        let loaded = self.make_entity_font_impl(myself, Entity::ContextMenuBreadcrumb)?;
+
+        context_menu_breadcrumb_font.replace(Rc::clone(&loaded));
+
+        Ok(loaded)
+    }
+
     fn char_select_font(&self, myself: &Rc<Self>) -> anyhow::Result<Rc<LoadedFont>> {
         let mut char_select_font = self.char_select_font.borrow_mut();
 
@@ -1071,6 +1124,14 @@ impl FontConfiguration {
         self.inner.command_palette_font(&self.inner)
     }
 
+    pub fn context_menu_font(&self) -> anyhow::Result<Rc<LoadedFont>> {
+        self.inner.context_menu_font(&self.inner)
+    }
+
+    pub fn context_menu_breadcrumb_font(&self) -> anyhow::Result<Rc<LoadedFont>> {
+        self.inner.context_menu_breadcrumb_font(&self.inner)
+    }
+
     pub fn pane_select_font(&self) -> anyhow::Result<Rc<LoadedFont>> {
         self.inner.pane_select_font(&self.inner)
     }
@@ -271,3 +271,10 @@ pub struct ShowKeysCommand {
     #[arg(long)]
     pub key_table: Option<String>,
 }
+
+#[derive(Debug, Parser, Clone)]
+pub struct ShowContextMenuStatsCommand {
+    /// How many of the most frequently used actions to show
+    #[arg(long, default_value = "10")]
+    pub top: usize,
+}
@@ -0,0 +1,110 @@
+// Compute shaders that produce a gaussian blurred shadow mask or
+// backdrop, and the vertex/fragment shaders that composite it into
+// the frame.
+
+struct BlurParams {
+  // The area of the mask that is covered by the shape, in texels
+  mask_min: vec2<f32>,
+  mask_max: vec2<f32>,
+  // (1, 0) for the horizontal pass, (0, 1) for the vertical pass
+  direction: vec2<i32>,
+  radius: i32,
+  sigma: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: BlurParams;
+@group(0) @binding(1) var src: texture_2d<f32>;
+@group(0) @binding(2) var dst: texture_storage_2d<rgba16float, write>;
+
+// Writes the unblurred shape: fully covered inside the mask
+// rectangle and transparent in the padding around it
+@compute @workgroup_size(8, 8)
+fn fill_mask(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(dst);
+    if (id.x >= size.x || id.y >= size.y) {
+        return;
+    }
+    let p = vec2<f32>(id.xy) + vec2<f32>(0.5);
+    let inside = all(p >= params.mask_min) && all(p < params.mask_max);
+    textureStore(dst, vec2<i32>(id.xy), vec4<f32>(select(0.0, 1.0, inside)));
+}
+
+// Copies the content that was read back from the frame, so
+// that it can be blurred in the same way as a mask
+@compute @workgroup_size(8, 8)
+fn load_backdrop(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(dst);
+    if (id.x >= size.x || id.y >= size.y) {
+        return;
+    }
+    textureStore(dst, vec2<i32>(id.xy), textureLoad(src, vec2<i32>(id.xy), 0));
+}
+
+// One dimension of the separable gaussian blur
+@compute @workgroup_size(8, 8)
+fn blur(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<i32>(textureDimensions(src));
+    let pos = vec2<i32>(id.xy);
+    if (pos.x >= size.x || pos.y >= size.y) {
+        return;
+    }
+    var sum = vec4<f32>(0.0);
+    var total = 0.0;
+    for (var i = -params.radius; i <= params.radius; i = i + 1) {
+        let weight = exp(-f32(i * i) / (2.0 * params.sigma * params.sigma));
+        let sample_pos = clamp(pos + params.direction * i, vec2<i32>(0), size - vec2<i32>(1));
+        sum = sum + textureLoad(src, sample_pos, 0) * weight;
+        total = total + weight;
+    }
+    textureStore(dst, pos, sum / total);
+}
+
+struct CompositeParams {
+  // left, top, right, bottom in clip space
+  rect: vec4<f32>,
+  // left, top, right, bottom in texture coordinates
+  tex_rect: vec4<f32>,
+  color: vec4<f32>,
+};
+
+struct CompositeOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> composite: CompositeParams;
+@group(0) @binding(1) var mask: texture_2d<f32>;
+@group(0) @binding(2) var mask_sampler: sampler;
+
+@vertex
+fn vs_composite(@builtin(vertex_index) idx: u32) -> CompositeOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+    );
+    let corner = corners[idx];
+    var out: CompositeOutput;
+    out.clip_position = vec4<f32>(
+        mix(composite.rect.x, composite.rect.z, corner.x),
+        mix(composite.rect.y, composite.rect.w, corner.y),
+        0.0,
+        1.0,
+    );
+    out.tex = mix(composite.tex_rect.xy, composite.tex_rect.zw, corner);
+    return out;
+}
+
+@fragment
+fn fs_composite(in: CompositeOutput) -> @location(0) vec4<f32> {
+    let coverage = textureSample(mask, mask_sampler, in.tex).r;
+    return vec4<f32>(composite.color.rgb, composite.color.a * coverage);
+}
+
+@fragment
+fn fs_backdrop(in: CompositeOutput) -> @location(0) vec4<f32> {
+    return textureSample(mask, mask_sampler, in.tex);
+}
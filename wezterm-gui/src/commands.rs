@@ -635,6 +635,27 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &["Edit"],
             icon: Some("md_content_copy"),
         },
+        CopyAsHtmlTo(_) => CommandDef {
+            brief: "Copy as Rich Text".into(),
+            doc: "Copies the selection, including its colors and \
+                  attributes, as HTML along with its plain text"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_content_copy"),
+        },
+        CopyAsHighlightedHtmlTo(_) => CommandDef {
+            brief: "Copy with Syntax Highlighting".into(),
+            doc: "Copies the selection as HTML, with the code \
+                  highlighted according to its language, along \
+                  with its plain text"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_content_copy"),
+        },
         CopyTextTo {
             text: _,
             destination: ClipboardCopyDestination::Clipboard,
@@ -756,6 +777,17 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &["Shell"],
             icon: Some("cod_empty_window"),
         },
+        PromptSshHost => CommandDef {
+            brief: "SSH to Host".into(),
+            doc: "Prompts for a host, completing names from \
+                  ~/.ssh/known_hosts, and opens a new tab connected \
+                  to it over ssh"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_ssh"),
+        },
         ClearScrollback(ScrollbackEraseMode::ScrollbackOnly) => CommandDef {
             brief: "Clear scrollback".into(),
             doc: "Clears any text that has scrolled out of the \
@@ -1031,6 +1063,21 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
                 icon: None,
             }
         }
+        SwapActivePaneWithIndex(n) => {
+            let n = *n;
+            let ordinal = english_ordinal(n as isize);
+            CommandDef {
+                brief: format!("Swap Active Pane with {ordinal} Pane").into(),
+                doc: format!("Swaps the positions of the active pane and the {ordinal} pane")
+                    .into(),
+                keys: vec![],
+                args: &[ArgType::ActivePane],
+                menubar: &[],
+                icon: Some("cod_arrow_swap"),
+            }
+        }
+        // Only meaningful for a split that was just right-clicked
+        StartSplitDrag(_) => return None,
         SetPaneZoomState(true) => CommandDef {
             brief: format!("Zooms the current Pane").into(),
             doc: format!(
@@ -1051,6 +1098,70 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &[],
             icon: Some("md_fullscreen"),
         },
+        MaximizePaneInDirection {
+            direction: Axis::Horizontal,
+        } => CommandDef {
+            brief: "Maximize Pane Width".into(),
+            doc: "Grows the current pane to the full width of the tab, \
+                  leaving the other panes visible"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("cod_arrow_both"),
+        },
+        MaximizePaneInDirection {
+            direction: Axis::Vertical,
+        } => CommandDef {
+            brief: "Maximize Pane Height".into(),
+            doc: "Grows the current pane to the full height of the tab, \
+                  leaving the other panes visible"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("cod_unfold"),
+        },
+        ApplyPaneLayout(PaneLayout::EvenHorizontal) => CommandDef {
+            brief: "Auto-tile Panes: Even Horizontal".into(),
+            doc: "Spreads the panes in the current tab evenly from left to right".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_layout_panel_center"),
+        },
+        ApplyPaneLayout(PaneLayout::EvenVertical) => CommandDef {
+            brief: "Auto-tile Panes: Even Vertical".into(),
+            doc: "Spreads the panes in the current tab evenly from top to bottom".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_layout_centered"),
+        },
+        ApplyPaneLayout(PaneLayout::MainHorizontal) => CommandDef {
+            brief: "Auto-tile Panes: Main Horizontal".into(),
+            doc: "Places the current pane across the top of the tab, with the other panes below it".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_layout_panel"),
+        },
+        ApplyPaneLayout(PaneLayout::MainVertical) => CommandDef {
+            brief: "Auto-tile Panes: Main Vertical".into(),
+            doc: "Places the current pane on the left of the tab, with the other panes to its right".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_layout_sidebar_left"),
+        },
+        ApplyPaneLayout(PaneLayout::Tiled) => CommandDef {
+            brief: "Auto-tile Panes: Tiled Grid".into(),
+            doc: "Arranges the panes in the current tab in a grid".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_layout"),
+        },
         EmitEvent(name) => CommandDef {
             brief: format!("Emit event `{name}`").into(),
             doc: format!(
@@ -2019,6 +2130,410 @@ pub fn derive_command_from_key_assignment(action: &KeyAssignment) -> Option<Comm
             menubar: &["Edit"],
             icon: Some("md_menu"),
         },
+        ShowNamedContextMenu(name) => CommandDef {
+            brief: format!("Show Context Menu {name}").into(),
+            doc: format!("Shows the context menu defined by context_menus.{name}").into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_menu"),
+        },
+        ShowScrollbackFilter => CommandDef {
+            brief: "Filter Scrollback".into(),
+            doc: "Shows only the lines of the scrollback that match a filter".into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("cod_filter"),
+        },
+        FocusPreviousPaneInHistory => CommandDef {
+            brief: "Focus Previous Pane".into(),
+            doc: "Activates the pane that was most recently focused \
+                  before the current one"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_history"),
+        },
+        ActivatePaneById { pane_id } => CommandDef {
+            brief: format!("Activate Pane {pane_id}").into(),
+            doc: format!("Activates pane {pane_id} and the tab that contains it").into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_terminal"),
+        },
+        ExportEffectiveConfig => CommandDef {
+            brief: "Export Effective Config".into(),
+            doc: "Writes the configuration in effect for this window, \
+                  including defaults and overrides, to a JSON file"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_settings_gear"),
+        },
+        SetScrollbackLines { lines } => CommandDef {
+            brief: format!("Set Scrollback to {lines} Lines").into(),
+            doc: format!("Keeps up to {lines} lines of scrollback for the current pane").into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_format_line_spacing"),
+        },
+        PipeSelectionToCommand => CommandDef {
+            brief: "Pipe Selection to Command".into(),
+            doc: "Prompts for a shell command, runs it with the selection \
+                  as its input and pastes its output"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("cod_terminal_bash"),
+        },
+        ToggleDimOnUnfocus => CommandDef {
+            brief: "Toggle Dim When Unfocused".into(),
+            doc: "Lowers the background opacity of this window to \
+                  dim_when_unfocused_opacity whenever it loses the focus"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_opacity"),
+        },
+        ShowFontMetricsOverlay => CommandDef {
+            brief: "Toggle Font Metrics Overlay".into(),
+            doc: "Draws the cell grid, baseline, cap height and underline \
+                  position of the current font over the terminal"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_format_font"),
+        },
+        ShowGlyphAtlas => CommandDef {
+            brief: "Show Glyph Atlas".into(),
+            doc: "Shows how much of the glyph atlas texture has been \
+                  allocated, to help diagnose glyph eviction"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_grid"),
+        },
+        FlagCurrentLine => CommandDef {
+            brief: "Flag Line".into(),
+            doc: "Flags or unflags the line under the mouse, or else the \
+                  line containing the cursor, marking it in the scrollbar"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_flag"),
+        },
+        ToggleColumnRuler => CommandDef {
+            brief: "Toggle Column Ruler".into(),
+            doc: "Adds or removes a vertical ruler at the column of the \
+                  cursor, to help with lining up text"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_ruler"),
+        },
+        ScrollToFlag(n) => {
+            let (direction, amount) = if *n < 0 { ("up", -n) } else { ("down", *n) };
+            let ordinal = english_ordinal(amount);
+            CommandDef {
+                brief: if *n == -1 {
+                    "Previous Flag".into()
+                } else if *n == 1 {
+                    "Next Flag".into()
+                } else {
+                    format!("Scroll {direction} {amount} flag(s)").into()
+                },
+                doc: format!(
+                    "Scrolls the viewport {direction} to the \
+                     {ordinal} flagged line in that direction"
+                )
+                .into(),
+                keys: vec![],
+                args: &[ArgType::ActivePane],
+                menubar: &[],
+                icon: Some("md_flag"),
+            }
+        }
+        PromptRenameTab => CommandDef {
+            brief: "Rename Tab".into(),
+            doc: "Prompts for a new title for the current tab".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_edit"),
+        },
+        PromptRenameWorkspace => CommandDef {
+            brief: "Rename Workspace".into(),
+            doc: "Prompts for a new name for the workspace of the current window".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_edit"),
+        },
+        ToggleAutoNameTab => CommandDef {
+            brief: "Toggle Auto-name Tab".into(),
+            doc: "Toggles whether the title of the current tab follows \
+                  the foreground process of its active pane"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_symbol_key"),
+        },
+        PasteToAllPanes => CommandDef {
+            brief: "Paste to All Panes".into(),
+            doc: "Pastes the clipboard into every pane in the current tab".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_broadcast"),
+        },
+        SendInputToPane { pane_id } => CommandDef {
+            brief: format!("Send Input To Pane {pane_id}").into(),
+            doc: format!("Prompts for a line of text and sends it to pane {pane_id}").into(),
+            keys: vec![],
+            args: &[ArgType::ActiveTab],
+            menubar: &[],
+            icon: Some("cod_send"),
+        },
+        StartReadOnlyShare => CommandDef {
+            brief: "Share Window Locally (read-only)".into(),
+            doc: "Starts a mux server on a local socket that shares this window \
+                  read-only with other sessions on this machine, and shows how \
+                  to connect to it"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_live_share"),
+        },
+        StopReadOnlyShare => CommandDef {
+            brief: "Stop Sharing Window".into(),
+            doc: "Stops the read-only share of this window and disconnects its clients".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_debug_stop"),
+        },
+        ToggleSecureInput => CommandDef {
+            brief: "Toggle Secure Input Mode".into(),
+            doc: "Blocks clipboard access, keeps the output out of the \
+                  scrollback and, on macOS, stops other applications \
+                  from observing keyboard input, for entering passwords"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_lock"),
+        },
+        SetWindowOpacity(opacity) => CommandDef {
+            brief: format!("Set Window Opacity to {:.0}%", **opacity * 100.).into(),
+            doc: format!(
+                "Overrides window_background_opacity for this window with {opacity}"
+            )
+            .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_opacity"),
+        },
+        SnapshotPane => CommandDef {
+            brief: "Snapshot Pane".into(),
+            doc: "Keeps a copy of the visible content of the current pane \
+                  so that it can be shown again later"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_camera"),
+        },
+        RestorePaneSnapshot(idx) => CommandDef {
+            brief: format!("Restore Pane Snapshot {idx}").into(),
+            doc: format!("Shows pane snapshot {idx} in a new tab").into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_camera"),
+        },
+        TogglePerformanceMode => CommandDef {
+            brief: "Toggle Performance Mode".into(),
+            doc: "Turns off animations, transparency and shadows and caps \
+                  the frame rate at performance_mode_max_fps for this window"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_speedometer"),
+        },
+        ToggleWindowPositionLock => CommandDef {
+            brief: "Toggle Window Position Lock".into(),
+            doc: "Prevents or allows moving this window by dragging its \
+                  title bar or tab bar, or with window:set_position"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_lock"),
+        },
+        ToggleSoftwareRenderer => CommandDef {
+            brief: "Toggle Software Renderer".into(),
+            doc: "Switches front_end to or from Software for new windows, \
+                  until toggled back, and opens one that uses it"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("cod_chip"),
+        },
+        SetPaneMouseReporting { enabled: true } => CommandDef {
+            brief: "Enable Mouse Reporting for the current pane".into(),
+            doc: "Lets the application in the current pane receive mouse \
+                  events again, if it has asked for them"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_mouse"),
+        },
+        SetPaneMouseReporting { enabled: false } => CommandDef {
+            brief: "Disable Mouse Reporting for the current pane".into(),
+            doc: "Handles the mouse in wezterm, for selection and the like, \
+                  even when the application in the current pane has asked \
+                  for mouse events"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_mouse_off"),
+        },
+        RunHealthCheck => CommandDef {
+            brief: "Run Health Check".into(),
+            doc: "Checks the fonts, GPU, configuration and multiplexer \
+                  domains, and shows the results"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &["Help"],
+            icon: Some("md_stethoscope"),
+        },
+        CompleteCommand => CommandDef {
+            brief: "Complete Command".into(),
+            doc: "Sends a tab to the shell to complete the command at the \
+                  prompt, and offers any candidates that it lists in a menu"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_auto_fix"),
+        },
+        SetContextMenuTheme(name) => CommandDef {
+            brief: format!("Set Context Menu Theme to {name}").into(),
+            doc: format!("Switches the colors of the context menu to the {name} theme").into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_palette"),
+        },
+        PasteWithPrefix => CommandDef {
+            brief: "Paste with Prefix".into(),
+            doc: "Prompts for a prefix, then pastes the clipboard with \
+                  that prefix added to the start of each line"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_content_paste"),
+        },
+        CopyVisibleText => CommandDef {
+            brief: "Copy Visible Text".into(),
+            doc: "Copies the text that is visible in the viewport, \
+                  without the scrollback, to the clipboard"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_content_copy"),
+        },
+        SpawnTabFromTemplate { name } => CommandDef {
+            brief: format!("New Tab from Template {name}").into(),
+            doc: format!("Builds the {name} layout from tab_templates").into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("cod_layout"),
+        },
+        ShowTabGallery => CommandDef {
+            brief: "Tab Gallery".into(),
+            doc: "Shows a thumbnail of each tab in this window, \
+                  and switches to the one that is picked"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_view_grid_outline"),
+        },
+        CloseAllOtherPanes => CommandDef {
+            brief: "Close All Other Panes".into(),
+            doc: "After confirmation, closes every pane in the current tab \
+                  except for the active one"
+                .into(),
+            keys: vec![],
+            args: &[ArgType::ActivePane],
+            menubar: &[],
+            icon: Some("md_close_box_multiple_outline"),
+        },
+        ScrollToSemanticZone { zone_type, amount } => {
+            let (direction, count) = if *amount < 0 {
+                ("up", -amount)
+            } else {
+                ("down", *amount)
+            };
+            let ordinal = english_ordinal(count);
+            CommandDef {
+                brief: if *amount == -1 {
+                    format!("Previous {zone_type:?}").into()
+                } else if *amount == 1 {
+                    format!("Next {zone_type:?}").into()
+                } else {
+                    format!("Scroll {direction} {count} {zone_type:?} zone(s)").into()
+                },
+                doc: format!(
+                    "Scrolls the viewport {direction} to the start of the \
+                     {ordinal} {zone_type:?} zone in that direction"
+                )
+                .into(),
+                keys: vec![],
+                args: &[ArgType::ActivePane],
+                menubar: &[],
+                icon: Some("md_format_vertical_align_center"),
+            }
+        }
+        SetCursorBlinkRate { rate_ms: 0 } => CommandDef {
+            brief: "Disable Cursor Blinking".into(),
+            doc: "Stops the cursor from blinking in this window".into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_cursor_text"),
+        },
+        SetCursorBlinkRate { rate_ms } => CommandDef {
+            brief: format!("Set Cursor Blink Rate to {rate_ms}ms").into(),
+            doc: format!("Overrides cursor_blink_rate for this window with {rate_ms}ms").into(),
+            keys: vec![],
+            args: &[ArgType::ActiveWindow],
+            menubar: &[],
+            icon: Some("md_cursor_text"),
+        },
     })
 }
 
@@ -94,6 +94,11 @@ impl GuiFrontEnd {
                 MuxNotification::WindowInvalidated(_) => {}
                 MuxNotification::PaneOutput(_) => {}
                 MuxNotification::PaneAdded(_) => {}
+                MuxNotification::ShowContextMenu { .. }
+                | MuxNotification::DescribeContextMenu { .. }
+                | MuxNotification::DumpModal { .. } => {
+                    // Handled by the TermWindow that contains the pane
+                }
                 MuxNotification::Alert {
                     pane_id,
                     alert:
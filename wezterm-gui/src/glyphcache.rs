@@ -625,6 +625,21 @@ impl GlyphCache {
 }
 
 impl GlyphCache {
+    /// Returns the atlas coordinates of every sprite that is currently
+    /// held by the cache, for visualizing how full the atlas is
+    pub fn sprite_coords(&self) -> Vec<Rect> {
+        self.glyph_cache
+            .values()
+            .filter_map(|glyph| glyph.texture.as_ref())
+            .chain(self.frame_cache.values())
+            .chain(self.line_glyphs.values())
+            .chain(self.block_glyphs.values())
+            .chain(self.cursor_glyphs.values())
+            .chain(self.color.values())
+            .map(|sprite| sprite.coords)
+            .collect()
+    }
+
     /// Resolve a glyph from the cache, rendering the glyph on-demand if
     /// the cache doesn't already hold the desired glyph.
     pub fn cached_glyph(
@@ -41,6 +41,8 @@ mod download;
 mod frontend;
 mod glyphcache;
 mod inputmap;
+mod menustats;
+mod menutheme;
 mod overlay;
 mod quad;
 mod renderstate;
@@ -53,6 +55,7 @@ mod spawn;
 mod stats;
 mod tabbar;
 mod termwindow;
+mod unicode_blocks;
 mod unicode_names;
 mod uniforms;
 mod update;
@@ -131,6 +134,12 @@ enum SubCommand {
 
     #[command(name = "show-keys", about = "Show key assignments")]
     ShowKeys(ShowKeysCommand),
+
+    #[command(
+        name = "show-context-menu-stats",
+        about = "Show the actions most often used from the context menu"
+    )]
+    ShowContextMenuStats(ShowContextMenuStatsCommand),
 }
 
 async fn async_run_ssh(opts: SshCommand) -> anyhow::Result<()> {
@@ -1274,5 +1283,6 @@ fn run() -> anyhow::Result<()> {
         ),
         SubCommand::LsFonts(cmd) => run_ls_fonts(config, &cmd),
         SubCommand::ShowKeys(cmd) => run_show_keys(config, &cmd),
+        SubCommand::ShowContextMenuStats(cmd) => menustats::show_context_menu_stats(cmd.top),
     }
 }
@@ -0,0 +1,97 @@
+//! Keeps a local count of the actions that were activated through
+//! the context menu, when `context_menu_usage_stats` is enabled.
+//! Nothing is sent anywhere; the counts are only shown by
+//! `wezterm show-context-menu-stats`.
+use anyhow::Context;
+use config::keyassignment::KeyAssignment;
+use rusqlite::Connection;
+use std::path::PathBuf;
+use tabout::{tabulate_output, Alignment, Column};
+
+fn db_path() -> PathBuf {
+    config::DATA_DIR.join("context-menu-stats.sqlite")
+}
+
+fn open_db() -> anyhow::Result<Connection> {
+    let path = db_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+    }
+    let conn = Connection::open(&path).with_context(|| format!("opening {}", path.display()))?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS context_menu_actions (
+            action TEXT PRIMARY KEY,
+            count INTEGER NOT NULL
+        )",
+        (),
+    )?;
+    Ok(conn)
+}
+
+/// Returns just the variant name of the assignment, so that
+/// eg: all of the SplitPane actions are counted together
+fn action_name(assignment: &KeyAssignment) -> String {
+    let debug = format!("{assignment:?}");
+    match debug.find(|c: char| !c.is_alphanumeric()) {
+        Some(end) => debug[..end].to_string(),
+        None => debug,
+    }
+}
+
+/// Increments the count for the specified action in the background
+pub fn record_context_menu_action(assignment: &KeyAssignment) {
+    let action = action_name(assignment);
+    std::thread::spawn(move || {
+        let result = open_db().and_then(|conn| {
+            conn.execute(
+                "INSERT INTO context_menu_actions (action, count) VALUES (?1, 1)
+                 ON CONFLICT(action) DO UPDATE SET count = count + 1",
+                (&action,),
+            )?;
+            Ok(())
+        });
+        if let Err(err) = result {
+            log::warn!("Failed to record context menu usage for {action}: {err:#}");
+        }
+    });
+}
+
+/// Prints the `top` most frequently used context menu actions
+pub fn show_context_menu_stats(top: usize) -> anyhow::Result<()> {
+    let conn = open_db()?;
+    let mut stmt = conn.prepare(
+        "SELECT action, count FROM context_menu_actions
+         ORDER BY count DESC, action LIMIT ?1",
+    )?;
+    let data = stmt
+        .query_map((top as i64,), |row| {
+            Ok(vec![
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?.to_string(),
+            ])
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if data.is_empty() {
+        println!(
+            "No context menu usage has been recorded in {}.\n\
+             Set `config.context_menu_usage_stats = true` to enable it.",
+            db_path().display()
+        );
+        return Ok(());
+    }
+
+    let cols = vec![
+        Column {
+            name: "ACTION".to_string(),
+            alignment: Alignment::Left,
+        },
+        Column {
+            name: "COUNT".to_string(),
+            alignment: Alignment::Right,
+        },
+    ];
+    tabulate_output(&cols, &data, &mut std::io::stdout().lock())?;
+    Ok(())
+}
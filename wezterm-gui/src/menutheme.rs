@@ -0,0 +1,143 @@
+//! Color presets for the context menu, picked with SetContextMenuTheme.
+//! The choice is saved to `menu_theme.toml` in the config directory,
+//! so it applies to every window and is kept across restarts.
+use anyhow::Context;
+use config::{ConfigHandle, RgbaColor};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// The themes offered by the Menu Theme sub-menu, in menu order.
+/// "Default" uses the colors from the config.
+pub const THEME_NAMES: &[&str] = &["Default", "Dark", "Light", "High Contrast", "Minimal"];
+
+#[derive(Debug, Clone, Copy)]
+pub struct ContextMenuTheme {
+    pub bg: RgbaColor,
+    pub fg: RgbaColor,
+    pub border: RgbaColor,
+    /// Whether the menu casts a drop shadow
+    pub shadow: bool,
+}
+
+impl ContextMenuTheme {
+    /// The context_menu_*_color values, which otherwise
+    /// follow the command palette colors
+    fn from_config(config: &ConfigHandle) -> Self {
+        let bg = config
+            .context_menu_bg_color
+            .unwrap_or(config.command_palette_bg_color);
+        Self {
+            bg,
+            fg: config
+                .context_menu_fg_color
+                .unwrap_or(config.command_palette_fg_color),
+            border: config.context_menu_border_color.unwrap_or(bg),
+            shadow: true,
+        }
+    }
+}
+
+static PRESETS: LazyLock<HashMap<&'static str, ContextMenuTheme>> = LazyLock::new(|| {
+    let mut presets = HashMap::new();
+    presets.insert(
+        "Dark",
+        ContextMenuTheme {
+            bg: (0x1e, 0x1e, 0x1e).into(),
+            fg: (0xd4, 0xd4, 0xd4).into(),
+            border: (0x45, 0x45, 0x45).into(),
+            shadow: true,
+        },
+    );
+    presets.insert(
+        "Light",
+        ContextMenuTheme {
+            bg: (0xf3, 0xf3, 0xf3).into(),
+            fg: (0x1e, 0x1e, 0x1e).into(),
+            border: (0xc8, 0xc8, 0xc8).into(),
+            shadow: true,
+        },
+    );
+    presets.insert(
+        "High Contrast",
+        ContextMenuTheme {
+            bg: (0x00, 0x00, 0x00).into(),
+            fg: (0xff, 0xff, 0xff).into(),
+            border: (0xff, 0xff, 0x00).into(),
+            shadow: false,
+        },
+    );
+    presets.insert(
+        "Minimal",
+        ContextMenuTheme {
+            bg: (0x2b, 0x2b, 0x2b).into(),
+            fg: (0xbb, 0xbb, 0xbb).into(),
+            border: (0x2b, 0x2b, 0x2b).into(),
+            shadow: false,
+        },
+    );
+    presets
+});
+
+static CURRENT_THEME: LazyLock<Mutex<String>> = LazyLock::new(|| {
+    Mutex::new(load_theme_name().unwrap_or_else(|err| {
+        log::warn!("Failed to load the context menu theme: {err:#}");
+        "Default".to_string()
+    }))
+});
+
+#[derive(Serialize, Deserialize)]
+struct ThemeFile {
+    theme: String,
+}
+
+fn theme_file_path() -> PathBuf {
+    config::CONFIG_DIRS[0].join("menu_theme.toml")
+}
+
+fn load_theme_name() -> anyhow::Result<String> {
+    let path = theme_file_path();
+    let data = match std::fs::read_to_string(&path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok("Default".into()),
+        Err(err) => return Err(err).with_context(|| format!("reading {}", path.display())),
+    };
+    let file: ThemeFile =
+        toml::from_str(&data).with_context(|| format!("parsing {}", path.display()))?;
+    Ok(file.theme)
+}
+
+pub fn current_theme_name() -> String {
+    CURRENT_THEME.lock().clone()
+}
+
+/// Returns the colors to use for the context menu
+pub fn current_theme(config: &ConfigHandle) -> ContextMenuTheme {
+    let name = CURRENT_THEME.lock();
+    PRESETS
+        .get(name.as_str())
+        .copied()
+        .unwrap_or_else(|| ContextMenuTheme::from_config(config))
+}
+
+/// Switches to the named theme and saves the choice
+pub fn set_theme(name: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        THEME_NAMES.contains(&name),
+        "{name} is not a context menu theme; choose one of {}",
+        THEME_NAMES.join(", ")
+    );
+    *CURRENT_THEME.lock() = name.to_string();
+
+    let path = theme_file_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+    }
+    let data = toml::to_string(&ThemeFile {
+        theme: name.to_string(),
+    })?;
+    std::fs::write(&path, data).with_context(|| format!("writing {}", path.display()))
+}
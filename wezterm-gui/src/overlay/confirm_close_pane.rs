@@ -28,6 +28,35 @@ pub fn confirm_close_pane(
     Ok(())
 }
 
+pub fn confirm_close_other_panes(
+    pane_id: PaneId,
+    others: Vec<PaneId>,
+    mut term: TermWizTerminal,
+    mux_window_id: WindowId,
+    window: ::window::Window,
+) -> anyhow::Result<()> {
+    let question = match others.len() {
+        1 => "🛑 Really kill the other pane in this tab?".to_string(),
+        n => format!("🛑 Really kill the {n} other panes in this tab?"),
+    };
+    if confirm::run_confirmation(&question, &mut term)? {
+        promise::spawn::spawn_into_main_thread(async move {
+            let mux = Mux::get();
+            let tab = match mux.get_active_tab_for_window(mux_window_id) {
+                Some(tab) => tab,
+                None => return,
+            };
+            for pane_id in others {
+                tab.kill_pane(pane_id);
+            }
+        })
+        .detach();
+    }
+    TermWindow::schedule_cancel_overlay_for_pane(window, pane_id);
+
+    Ok(())
+}
+
 pub fn confirm_close_tab(
     tab_id: TabId,
     mut term: TermWizTerminal,
@@ -0,0 +1,234 @@
+use mux::termwiztermtab::TermWizTerminal;
+use regex::{Regex, RegexBuilder};
+use termwiz::cell::{AttributeChange, CellAttributes};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent};
+use termwiz::surface::{Change, Line, Position, SEQ_ZERO};
+use termwiz::terminal::Terminal;
+use termwiz_funcs::truncate_right;
+
+/// Shows only those of `lines` that match a filter that is typed
+/// into the first row of the overlay, in the manner of grep
+struct ScrollbackFilter {
+    lines: Vec<Line>,
+    /// The text of each line, used for literal matching
+    lowercase_text: Vec<String>,
+    filter: String,
+    use_regex: bool,
+    error: Option<String>,
+    /// Indices into `lines` of the lines that match the filter
+    matches: Vec<usize>,
+    /// How many matches are hidden below the bottom of the view
+    scroll_back: usize,
+    max_rows: usize,
+}
+
+impl ScrollbackFilter {
+    fn new(lines: Vec<Line>) -> Self {
+        let lowercase_text = lines
+            .iter()
+            .map(|line| line.as_str().to_lowercase())
+            .collect();
+        let mut filter = Self {
+            lines,
+            lowercase_text,
+            filter: String::new(),
+            use_regex: false,
+            error: None,
+            matches: vec![],
+            scroll_back: 0,
+            max_rows: 0,
+        };
+        filter.update_filter();
+        filter
+    }
+
+    fn update_filter(&mut self) {
+        self.scroll_back = 0;
+        self.error = None;
+
+        if self.use_regex && !self.filter.is_empty() {
+            match RegexBuilder::new(&self.filter)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(re) => self.update_regex_filter(&re),
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                    self.matches.clear();
+                }
+            }
+            return;
+        }
+
+        let filter = self.filter.to_lowercase();
+        self.matches = self
+            .lowercase_text
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| text.contains(&filter))
+            .map(|(idx, _)| idx)
+            .collect();
+    }
+
+    fn update_regex_filter(&mut self, re: &Regex) {
+        self.matches = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| re.is_match(&line.as_str()))
+            .map(|(idx, _)| idx)
+            .collect();
+    }
+
+    fn max_scroll_back(&self) -> usize {
+        self.matches.len().saturating_sub(self.max_rows)
+    }
+
+    fn scroll_up(&mut self, amount: usize) {
+        self.scroll_back = (self.scroll_back + amount).min(self.max_scroll_back());
+    }
+
+    fn scroll_down(&mut self, amount: usize) {
+        self.scroll_back = self.scroll_back.saturating_sub(amount);
+    }
+
+    fn render(&mut self, term: &mut TermWizTerminal) -> termwiz::Result<()> {
+        let size = term.get_screen_size()?;
+        self.max_rows = size.rows.saturating_sub(1);
+        self.scroll_back = self.scroll_back.min(self.max_scroll_back());
+
+        let mode = if self.use_regex { "Regex" } else { "Literal" };
+        let status = match &self.error {
+            Some(err) => format!("invalid regex: {err}"),
+            None => format!("{} of {} lines", self.matches.len(), self.lines.len()),
+        };
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(0),
+            },
+            AttributeChange::Reverse(true).into(),
+            Change::Text(truncate_right(
+                &format!(
+                    "{mode} filter: {}  ({status}, CTRL-R toggles regex, ESC closes)",
+                    self.filter
+                ),
+                size.cols,
+            )),
+            Change::ClearToEndOfLine(ColorAttribute::Default),
+            Change::AllAttributes(CellAttributes::default()),
+        ];
+
+        let end = self.matches.len() - self.scroll_back;
+        let start = end.saturating_sub(self.max_rows);
+        for (row, &line_idx) in self.matches[start..end].iter().enumerate() {
+            let mut line = self.lines[line_idx].clone();
+            if line.len() > size.cols {
+                line.resize(size.cols, SEQ_ZERO);
+            }
+            changes.push(Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(row + 1),
+            });
+            let attr = CellAttributes::default();
+            changes.append(&mut line.changes(&attr));
+            changes.push(Change::AllAttributes(attr));
+        }
+
+        changes.push(Change::CursorPosition {
+            x: Position::Absolute(
+                (mode.len() + " filter: ".len() + self.filter.len()).min(size.cols),
+            ),
+            y: Position::Absolute(0),
+        });
+
+        term.render(&changes)
+    }
+
+    fn run_loop(&mut self, term: &mut TermWizTerminal) -> anyhow::Result<()> {
+        self.render(term)?;
+        while let Ok(Some(event)) = term.poll_input(None) {
+            match event {
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('G' | 'C'),
+                    modifiers: Modifiers::CTRL,
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::Escape,
+                    ..
+                }) => {
+                    break;
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('R'),
+                    modifiers: Modifiers::CTRL,
+                }) => {
+                    self.use_regex = !self.use_regex;
+                    self.update_filter();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('U'),
+                    modifiers: Modifiers::CTRL,
+                }) => {
+                    self.filter.clear();
+                    self.update_filter();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Backspace,
+                    ..
+                }) => {
+                    if self.filter.pop().is_some() {
+                        self.update_filter();
+                    }
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char(c),
+                    modifiers: Modifiers::NONE | Modifiers::SHIFT,
+                }) => {
+                    self.filter.push(c);
+                    self.update_filter();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::UpArrow,
+                    ..
+                }) => self.scroll_up(1),
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::DownArrow,
+                    ..
+                }) => self.scroll_down(1),
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::PageUp,
+                    ..
+                }) => self.scroll_up(self.max_rows),
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::PageDown,
+                    ..
+                }) => self.scroll_down(self.max_rows),
+                InputEvent::Mouse(MouseEvent { mouse_buttons, .. })
+                    if mouse_buttons.contains(MouseButtons::VERT_WHEEL) =>
+                {
+                    if mouse_buttons.contains(MouseButtons::WHEEL_POSITIVE) {
+                        self.scroll_up(1);
+                    } else {
+                        self.scroll_down(1);
+                    }
+                }
+                _ => continue,
+            }
+            self.render(term)?;
+        }
+        Ok(())
+    }
+}
+
+/// Displays the lines that match a filter that the user types in.
+/// The lines themselves are not modified, so closing the overlay
+/// returns to the full scrollback of the pane.
+pub fn show_scrollback_filter(mut term: TermWizTerminal, lines: Vec<Line>) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+    let mut filter = ScrollbackFilter::new(lines);
+    filter.run_loop(&mut term)
+}
@@ -0,0 +1,113 @@
+use mux::termwiztermtab::TermWizTerminal;
+use termwiz::cell::{AttributeChange, CellAttributes};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers};
+use termwiz::surface::{Change, Position};
+use termwiz::terminal::Terminal;
+use termwiz_funcs::truncate_right;
+use window::Rect;
+
+/// Shades, from empty to full, used for the allocated fraction of each cell
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Divides the `side` x `side` atlas into a grid of `cols` x `rows`
+/// and returns the fraction of each grid cell that is covered by `sprites`
+fn coverage(side: usize, sprites: &[Rect], cols: usize, rows: usize) -> Vec<Vec<f32>> {
+    let mut grid = vec![vec![0f32; cols]; rows];
+    let cell_width = side as f32 / cols as f32;
+    let cell_height = side as f32 / rows as f32;
+
+    for sprite in sprites {
+        let (x0, y0) = (sprite.min_x() as f32, sprite.min_y() as f32);
+        let (x1, y1) = (sprite.max_x() as f32, sprite.max_y() as f32);
+        let first_col = (x0 / cell_width) as usize;
+        let last_col = ((x1 / cell_width).ceil() as usize).min(cols);
+        let first_row = (y0 / cell_height) as usize;
+        let last_row = ((y1 / cell_height).ceil() as usize).min(rows);
+
+        for row in first_row..last_row {
+            let top = row as f32 * cell_height;
+            let height = y1.min(top + cell_height) - y0.max(top);
+            for col in first_col..last_col {
+                let left = col as f32 * cell_width;
+                let width = x1.min(left + cell_width) - x0.max(left);
+                grid[row][col] += (width * height).max(0.) / (cell_width * cell_height);
+            }
+        }
+    }
+
+    grid
+}
+
+/// Draws a map of the glyph atlas, with each cell shaded according
+/// to how much of that region of the texture has been allocated.
+/// The map stays on screen until the user presses ESC or `q`.
+pub fn show_glyph_atlas(
+    mut term: TermWizTerminal,
+    side: usize,
+    sprites: Vec<Rect>,
+) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+
+    let allocated: isize = sprites.iter().map(|sprite| sprite.area()).sum();
+    let percent = allocated as f32 * 100. / (side * side).max(1) as f32;
+
+    let render = |term: &mut TermWizTerminal| -> anyhow::Result<()> {
+        let size = term.get_screen_size()?;
+        let rows = size.rows.saturating_sub(1).max(1);
+        let cols = size.cols.max(1);
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(0),
+            },
+            AttributeChange::Reverse(true).into(),
+            Change::Text(truncate_right(
+                &format!(
+                    "Glyph atlas {side}x{side}: {} sprites, {percent:.1}% allocated (ESC closes)",
+                    sprites.len()
+                ),
+                size.cols,
+            )),
+            Change::ClearToEndOfLine(ColorAttribute::Default),
+            Change::AllAttributes(CellAttributes::default()),
+        ];
+
+        for (row, cells) in coverage(side, &sprites, cols, rows).iter().enumerate() {
+            changes.push(Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(row + 1),
+            });
+            let text: String = cells
+                .iter()
+                .map(|&fraction| {
+                    let idx = (fraction.min(1.) * (SHADES.len() - 1) as f32).round() as usize;
+                    SHADES[idx]
+                })
+                .collect();
+            changes.push(Change::Text(text));
+        }
+
+        term.render(&changes)?;
+        Ok(())
+    };
+
+    render(&mut term)?;
+    while let Ok(Some(event)) = term.poll_input(None) {
+        match event {
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Escape,
+                ..
+            })
+            | InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::NONE,
+            }) => break,
+            InputEvent::Resized { .. } => render(&mut term)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use mux::termwiztermtab::TermWizTerminal;
+use termwiz::cell::{AttributeChange, CellAttributes, Intensity};
+use termwiz::color::{AnsiColor, ColorAttribute};
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers};
+use termwiz::surface::{Change, Position};
+use termwiz::terminal::Terminal;
+use termwiz_funcs::truncate_right;
+
+/// The outcome of one of the checks made by RunHealthCheck
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// Lists the results of the health check, and keeps them on
+/// screen until the user presses ESC or `q`
+pub fn show_health_check(
+    mut term: TermWizTerminal,
+    checks: Vec<HealthCheck>,
+) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+
+    let render = |term: &mut TermWizTerminal| -> anyhow::Result<()> {
+        let size = term.get_screen_size()?;
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(0),
+            },
+            AttributeChange::Intensity(Intensity::Bold).into(),
+            Change::Text(truncate_right(
+                "WezTerm Health Check (ESC or q closes)",
+                size.cols,
+            )),
+            Change::AllAttributes(CellAttributes::default()),
+        ];
+
+        let name_width = checks
+            .iter()
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or(0);
+        for (row, check) in checks.iter().enumerate() {
+            changes.push(Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(row + 2),
+            });
+            let (mark, color) = if check.ok {
+                ("✓", AnsiColor::Green)
+            } else {
+                ("✗", AnsiColor::Red)
+            };
+            changes.push(AttributeChange::Foreground(color.into()).into());
+            changes.push(Change::Text(format!("{mark} ")));
+            changes.push(Change::AllAttributes(CellAttributes::default()));
+            changes.push(Change::Text(truncate_right(
+                &format!("{:name_width$}  {}", check.name, check.detail),
+                size.cols.saturating_sub(2),
+            )));
+        }
+        term.render(&changes)?;
+        Ok(())
+    };
+
+    render(&mut term)?;
+    while let Ok(Some(event)) = term.poll_input(None) {
+        match event {
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Escape,
+                ..
+            })
+            | InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::NONE,
+            }) => break,
+            InputEvent::Resized { .. } => render(&mut term)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
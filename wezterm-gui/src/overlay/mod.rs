@@ -10,13 +10,19 @@ pub mod confirm;
 pub mod confirm_close_pane;
 pub mod copy;
 pub mod debug;
+pub mod filter;
+pub mod glyph_atlas;
+pub mod health_check;
 pub mod launcher;
 pub mod prompt;
 pub mod quickselect;
 pub mod selector;
+pub mod snapshot;
+pub mod tab_gallery;
 
 pub use confirm_close_pane::{
-    confirm_close_pane, confirm_close_tab, confirm_close_window, confirm_quit_program,
+    confirm_close_other_panes, confirm_close_pane, confirm_close_tab, confirm_close_window,
+    confirm_quit_program,
 };
 pub use copy::{CopyModeParams, CopyOverlay};
 pub use debug::show_debug_overlay;
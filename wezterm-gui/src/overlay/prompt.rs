@@ -10,12 +10,15 @@ use termwiz::terminal::Terminal;
 
 struct PromptHost {
     history: BasicHistory,
+    /// Words offered for tab completion
+    completions: Vec<String>,
 }
 
 impl PromptHost {
     fn new() -> Self {
         Self {
             history: BasicHistory::default(),
+            completions: vec![],
         }
     }
 }
@@ -25,6 +28,24 @@ impl LineEditorHost for PromptHost {
         &mut self.history
     }
 
+    /// Completes the text after the last `@` (or the whole line),
+    /// so that `user@` can be typed ahead of a host name
+    fn complete(&self, line: &str, cursor_position: usize) -> Vec<CompletionCandidate> {
+        let start = line[..cursor_position]
+            .rfind('@')
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        let word = &line[start..cursor_position];
+        self.completions
+            .iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .map(|candidate| CompletionCandidate {
+                range: start..cursor_position,
+                text: candidate.clone(),
+            })
+            .collect()
+    }
+
     fn resolve_action(
         &mut self,
         event: &InputEvent,
@@ -80,6 +101,90 @@ pub fn show_line_prompt_overlay(
     Ok(())
 }
 
+/// Prompts for a line of text, pre-filled with `initial_value`, and then
+/// calls `apply` on the main thread with the line that was entered.
+/// `apply` is not called if the prompt is cancelled.
+pub fn show_text_prompt_overlay<F>(
+    mut term: TermWizTerminal,
+    description: &str,
+    initial_value: String,
+    apply: F,
+) -> anyhow::Result<()>
+where
+    F: FnOnce(String) + Send + 'static,
+{
+    term.no_grab_mouse_in_raw_mode();
+    term.render(&[Change::Text(format!("{description}\r\n"))])?;
+
+    let mut host = PromptHost::new();
+    let mut editor = LineEditor::new(&mut term);
+    editor.set_prompt("> ");
+    let line = editor.read_line_with_optional_initial_value(&mut host, Some(&initial_value))?;
+
+    if let Some(line) = line {
+        promise::spawn::spawn_into_main_thread(async move {
+            apply(line);
+            anyhow::Result::<()>::Ok(())
+        })
+        .detach();
+    }
+
+    Ok(())
+}
+
+/// Prompts for a host name, completing it from `hosts` when Tab
+/// is pressed, and then calls `apply` on the main thread with the
+/// line that was entered
+pub fn show_host_prompt_overlay<F>(
+    mut term: TermWizTerminal,
+    description: &str,
+    hosts: Vec<String>,
+    apply: F,
+) -> anyhow::Result<()>
+where
+    F: FnOnce(String) + Send + 'static,
+{
+    term.no_grab_mouse_in_raw_mode();
+    term.render(&[Change::Text(format!("{description}\r\n"))])?;
+
+    let mut host = PromptHost::new();
+    host.completions = hosts;
+    let mut editor = LineEditor::new(&mut term);
+    editor.set_prompt("> ");
+    let line = editor.read_line(&mut host)?;
+
+    if let Some(line) = line.filter(|line| !line.trim().is_empty()) {
+        promise::spawn::spawn_into_main_thread(async move {
+            apply(line.trim().to_string());
+            anyhow::Result::<()>::Ok(())
+        })
+        .detach();
+    }
+
+    Ok(())
+}
+
+/// Displays `message` until the user presses Escape, Enter or `q`
+pub fn show_message_overlay(mut term: TermWizTerminal, message: &str) -> anyhow::Result<()> {
+    term.no_grab_mouse_in_raw_mode();
+    term.render(&[
+        Change::Text(message.replace('\n', "\r\n")),
+        Change::Text("\r\n\r\nPress Escape, Enter or q to close this message.".to_string()),
+    ])?;
+
+    while let Ok(Some(event)) = term.poll_input(None) {
+        if let InputEvent::Key(KeyEvent {
+            key: KeyCode::Escape | KeyCode::Enter | KeyCode::Char('q'),
+            ..
+        }) = event
+        {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
 fn trampoline(name: String, window: GuiWin, pane: MuxPane, line: Option<String>) {
     promise::spawn::spawn(async move {
         config::with_lua_config_on_main_thread(move |lua| do_event(lua, name, window, pane, line))
@@ -0,0 +1,49 @@
+use mux::termwiztermtab::TermWizTerminal;
+use termwiz::cell::CellAttributes;
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers};
+use termwiz::surface::{Change, Line, Position, SEQ_ZERO};
+use termwiz::terminal::Terminal;
+
+/// Draws the lines that were captured by SnapshotPane, and keeps
+/// them on screen until the user presses ESC or `q`
+pub fn show_pane_snapshot(mut term: TermWizTerminal, lines: Vec<Line>) -> anyhow::Result<()> {
+    term.set_raw_mode()?;
+
+    let render = |term: &mut TermWizTerminal| -> anyhow::Result<()> {
+        let size = term.get_screen_size()?;
+        let mut changes = vec![Change::ClearScreen(ColorAttribute::Default)];
+        for (row, line) in lines.iter().take(size.rows).enumerate() {
+            let mut line = line.clone();
+            if line.len() > size.cols {
+                line.resize(size.cols, SEQ_ZERO);
+            }
+            changes.push(Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(row),
+            });
+            let attr = CellAttributes::default();
+            changes.append(&mut line.changes(&attr));
+            changes.push(Change::AllAttributes(attr));
+        }
+        term.render(&changes)?;
+        Ok(())
+    };
+
+    render(&mut term)?;
+    while let Ok(Some(event)) = term.poll_input(None) {
+        match event {
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Escape,
+                ..
+            })
+            | InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::NONE,
+            }) => break,
+            InputEvent::Resized { .. } => render(&mut term)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
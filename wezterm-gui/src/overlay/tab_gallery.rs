@@ -0,0 +1,226 @@
+use crate::termwindow::TermWindowNotif;
+use config::keyassignment::KeyAssignment;
+use mux::pane::PaneId;
+use mux::termwiztermtab::TermWizTerminal;
+use termwiz::cell::{unicode_column_width, AttributeChange, CellAttributes, Underline};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent};
+use termwiz::surface::{Change, Line, Position};
+use termwiz::terminal::Terminal;
+use termwiz_funcs::truncate_right;
+
+/// The visible content of the active pane of a tab, which is
+/// scaled down to make its thumbnail
+pub struct GalleryTab {
+    pub title: String,
+    pub cols: usize,
+    pub lines: Vec<Line>,
+}
+
+/// The position and size of a thumbnail, in cells
+struct Tile {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+struct TabGallery {
+    tabs: Vec<GalleryTab>,
+    selected: usize,
+    tiles: Vec<Tile>,
+    /// How many tiles there are in each row of the grid
+    columns: usize,
+    window: ::window::Window,
+    pane_id: PaneId,
+}
+
+impl TabGallery {
+    /// Arranges the thumbnails in a grid that is roughly as wide as it is tall
+    fn layout(&mut self, cols: usize, rows: usize) {
+        let num_tabs = self.tabs.len().max(1);
+        self.columns = (num_tabs as f32).sqrt().ceil() as usize;
+        let grid_rows = (num_tabs + self.columns - 1) / self.columns;
+
+        // Leave the first row for the help text, and a gap between tiles
+        let width = (cols / self.columns).saturating_sub(1).max(1);
+        let height = (rows.saturating_sub(1) / grid_rows)
+            .saturating_sub(1)
+            .max(2);
+
+        self.tiles = (0..self.tabs.len())
+            .map(|idx| Tile {
+                x: (idx % self.columns) * (width + 1),
+                y: 1 + (idx / self.columns) * (height + 1),
+                width,
+                height,
+            })
+            .collect();
+    }
+
+    fn tile_at(&self, x: usize, y: usize) -> Option<usize> {
+        self.tiles.iter().position(|tile| {
+            x >= tile.x && x < tile.x + tile.width && y >= tile.y && y < tile.y + tile.height
+        })
+    }
+
+    fn render(&mut self, term: &mut TermWizTerminal) -> termwiz::Result<()> {
+        let size = term.get_screen_size()?;
+        self.layout(size.cols, size.rows);
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(0),
+            },
+            Change::Text(truncate_right(
+                "Tab Gallery: click or press Enter to switch tabs, ESC closes",
+                size.cols,
+            )),
+        ];
+
+        for (idx, (tab, tile)) in self.tabs.iter().zip(self.tiles.iter()).enumerate() {
+            changes.push(Change::CursorPosition {
+                x: Position::Absolute(tile.x),
+                y: Position::Absolute(tile.y),
+            });
+            if idx == self.selected {
+                changes.push(AttributeChange::Reverse(true).into());
+            } else {
+                changes.push(AttributeChange::Underline(Underline::Single).into());
+            }
+            let title = truncate_right(&format!("{}: {}", idx + 1, tab.title), tile.width);
+            let padding = tile
+                .width
+                .saturating_sub(unicode_column_width(&title, None));
+            changes.push(Change::Text(format!("{title}{}", " ".repeat(padding))));
+            changes.push(Change::AllAttributes(CellAttributes::default()));
+
+            // Sample the pane content at the reduced resolution of the tile
+            let thumb_rows = tile.height - 1;
+            for row in 0..thumb_rows {
+                changes.push(Change::CursorPosition {
+                    x: Position::Absolute(tile.x),
+                    y: Position::Absolute(tile.y + 1 + row),
+                });
+                let line = match tab.lines.get(row * tab.lines.len() / thumb_rows) {
+                    Some(line) => line,
+                    None => continue,
+                };
+                for col in 0..tile.width {
+                    match line.get_cell(col * tab.cols.max(1) / tile.width) {
+                        Some(cell) if cell.width() == 1 && !cell.str().is_empty() => {
+                            changes.push(Change::AllAttributes(cell.attrs().clone()));
+                            changes.push(Change::Text(cell.str().to_string()));
+                        }
+                        _ => {
+                            changes.push(Change::AllAttributes(CellAttributes::default()));
+                            changes.push(Change::Text(" ".to_string()));
+                        }
+                    }
+                }
+                changes.push(Change::AllAttributes(CellAttributes::default()));
+            }
+        }
+
+        term.render(&changes)
+    }
+
+    fn activate(&self, idx: usize) {
+        self.window.notify(TermWindowNotif::PerformAssignment {
+            pane_id: self.pane_id,
+            assignment: KeyAssignment::ActivateTab(idx as isize),
+            tx: None,
+        });
+    }
+
+    fn run_loop(&mut self, term: &mut TermWizTerminal) -> anyhow::Result<()> {
+        self.render(term)?;
+        while let Ok(Some(event)) = term.poll_input(None) {
+            match event {
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Escape,
+                    ..
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('q'),
+                    modifiers: Modifiers::NONE,
+                }) => break,
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Enter,
+                    ..
+                }) => {
+                    self.activate(self.selected);
+                    break;
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::LeftArrow,
+                    ..
+                }) => self.selected = self.selected.saturating_sub(1),
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::RightArrow,
+                    ..
+                }) => self.selected = (self.selected + 1).min(self.tabs.len() - 1),
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::UpArrow,
+                    ..
+                }) => {
+                    if self.selected >= self.columns {
+                        self.selected -= self.columns;
+                    }
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::DownArrow,
+                    ..
+                }) => {
+                    if self.selected + self.columns < self.tabs.len() {
+                        self.selected += self.columns;
+                    }
+                }
+                InputEvent::Mouse(MouseEvent {
+                    x,
+                    y,
+                    mouse_buttons,
+                    ..
+                }) => {
+                    if let Some(idx) = self.tile_at(x as usize, y as usize) {
+                        if mouse_buttons == MouseButtons::LEFT {
+                            self.activate(idx);
+                            break;
+                        }
+                        self.selected = idx;
+                    }
+                }
+                InputEvent::Resized { .. } => {}
+                _ => continue,
+            }
+            self.render(term)?;
+        }
+        Ok(())
+    }
+}
+
+/// Shows a thumbnail of each of `tabs`, and switches to the one
+/// that the user picks
+pub fn show_tab_gallery(
+    mut term: TermWizTerminal,
+    tabs: Vec<GalleryTab>,
+    active_idx: usize,
+    window: ::window::Window,
+    pane_id: PaneId,
+) -> anyhow::Result<()> {
+    if tabs.is_empty() {
+        return Ok(());
+    }
+    term.set_raw_mode()?;
+    let mut gallery = TabGallery {
+        selected: active_idx.min(tabs.len() - 1),
+        tabs,
+        tiles: vec![],
+        columns: 1,
+        window,
+        pane_id,
+    };
+    gallery.run_loop(&mut term)
+}
@@ -1,6 +1,7 @@
 use super::glyphcache::GlyphCache;
 use super::quad::*;
 use super::utilsprites::{RenderMetrics, UtilSprites};
+use crate::termwindow::blur::BlurRegion;
 use crate::termwindow::webgpu::{adapter_info_to_gpu_info, WebGpuState, WebGpuTexture};
 use ::window::bitmaps::atlas::OutOfTextureSpace;
 use ::window::bitmaps::Texture2d;
@@ -472,6 +473,10 @@ impl RenderLayer {
         })
     }
 
+    pub fn zindex(&self) -> i8 {
+        self.zindex
+    }
+
     pub fn clear_quad_allocation(&self) {
         for vb in self.vb.borrow().iter() {
             vb.clear_quad_allocation();
@@ -576,6 +581,8 @@ pub struct RenderState {
     pub util_sprites: UtilSprites,
     pub glyph_prog: Option<glium::Program>,
     pub layers: RefCell<Vec<Rc<RenderLayer>>>,
+    /// Shadows to be blurred by the WebGpu BlurPass in the current frame
+    pub blur_regions: RefCell<Vec<BlurRegion>>,
 }
 
 impl RenderState {
@@ -605,6 +612,7 @@ impl RenderState {
                         util_sprites,
                         glyph_prog,
                         layers: RefCell::new(vec![main_layer]),
+                        blur_regions: RefCell::new(vec![]),
                     });
                 }
                 Err(OutOfTextureSpace {
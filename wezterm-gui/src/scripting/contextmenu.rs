@@ -0,0 +1,74 @@
+//! LuaContextMenu lets lua code assemble a context menu one entry
+//! at a time, and then show it in a gui window
+use super::guiwin::GuiWin;
+use crate::termwindow::contextmenu::{ContextMenu, ContextMenuBuilder};
+use crate::termwindow::TermWindowNotif;
+use config::keyassignment::KeyAssignment;
+use config::lua::mlua::{self, UserData, UserDataMethods, UserDataRef};
+use config::lua::wrap_callback;
+use luahelper::from_lua_value_dynamic;
+use std::rc::Rc;
+
+#[derive(Clone)]
+pub struct LuaContextMenu {
+    builder: ContextMenuBuilder,
+    entries: usize,
+}
+
+impl Default for LuaContextMenu {
+    fn default() -> Self {
+        Self {
+            builder: ContextMenu::builder(),
+            entries: 0,
+        }
+    }
+}
+
+impl UserData for LuaContextMenu {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_meta_method(mlua::MetaMethod::ToString, |_, this, _: ()| {
+            Ok(format!("ContextMenu(entries:{})", this.entries))
+        });
+
+        methods.add_method_mut(
+            "add_entry",
+            |lua, this, (label, icon, action): (String, Option<String>, mlua::Value)| {
+                let action = match action {
+                    mlua::Value::Function(callback) => {
+                        KeyAssignment::EmitEvent(wrap_callback(lua, callback)?)
+                    }
+                    action => from_lua_value_dynamic(action)?,
+                };
+                this.builder =
+                    std::mem::take(&mut this.builder).entry(label, icon.as_deref(), action);
+                this.entries += 1;
+                Ok(())
+            },
+        );
+
+        methods.add_method_mut("add_separator", |_, this, _: ()| {
+            this.builder = std::mem::take(&mut this.builder).separator();
+            Ok(())
+        });
+
+        methods.add_method_mut("add_section_header", |_, this, label: String| {
+            this.builder = std::mem::take(&mut this.builder).section_header(label);
+            Ok(())
+        });
+
+        methods.add_method(
+            "show",
+            |_, this, (window, x, y): (UserDataRef<GuiWin>, Option<isize>, Option<isize>)| {
+                let builder = this.builder.clone();
+                window
+                    .window
+                    .notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+                        let (mouse_x, mouse_y) = term_window.context_menu_position();
+                        let menu = builder.build(x.unwrap_or(mouse_x), y.unwrap_or(mouse_y));
+                        term_window.push_modal(Rc::new(menu));
+                    })));
+                Ok(())
+            },
+        );
+    }
+}
@@ -62,7 +62,14 @@ impl UserData for GuiWin {
             },
         );
         methods.add_method("set_position", |_, this, (x, y): (isize, isize)| {
-            this.window.set_window_position(euclid::point2(x, y));
+            this.window
+                .notify(TermWindowNotif::Apply(Box::new(move |myself| {
+                    if myself.is_window_position_locked() {
+                        log::warn!("window:set_position ignored as the window position is locked");
+                    } else if let Some(window) = myself.window.as_ref() {
+                        window.set_window_position(euclid::point2(x, y));
+                    }
+                })));
             Ok(())
         });
         methods.add_method("maximize", |_, this, _: ()| {
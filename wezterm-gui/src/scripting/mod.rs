@@ -1,14 +1,18 @@
 use crate::frontend::try_front_end;
 use crate::inputmap::InputMap;
-use config::keyassignment::KeyTable;
+use crate::termwindow::TermWindowNotif;
+use config::keyassignment::{KeyTable, MenuItemDef};
 use config::lua::get_or_create_sub_module;
-use config::lua::mlua::{self, Lua};
+use config::lua::mlua::{self, Lua, UserDataRef};
 use config::{DeferredKeyCode, GpuInfo, Key, KeyNoAction};
-use luahelper::dynamic_to_lua_value;
+use guiwin::GuiWin;
+use luahelper::{dynamic_to_lua_value, from_lua_value_dynamic};
 use mux::window::WindowId as MuxWindowId;
+use mux_lua::MuxPane;
 use std::collections::HashMap;
 use wezterm_dynamic::ToDynamic;
 
+pub mod contextmenu;
 pub mod guiwin;
 
 fn luaerr(err: anyhow::Error) -> mlua::Error {
@@ -78,6 +82,30 @@ pub fn register(lua: &Lua) -> anyhow::Result<()> {
         })?,
     )?;
 
+    window_mod.set(
+        "show_context_menu",
+        lua.create_function(
+            |_, (window, pane, items): (UserDataRef<GuiWin>, UserDataRef<MuxPane>, mlua::Value)| {
+                let items: Vec<MenuItemDef> = from_lua_value_dynamic(items)?;
+                let pane_id = pane.0;
+                window
+                    .window
+                    .notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+                        let (x, y) = term_window.context_menu_position();
+                        term_window.open_context_menu(items, x, y, Some(pane_id));
+                    })));
+                Ok(())
+            },
+        )?,
+    )?;
+
+    let context_menu = lua.create_table()?;
+    context_menu.set(
+        "new",
+        lua.create_function(|_, _: mlua::MultiValue| Ok(contextmenu::LuaContextMenu::default()))?,
+    )?;
+    window_mod.set("ContextMenu", context_menu)?;
+
     window_mod.set(
         "enumerate_gpus",
         lua.create_function(|_, _: ()| {
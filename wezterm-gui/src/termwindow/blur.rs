@@ -0,0 +1,570 @@
+//! Renders soft shadows using a separable gaussian blur on the GPU
+//! when the WebGpu front end is in use.  Elements register the area
+//! to be blurred as a `BlurRegion` while they are being rendered, and
+//! the draw step runs the `BlurPass` for each region just before the
+//! layer with the same zindex, so that the shadow ends up behind
+//! the element that cast it.
+//! A region can instead blur the backdrop: whatever has already been
+//! drawn behind it, which is read back from the frame, blurred and
+//! written back in place.
+use std::cell::RefCell;
+use wgpu::util::DeviceExt;
+use window::color::LinearRgba;
+use window::RectF;
+
+/// Shadows are blurred in a texture that extends the shape by this
+/// many standard deviations on each side, which is far enough for
+/// the gaussian to have faded out
+const SIGMA_EXTENT: f32 = 3.;
+
+const WORKGROUP_SIZE: u32 = 8;
+
+const MASK_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+#[derive(Debug, Clone, Copy)]
+pub enum BlurKind {
+    /// A shadow of this color cast by the rect
+    Shadow(LinearRgba),
+    /// The content that is behind the rect
+    Backdrop,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlurRegion {
+    pub zindex: i8,
+    /// The shape that casts the shadow, or whose backdrop is
+    /// blurred, in window pixel coordinates
+    pub rect: RectF,
+    pub kind: BlurKind,
+    /// The standard deviation of the blur, in pixels
+    pub sigma: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct BlurParams {
+    mask_min: [f32; 2],
+    mask_max: [f32; 2],
+    direction: [i32; 2],
+    radius: i32,
+    sigma: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct CompositeParams {
+    rect: [f32; 4],
+    tex_rect: [f32; 4],
+    color: [f32; 4],
+}
+
+/// A pair of textures that the blur ping-pongs between
+struct MaskTextures {
+    width: u32,
+    height: u32,
+    front: wgpu::TextureView,
+    back: wgpu::TextureView,
+}
+
+/// The copy of the frame that a backdrop blur reads from
+struct SourceTexture {
+    width: u32,
+    height: u32,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+pub struct BlurPass {
+    compute_bind_group_layout: wgpu::BindGroupLayout,
+    fill_pipeline: wgpu::ComputePipeline,
+    load_pipeline: wgpu::ComputePipeline,
+    blur_pipeline: wgpu::ComputePipeline,
+    composite_bind_group_layout: wgpu::BindGroupLayout,
+    composite_pipeline: wgpu::RenderPipeline,
+    backdrop_pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+    /// Whether the surface can be copied from, which
+    /// is needed to blur the backdrop
+    supports_backdrop: bool,
+    /// The menu that casts the shadow usually stays the same size
+    /// for many frames, so keep its textures around between frames
+    textures: RefCell<Option<MaskTextures>>,
+    source: RefCell<Option<SourceTexture>>,
+}
+
+impl BlurPass {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        supports_backdrop: bool,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("../blur.wgsl"));
+
+        let compute_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::StorageTexture {
+                            access: wgpu::StorageTextureAccess::WriteOnly,
+                            format: MASK_FORMAT,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("blur compute bind group layout"),
+            });
+
+        let compute_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blur Compute Pipeline Layout"),
+            bind_group_layouts: &[&compute_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let compute_pipeline = |entry_point: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&compute_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                cache: None,
+            })
+        };
+        let fill_pipeline = compute_pipeline("fill_mask");
+        let load_pipeline = compute_pipeline("load_backdrop");
+        let blur_pipeline = compute_pipeline("blur");
+
+        let composite_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+                label: Some("blur composite bind group layout"),
+            });
+
+        let composite_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blur Composite Pipeline Layout"),
+            bind_group_layouts: &[&composite_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = |entry_point: &str, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&composite_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_composite"),
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(blend),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let composite_pipeline = render_pipeline("fs_composite", wgpu::BlendState::ALPHA_BLENDING);
+        // The blurred backdrop takes the place of what was there
+        let backdrop_pipeline = render_pipeline("fs_backdrop", wgpu::BlendState::REPLACE);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            compute_bind_group_layout,
+            fill_pipeline,
+            load_pipeline,
+            blur_pipeline,
+            composite_bind_group_layout,
+            composite_pipeline,
+            backdrop_pipeline,
+            sampler,
+            supports_backdrop,
+            textures: RefCell::new(None),
+            source: RefCell::new(None),
+        }
+    }
+
+    pub fn supports_backdrop(&self) -> bool {
+        self.supports_backdrop
+    }
+
+    fn source_texture(
+        &self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) {
+        let mut source = self.source.borrow_mut();
+        if let Some(s) = source.as_ref() {
+            if s.width == width && s.height == height && s.texture.format() == format {
+                return;
+            }
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("blur backdrop source"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        source.replace(SourceTexture {
+            width,
+            height,
+            texture,
+            view,
+        });
+    }
+
+    fn mask_textures(&self, device: &wgpu::Device, width: u32, height: u32) {
+        let mut textures = self.textures.borrow_mut();
+        if let Some(t) = textures.as_ref() {
+            if t.width == width && t.height == height {
+                return;
+            }
+        }
+
+        let create = |label| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: MASK_FORMAT,
+                    usage: wgpu::TextureUsages::STORAGE_BINDING
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        textures.replace(MaskTextures {
+            width,
+            height,
+            front: create("blur mask front"),
+            back: create("blur mask back"),
+        });
+    }
+
+    fn compute_bind_group(
+        &self,
+        device: &wgpu::Device,
+        params: BlurParams,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("BlurParams Buffer"),
+            contents: bytemuck::cast_slice(&[params]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.compute_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(src),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(dst),
+                },
+            ],
+            label: Some("blur compute bind group"),
+        })
+    }
+
+    /// Blurs the region and draws the result into `view`, which is
+    /// a view of `target`, the frame being rendered.
+    /// When `clear` is true, `view` is cleared first, as this is the
+    /// first pass to touch it in this frame.
+    pub fn draw(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::Texture,
+        view: &wgpu::TextureView,
+        region: &BlurRegion,
+        clear: bool,
+    ) {
+        let surface_width = target.width() as f32;
+        let surface_height = target.height() as f32;
+        let bounds = RectF::new(
+            euclid::point2(0., 0.),
+            euclid::size2(surface_width, surface_height),
+        );
+        let sigma = region.sigma.max(0.5);
+        let pad = (sigma * SIGMA_EXTENT).ceil();
+
+        // The area of the frame that is covered by the blur textures,
+        // and the part of it that is drawn into the frame
+        let (area, dest) = match region.kind {
+            BlurKind::Shadow(_) => {
+                let area = region.rect.inflate(pad, pad);
+                (area, area)
+            }
+            BlurKind::Backdrop => {
+                // Only what is in the frame can be read back from it
+                let area = region.rect.inflate(pad, pad).round_out();
+                match (
+                    area.intersection(&bounds),
+                    region.rect.intersection(&bounds),
+                ) {
+                    (Some(area), Some(dest)) => (area, dest),
+                    _ => return,
+                }
+            }
+        };
+
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        let width = (area.width().ceil() as u32).clamp(1, max_dimension);
+        let height = (area.height().ceil() as u32).clamp(1, max_dimension);
+
+        self.mask_textures(device, width, height);
+        let textures = self.textures.borrow();
+        let textures = textures.as_ref().unwrap();
+
+        let mask_min = [
+            region.rect.min_x() - area.min_x(),
+            region.rect.min_y() - area.min_y(),
+        ];
+        let params = |direction| BlurParams {
+            mask_min,
+            mask_max: [
+                mask_min[0] + region.rect.width(),
+                mask_min[1] + region.rect.height(),
+            ],
+            direction,
+            radius: pad as i32,
+            sigma,
+        };
+
+        // The first pass puts the unblurred content into `front`
+        let (first_pipeline, first) = match region.kind {
+            BlurKind::Shadow(_) => (
+                &self.fill_pipeline,
+                self.compute_bind_group(device, params([0, 0]), &textures.back, &textures.front),
+            ),
+            BlurKind::Backdrop => {
+                self.source_texture(device, target.format(), width, height);
+                let source = self.source.borrow();
+                let source = source.as_ref().unwrap();
+                encoder.copy_texture_to_texture(
+                    wgpu::TexelCopyTextureInfo {
+                        texture: target,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d {
+                            x: area.min_x() as u32,
+                            y: area.min_y() as u32,
+                            z: 0,
+                        },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    wgpu::TexelCopyTextureInfo {
+                        texture: &source.texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d::ZERO,
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                );
+                (
+                    &self.load_pipeline,
+                    self.compute_bind_group(device, params([0, 0]), &source.view, &textures.front),
+                )
+            }
+        };
+        // and then it ends up back in `front` after the two blur passes
+        let horizontal =
+            self.compute_bind_group(device, params([1, 0]), &textures.front, &textures.back);
+        let vertical =
+            self.compute_bind_group(device, params([0, 1]), &textures.back, &textures.front);
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Blur Pass"),
+                timestamp_writes: None,
+            });
+            let groups_x = (width + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+            let groups_y = (height + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+
+            compute_pass.set_pipeline(first_pipeline);
+            compute_pass.set_bind_group(0, &first, &[]);
+            compute_pass.dispatch_workgroups(groups_x, groups_y, 1);
+
+            compute_pass.set_pipeline(&self.blur_pipeline);
+            for bind_group in [&horizontal, &vertical] {
+                compute_pass.set_bind_group(0, bind_group, &[]);
+                compute_pass.dispatch_workgroups(groups_x, groups_y, 1);
+            }
+        }
+
+        let to_clip_x = |x: f32| x / surface_width * 2. - 1.;
+        let to_clip_y = |y: f32| 1. - y / surface_height * 2.;
+        let to_tex_x = |x: f32| (x - area.min_x()) / width as f32;
+        let to_tex_y = |y: f32| (y - area.min_y()) / height as f32;
+        let (color, pipeline) = match region.kind {
+            BlurKind::Shadow(color) => (color, &self.composite_pipeline),
+            BlurKind::Backdrop => (LinearRgba::TRANSPARENT, &self.backdrop_pipeline),
+        };
+        let composite = CompositeParams {
+            rect: [
+                to_clip_x(dest.min_x()),
+                to_clip_y(dest.min_y()),
+                to_clip_x(dest.max_x()),
+                to_clip_y(dest.max_y()),
+            ],
+            tex_rect: [
+                to_tex_x(dest.min_x()),
+                to_tex_y(dest.min_y()),
+                to_tex_x(dest.max_x()),
+                to_tex_y(dest.max_y()),
+            ],
+            color: [color.0, color.1, color.2, color.3],
+        };
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("CompositeParams Buffer"),
+            contents: bytemuck::cast_slice(&[composite]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let composite_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.composite_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&textures.front),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: Some("blur composite bind group"),
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blur Composite Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: if clear {
+                        wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 0.,
+                            g: 0.,
+                            b: 0.,
+                            a: 0.,
+                        })
+                    } else {
+                        wgpu::LoadOp::Load
+                    },
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &composite_bind_group, &[]);
+        render_pass.draw(0..6, 0..1);
+    }
+}
@@ -3,6 +3,7 @@ use crate::color::LinearRgba;
 use crate::customglyph::{BlockKey, Poly};
 use crate::glyphcache::CachedGlyph;
 use crate::quad::{QuadImpl, QuadTrait, TripleLayerQuadAllocator, TripleLayerQuadAllocatorTrait};
+use crate::termwindow::blur::{BlurKind, BlurRegion};
 use crate::termwindow::{
     ColorEase, MouseCapture, RenderState, TermWindowNotif, UIItem, UIItemType,
 };
@@ -13,6 +14,7 @@ use config::{Dimension, DimensionContext};
 use finl_unicode::grapheme_clusters::Graphemes;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use termwiz::cell::{grapheme_column_width, Presentation};
 use termwiz::surface::Line;
 use wezterm_font::units::PixelUnit;
@@ -20,6 +22,10 @@ use wezterm_font::LoadedFont;
 use wezterm_term::color::{ColorAttribute, ColorPalette};
 use window::bitmaps::atlas::Sprite;
 
+/// The warning about backdrop blur being unavailable would otherwise
+/// be logged on every frame while the element is shown
+static BACKDROP_BLUR_UNSUPPORTED_REPORTED: AtomicBool = AtomicBool::new(false);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VerticalAlign {
     Top,
@@ -196,6 +202,16 @@ pub struct ElementColors {
     pub text: InheritableColor,
 }
 
+/// A soft shadow drawn behind the border rectangle of an element.
+/// The offsets and blur radius are measured in pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct BoxShadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur_radius: f32,
+    pub color: LinearRgba,
+}
+
 struct ResolvedColor {
     color: LinearRgba,
     alt_color: LinearRgba,
@@ -232,6 +248,11 @@ pub struct Element {
     pub border_corners: Option<Corners>,
     pub colors: ElementColors,
     pub hover_colors: Option<ElementColors>,
+    pub shadow: Option<BoxShadow>,
+    /// The standard deviation, in pixels, of a blur applied to
+    /// whatever is behind the border rectangle of the element.
+    /// Only the WebGpu front end supports this.
+    pub backdrop_blur: Option<f32>,
     pub font: Rc<LoadedFont>,
     pub content: ElementContent,
     pub presentation: Option<Presentation>,
@@ -255,6 +276,8 @@ impl Element {
             vertical_align: VerticalAlign::default(),
             colors: ElementColors::default(),
             hover_colors: None,
+            shadow: None,
+            backdrop_blur: None,
             font: Rc::clone(font),
             content,
             presentation: None,
@@ -368,6 +391,16 @@ impl Element {
         self
     }
 
+    pub fn shadow(mut self, shadow: Option<BoxShadow>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    pub fn backdrop_blur(mut self, sigma: Option<f32>) -> Self {
+        self.backdrop_blur = sigma;
+        self
+    }
+
     pub fn margin(mut self, margin: BoxDimension) -> Self {
         self.margin = margin;
         self
@@ -417,6 +450,8 @@ pub struct ComputedElement {
     pub border_corners: Option<PixelCorners>,
     pub colors: ElementColors,
     pub hover_colors: Option<ElementColors>,
+    pub shadow: Option<BoxShadow>,
+    pub backdrop_blur: Option<f32>,
     /// The outer bounds of the area enclosed by the padding
     pub padding: RectF,
     /// The outer bounds of the content
@@ -677,6 +712,8 @@ impl super::TermWindow {
                     border_corners,
                     colors: element.colors.clone(),
                     hover_colors: element.hover_colors.clone(),
+                    shadow: element.shadow,
+                    backdrop_blur: element.backdrop_blur,
                     bounds: rects.bounds,
                     border_rect: rects.border_rect,
                     padding: rects.padding,
@@ -790,6 +827,8 @@ impl super::TermWindow {
                     border_corners,
                     colors: element.colors.clone(),
                     hover_colors: element.hover_colors.clone(),
+                    shadow: element.shadow,
+                    backdrop_blur: element.backdrop_blur,
                     bounds: rects.bounds,
                     border_rect: rects.border_rect,
                     padding: rects.padding,
@@ -810,6 +849,8 @@ impl super::TermWindow {
                     border_corners,
                     colors: element.colors.clone(),
                     hover_colors: element.hover_colors.clone(),
+                    shadow: element.shadow,
+                    backdrop_blur: element.backdrop_blur,
                     bounds: rects.bounds,
                     border_rect: rects.border_rect,
                     padding: rects.padding,
@@ -855,6 +896,12 @@ impl super::TermWindow {
             None => &element.colors,
         };
 
+        if let Some(sigma) = element.backdrop_blur {
+            self.render_element_backdrop_blur(element, sigma, gl_state);
+        }
+        if let Some(shadow) = &element.shadow {
+            self.render_element_shadow(element, shadow, gl_state, &mut layers)?;
+        }
         self.render_element_background(element, colors, &mut layers, inherited_colors)?;
         let left = self.dimensions.pixel_width as f32 / -2.0;
         let top = self.dimensions.pixel_height as f32 / -2.0;
@@ -1007,6 +1054,78 @@ impl super::TermWindow {
         }
     }
 
+    /// Registers the area behind the element with the BlurPass, which
+    /// blurs it before the element is drawn over it. There is no way
+    /// to do that with the other front ends, so it is left unblurred.
+    fn render_element_backdrop_blur(
+        &self,
+        element: &ComputedElement,
+        sigma: f32,
+        gl_state: &RenderState,
+    ) {
+        if self
+            .webgpu
+            .as_ref()
+            .and_then(|webgpu| webgpu.blur_pass.as_ref())
+            .map_or(false, |blur_pass| blur_pass.supports_backdrop())
+        {
+            gl_state.blur_regions.borrow_mut().push(BlurRegion {
+                zindex: element.zindex,
+                rect: element.border_rect,
+                kind: BlurKind::Backdrop,
+                sigma,
+            });
+        } else if !BACKDROP_BLUR_UNSUPPORTED_REPORTED.swap(true, Ordering::Relaxed) {
+            log::warn!(
+                "Blurring the background of an element requires \
+                 `front_end = \"WebGpu\"` and a GPU that supports compute \
+                 shaders; it will be drawn without the blur"
+            );
+        }
+    }
+
+    /// With WebGpu, the shadow is registered as a BlurRegion so that
+    /// the BlurPass can draw it with a gaussian blur whose standard
+    /// deviation is half of the blur radius, as in CSS.
+    /// Otherwise, a blurred shadow is approximated by stacking
+    /// progressively larger, fainter copies of the border rectangle,
+    /// so that the overlapping copies build up to the full shadow color
+    /// in the middle and fade out over the blur radius at the edges
+    fn render_element_shadow(
+        &self,
+        element: &ComputedElement,
+        shadow: &BoxShadow,
+        gl_state: &RenderState,
+        layers: &mut TripleLayerQuadAllocator,
+    ) -> anyhow::Result<()> {
+        let rect = element
+            .border_rect
+            .translate(euclid::vec2(shadow.offset_x, shadow.offset_y));
+
+        if self
+            .webgpu
+            .as_ref()
+            .map_or(false, |webgpu| webgpu.blur_pass.is_some())
+        {
+            gl_state.blur_regions.borrow_mut().push(BlurRegion {
+                zindex: element.zindex,
+                rect,
+                kind: BlurKind::Shadow(shadow.color),
+                sigma: shadow.blur_radius / 2.,
+            });
+            return Ok(());
+        }
+
+        let steps = shadow.blur_radius.ceil().clamp(1., 8.) as usize;
+        let step_size = shadow.blur_radius / steps as f32;
+        let color = shadow.color.mul_alpha(1. / steps as f32);
+        for step in (0..steps).rev() {
+            let inflate = step as f32 * step_size;
+            self.filled_rectangle(layers, 0, rect.inflate(inflate, inflate), color)?;
+        }
+        Ok(())
+    }
+
     fn render_element_background<'a>(
         &self,
         element: &ComputedElement,
@@ -1,6 +1,6 @@
 use crate::overlay::selector::{matcher_pattern, matcher_score};
 use crate::termwindow::box_model::*;
-use crate::termwindow::modal::Modal;
+use crate::termwindow::modal::{EventPropagation, Modal};
 use crate::termwindow::render::corners::{
     BOTTOM_LEFT_ROUNDED_CORNER, BOTTOM_RIGHT_ROUNDED_CORNER, TOP_LEFT_ROUNDED_CORNER,
     TOP_RIGHT_ROUNDED_CORNER,
@@ -597,8 +597,12 @@ impl Modal for CharSelector {
         false
     }
 
-    fn mouse_event(&self, _event: MouseEvent, _term_window: &mut TermWindow) -> anyhow::Result<()> {
-        Ok(())
+    fn mouse_event(
+        &self,
+        _event: MouseEvent,
+        _term_window: &mut TermWindow,
+    ) -> anyhow::Result<EventPropagation> {
+        Ok(EventPropagation::Consumed)
     }
 
     fn key_down(
@@ -1,13 +1,41 @@
+use crate::overlay::confirm::run_confirmation;
+use crate::overlay::prompt::show_text_prompt_overlay;
+use crate::overlay::start_overlay;
 use crate::termwindow::TermWindowNotif;
 use crate::TermWindow;
 use config::keyassignment::{ClipboardCopyDestination, ClipboardPasteSource};
-use mux::pane::Pane;
+use mux::pane::{CachePolicy, Pane, PaneId};
 use mux::Mux;
 use std::sync::Arc;
+use std::time::Duration;
 use window::{Clipboard, WindowOps};
 
 impl TermWindow {
     pub fn copy_to_clipboard(&self, clipboard: ClipboardCopyDestination, text: String) {
+        self.copy_html_or_text_to_clipboard(clipboard, None, text);
+    }
+
+    /// Copy `html` to the clipboard, with `text` as the plain text
+    /// alternative for applications that don't accept html
+    pub fn copy_html_to_clipboard(
+        &self,
+        clipboard: ClipboardCopyDestination,
+        html: String,
+        text: String,
+    ) {
+        self.copy_html_or_text_to_clipboard(clipboard, Some(html), text);
+    }
+
+    fn copy_html_or_text_to_clipboard(
+        &self,
+        clipboard: ClipboardCopyDestination,
+        html: Option<String>,
+        text: String,
+    ) {
+        if self.secure_input {
+            log::warn!("secure input mode is enabled; not copying to the clipboard");
+            return;
+        }
         let clipboard = match clipboard {
             ClipboardCopyDestination::Clipboard => [Some(Clipboard::Clipboard), None],
             ClipboardCopyDestination::PrimarySelection => [Some(Clipboard::PrimarySelection), None],
@@ -16,14 +44,22 @@ impl TermWindow {
                 Some(Clipboard::PrimarySelection),
             ],
         };
+        let window = self.window.as_ref().unwrap();
         for &c in &clipboard {
             if let Some(c) = c {
-                self.window.as_ref().unwrap().set_clipboard(c, text.clone());
+                match &html {
+                    Some(html) => window.set_clipboard_html(c, html.clone(), text.clone()),
+                    None => window.set_clipboard(c, text.clone()),
+                }
             }
         }
     }
 
     pub fn paste_from_clipboard(&mut self, pane: &Arc<dyn Pane>, clipboard: ClipboardPasteSource) {
+        if self.secure_input {
+            log::warn!("secure input mode is enabled; not reading the clipboard");
+            return;
+        }
         let pane_id = pane.pane_id();
         log::trace!(
             "paste_from_clipboard in pane {} {:?}",
@@ -57,4 +93,127 @@ impl TermWindow {
         .detach();
         self.maybe_scroll_to_bottom_for_input(&pane);
     }
+
+    /// Asks for confirmation, then pastes the clipboard into each
+    /// of the panes in the active tab
+    pub fn paste_to_all_panes(&mut self) {
+        if self.secure_input {
+            log::warn!("secure input mode is enabled; not reading the clipboard");
+            return;
+        }
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let panes: Vec<Arc<dyn Pane>> = tab
+            .iter_panes_ignoring_zoom()
+            .into_iter()
+            .map(|pos| pos.pane)
+            .collect();
+        let pane_ids: Vec<PaneId> = panes.iter().map(|pane| pane.pane_id()).collect();
+        let names: Vec<String> = panes
+            .iter()
+            .map(|pane| {
+                pane.get_foreground_process_name(CachePolicy::AllowStale)
+                    .and_then(|path| {
+                        std::path::Path::new(&path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                    })
+                    .unwrap_or_else(|| pane.get_title())
+            })
+            .collect();
+
+        let message = format!(
+            "🛑 Really paste the clipboard into these {} panes?\n\n{}",
+            names.len(),
+            names.join("\n")
+        );
+        let window = self.window.clone().unwrap();
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, mut term| {
+            if run_confirmation(&message, &mut term)? {
+                window.notify(TermWindowNotif::Apply(Box::new(move |myself| {
+                    myself.paste_to_panes(pane_ids);
+                })));
+            }
+            Ok(())
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    /// Prompts for a prefix, starting with the one used last time,
+    /// and then pastes the clipboard with it added to each line
+    pub fn show_paste_with_prefix_prompt(&mut self, pane: &Arc<dyn Pane>) {
+        if self.secure_input {
+            log::warn!("secure input mode is enabled; not reading the clipboard");
+            return;
+        }
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let pane_id = pane.pane_id();
+        let window = self.window.clone().unwrap();
+        let initial_value = self.paste_prefix.clone();
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            show_text_prompt_overlay(
+                term,
+                "Enter a prefix to add to each pasted line",
+                initial_value,
+                move |prefix| {
+                    window.notify(TermWindowNotif::Apply(Box::new(move |myself| {
+                        myself.paste_with_prefix(pane_id, prefix);
+                    })));
+                },
+            )
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn paste_with_prefix(&mut self, pane_id: PaneId, prefix: String) {
+        self.paste_prefix = prefix.clone();
+        let window = self.window.as_ref().unwrap().clone();
+        let future = window.get_clipboard(Clipboard::Clipboard);
+        promise::spawn::spawn(async move {
+            if let Ok(clip) = future.await {
+                let text: String = clip
+                    .split_inclusive('\n')
+                    .map(|line| format!("{prefix}{line}"))
+                    .collect();
+                if let Some(pane) = Mux::get().get_pane(pane_id) {
+                    pane.send_paste(&text).ok();
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Pastes the clipboard into each of the specified panes in turn,
+    /// pausing for paste_to_all_delay_ms between them
+    fn paste_to_panes(&mut self, pane_ids: Vec<PaneId>) {
+        let window = self.window.as_ref().unwrap().clone();
+        let delay = Duration::from_millis(self.config.paste_to_all_delay_ms);
+        let future = window.get_clipboard(Clipboard::Clipboard);
+        promise::spawn::spawn(async move {
+            if let Ok(clip) = future.await {
+                for (idx, pane_id) in pane_ids.into_iter().enumerate() {
+                    if idx > 0 && !delay.is_zero() {
+                        smol::Timer::after(delay).await;
+                    }
+                    if let Some(pane) = Mux::get().get_pane(pane_id) {
+                        pane.send_paste(&clip).ok();
+                    }
+                }
+            }
+        })
+        .detach();
+    }
 }
@@ -1,97 +1,613 @@
 use crate::termwindow::box_model::*;
-use crate::termwindow::modal::Modal;
+use crate::termwindow::menuhit::{MenuBounds, RowBounds};
+use crate::termwindow::modal::{EventPropagation, Modal};
 use crate::termwindow::render::corners::{
     BOTTOM_LEFT_ROUNDED_CORNER, BOTTOM_RIGHT_ROUNDED_CORNER, TOP_LEFT_ROUNDED_CORNER,
     TOP_RIGHT_ROUNDED_CORNER,
 };
-use crate::termwindow::TermWindow;
+use crate::termwindow::{RenderState, TermWindow, TermWindowNotif};
 use crate::utilsprites::RenderMetrics;
 use config::keyassignment::{
-    KeyAssignment, PaneSelectArguments, PaneSelectMode, RotationDirection, SpawnCommand,
-    SpawnTabDomain,
+    Axis, ClipboardCopyDestination, KeyAssignment, MenuItemDef, MenuSection, PaneDirection,
+    PaneLayout, PaneSelectArguments, PaneSelectMode, RotationDirection, ScrollbackEraseMode,
+    SpawnCommand, SpawnTabDomain, SplitPane,
 };
 use config::{Dimension, DimensionContext};
+use finl_unicode::categories::CharacterCategories;
+use mux::pane::{CachePolicy, Pane, PaneId};
+use mux::tab::{PositionedPane, PositionedSplit, SplitDirection, TabId};
+use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
 use termwiz::nerdfonts::NERD_FONTS;
-use wezterm_term::{KeyCode, KeyModifiers, MouseEvent};
+use unicode_normalization::UnicodeNormalization;
+use wezterm_font::LoadedFont;
+use wezterm_term::input::MouseButton;
+use wezterm_term::{KeyCode, KeyModifiers, MouseEvent, SemanticType, TerminalConfiguration};
 use window::color::LinearRgba;
 
+/// The glyph shown alongside the label of a menu item.
+/// It is serialized as either the nerdfont name or the glyph itself.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+enum MenuIcon {
+    /// The name of a glyph in NERD_FONTS
+    NerdFont(&'static str),
+    /// A glyph supplied directly, eg: by a Lua defined menu
+    Literal(char),
+}
+
+impl MenuIcon {
+    /// Resolves a single character to itself, and anything else
+    /// to the nerdfont of that name
+    fn from_name(icon: &str) -> Option<Self> {
+        let mut chars = icon.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(Self::Literal(c));
+        }
+        NERD_FONTS
+            .get_key_value(icon)
+            .map(|(name, _)| Self::NerdFont(*name))
+    }
+}
+
+impl TryFrom<String> for MenuIcon {
+    type Error = String;
+
+    fn try_from(icon: String) -> Result<Self, String> {
+        Self::from_name(&icon).ok_or_else(|| format!("nerdfont {icon} not found"))
+    }
+}
+
+impl From<MenuIcon> for String {
+    fn from(icon: MenuIcon) -> String {
+        match icon {
+            MenuIcon::NerdFont(name) => name.to_string(),
+            MenuIcon::Literal(c) => c.to_string(),
+        }
+    }
+}
+
+/// KeyAssignment only implements the dynamic conversions, so
+/// actions are serialized as the JSON form of their dynamic value
+mod action_serde {
+    use config::keyassignment::KeyAssignment;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use wezterm_dynamic::{FromDynamic, ToDynamic};
+
+    pub fn serialize<S: Serializer>(action: &KeyAssignment, s: S) -> Result<S::Ok, S::Error> {
+        config::dynamic_to_json(&action.to_dynamic()).serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<KeyAssignment, D::Error> {
+        let value = serde_json::Value::deserialize(d)?;
+        KeyAssignment::from_dynamic(&config::json_to_dynamic(&value), Default::default())
+            .map_err(serde::de::Error::custom)
+    }
+
+    pub mod option {
+        use config::keyassignment::KeyAssignment;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            action: &Option<KeyAssignment>,
+            s: S,
+        ) -> Result<S::Ok, S::Error> {
+            match action {
+                Some(action) => super::serialize(action, s),
+                None => None::<()>.serialize(s),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            d: D,
+        ) -> Result<Option<KeyAssignment>, D::Error> {
+            match Option::<serde_json::Value>::deserialize(d)? {
+                Some(value) => super::deserialize(value)
+                    .map(Some)
+                    .map_err(serde::de::Error::custom),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
 /// A menu item in the context menu
+#[derive(Clone, Serialize, Deserialize)]
 enum MenuItem {
     Entry {
-        label: &'static str,
-        icon: Option<&'static str>,
+        label: Cow<'static, str>,
+        icon: Option<MenuIcon>,
+        #[serde(with = "action_serde")]
         action: KeyAssignment,
+        /// A count shown in a colored circle to the right of the
+        /// label, when it is non-zero
+        #[serde(default)]
+        badge: Option<u32>,
+        /// Previewed while the entry is selected, without being
+        /// performed, to show what `action` would affect
+        #[serde(default, with = "action_serde::option")]
+        preview_action: Option<KeyAssignment>,
+    },
+    /// An entry that toggles some state; a checkmark is shown in
+    /// the icon column while the state is on, and `icon` otherwise
+    Checkmark {
+        label: Cow<'static, str>,
+        icon: Option<MenuIcon>,
+        #[serde(with = "action_serde")]
+        action: KeyAssignment,
+        checked: bool,
+    },
+    /// Opens a nested menu alongside this one
+    SubMenu {
+        label: Cow<'static, str>,
+        icon: Option<MenuIcon>,
+        items: Vec<MenuItem>,
     },
     Separator,
+    /// The labels of the sub-menu entries that were followed to get
+    /// to a sub-menu, shown at the top of it. It cannot be selected.
+    Breadcrumb(Vec<Cow<'static, str>>),
+    /// A dimmed title for the items that follow it.
+    /// It cannot be selected.
+    SectionHeader(Cow<'static, str>),
+}
+
+impl MenuItem {
+    /// An entry that performs `action`, without a badge or preview
+    fn entry(
+        label: impl Into<Cow<'static, str>>,
+        icon: Option<MenuIcon>,
+        action: KeyAssignment,
+    ) -> Self {
+        Self::Entry {
+            label: label.into(),
+            icon,
+            action,
+            badge: None,
+            preview_action: None,
+        }
+    }
+
+    /// An entry labelled `label` that does nothing, for the tests
+    #[cfg(test)]
+    fn nop_entry(label: &'static str) -> Self {
+        Self::entry(label, None, KeyAssignment::Nop)
+    }
+}
+
+/// The first label of the breadcrumb of a sub-menu
+const BREADCRUMB_ROOT: &str = "Context Menu";
+
+/// Shown in place of the icon of a checked `MenuItem::Checkmark`
+const CHECKMARK_ICON: MenuIcon = MenuIcon::NerdFont("cod_check");
+
+/// The priorities of the built-in sections of the default menu,
+/// which `context_menu.section` entries are ordered against
+const PANE_ACTIONS_PRIORITY: i32 = 100;
+const NEW_TAB_PRIORITY: i32 = 200;
+const TAB_ORDER_PRIORITY: i32 = 300;
+const COPY_PRIORITY: i32 = 400;
+const CHARACTER_INFO_PRIORITY: i32 = 500;
+const CLOSE_PANE_PRIORITY: i32 = 600;
+#[cfg(feature = "debug_menu")]
+const DEBUG_PRIORITY: i32 = 1000;
+
+/// How far either side of the right edge of the menu, in pixels at
+/// 96 dpi, that it can be grabbed to drag it wider
+const RESIZE_GRIP_WIDTH: f32 = 4.;
+
+/// Log target used to announce the highlighted menu item
+const ACCESSIBILITY_LOG_TARGET: &str = "wezterm_gui::accessibility";
+
+/// The colors and decorations that the element tree of a menu is
+/// built from, as resolved from the config and the menu theme
+struct MenuStyle {
+    bg: LinearRgba,
+    fg: LinearRgba,
+    border: LinearRgba,
+    badge_bg: LinearRgba,
+    badge_fg: LinearRgba,
+    /// The dimmer text of the breadcrumb of a sub-menu
+    breadcrumb_fg: LinearRgba,
+    /// The space above and below each row, in cells;
+    /// twice as much is left to either side of it
+    item_padding: f32,
+    /// The minimum width of each row
+    row_width: Dimension,
+    /// Measured in cells
+    corner_radius: f32,
+    shadow: Option<BoxShadow>,
+    backdrop_blur: Option<f32>,
+}
+
+impl MenuStyle {
+    fn row_colors(&self) -> RowColors {
+        RowColors {
+            normal: ElementColors {
+                border: BorderColor::default(),
+                bg: LinearRgba::TRANSPARENT.into(),
+                text: self.fg.into(),
+            },
+            selected: ElementColors {
+                border: BorderColor::default(),
+                bg: self.fg.into(),
+                text: self.bg.into(),
+            },
+        }
+    }
+}
+
+/// The fonts that the element tree of a menu is built from
+struct MenuFonts {
+    item: Rc<LoadedFont>,
+    breadcrumb: Rc<LoadedFont>,
 }
 
-/// Actual pixel bounds of a rendered row, extracted from the computed element tree
+/// The colors of a row of the menu. The icon and label of a row
+/// inherit these, so the highlight can be moved without shaping
+/// the labels again.
 #[derive(Clone, Default)]
-struct RowBounds {
-    y: f32,
-    height: f32,
+struct RowColors {
+    normal: ElementColors,
+    selected: ElementColors,
+}
+
+/// The name of each code point in `unicode_names::NAMES`; where it
+/// has aliases, the first of them, as the names are sorted
+static CHAR_NAMES: LazyLock<HashMap<u32, &'static str>> = LazyLock::new(|| {
+    let mut names = HashMap::new();
+    for (name, value) in crate::unicode_names::NAMES {
+        names.entry(*value).or_insert(*name);
+    }
+    names
+});
+
+/// Returns the name of the Unicode block that `c` is in, if any
+fn unicode_block(c: char) -> Option<&'static str> {
+    let blocks = crate::unicode_blocks::BLOCKS;
+    let c = c as u32;
+    let idx = blocks.partition_point(|(_, end, _)| *end < c);
+    match blocks.get(idx) {
+        Some((start, _, name)) if *start <= c => Some(name),
+        _ => None,
+    }
+}
+
+/// The ways in which building or laying out a context menu can fail
+#[derive(Debug, thiserror::Error)]
+pub enum ContextMenuError {
+    #[error("failed to resolve the context menu font")]
+    Font(#[source] anyhow::Error),
+    #[error("failed to lay out the context menu")]
+    Layout(#[source] anyhow::Error),
+    /// The window has no render state during shutdown, or before
+    /// the GPU context has been set up
+    #[error("render state not available")]
+    NoRenderState,
+    #[error("failed to serialize the context menu items")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// The result of laying out a menu with `ContextMenu::compute`
+struct MenuLayout {
+    element: Vec<ComputedElement>,
+    menu_x: f32,
+    menu_y: f32,
+    menu_width: f32,
+    menu_height: f32,
+    row_bounds: Vec<RowBounds>,
+    scrollbar_left: Option<f32>,
+    visible_rows: usize,
+    rows_per_column: usize,
+    row_colors: RowColors,
+    selection_animation: Option<Duration>,
 }
 
 pub struct ContextMenu {
     element: RefCell<Option<Vec<ComputedElement>>>,
     /// Currently selected/hovered row (-1 = none)
     selected_row: RefCell<i32>,
-    items: Vec<MenuItem>,
-    /// Initial mouse position (used for computing menu position)
-    initial_mouse_x: f32,
-    initial_mouse_y: f32,
+    /// Built again by reconfigure for the pane menu, as its
+    /// items depend on the configuration
+    items: RefCell<Vec<MenuItem>>,
+    /// Initial mouse position (used for computing menu position).
+    /// Re-clamped into the window when it is resized.
+    initial_mouse_x: RefCell<f32>,
+    initial_mouse_y: RefCell<f32>,
     /// Actual rendered bounds of menu and each row, set after compute
     menu_x: RefCell<f32>,
     menu_y: RefCell<f32>,
     menu_width: RefCell<f32>,
     menu_height: RefCell<f32>,
     row_bounds: RefCell<Vec<RowBounds>>,
+    /// The left edge of the scrollbar, when the menu has one
+    scrollbar_left: RefCell<Option<f32>>,
+    /// Index of the first item shown when the menu is taller than the window
+    scroll_offset: RefCell<usize>,
+    /// Number of items that fit in the window, set after compute
+    visible_rows: RefCell<usize>,
+    /// Number of rows in each column, set after compute
+    rows_per_column: RefCell<usize>,
+    /// The colors that compute gave the rows of `element`
+    row_colors: RefCell<RowColors>,
+    /// How long the highlight takes to slide to a newly selected row,
+    /// or None when it moves there at once
+    selection_animation: RefCell<Option<Duration>>,
+    /// While the highlight slides from one row to another: when it
+    /// started and the top of the row that it started from
+    highlight_start: RefCell<Option<(Instant, f32)>>,
+    /// The top of the highlight, and of the row that it is sliding to
+    highlight_y: RefCell<f32>,
+    target_y: RefCell<f32>,
+    /// Whether the right edge is being dragged, and the mouse
+    /// position that the width was last updated for
+    is_resizing: RefCell<bool>,
+    drag_start_x: RefCell<f32>,
+    /// The width that the right edge was dragged to, which is kept
+    /// when the element is computed again
+    resized_width: RefCell<Option<f32>>,
+    /// When opened from the tab bar, the tab that the actions apply to
+    target_tab: Option<TabId>,
+    /// When opened over a pane other than the active one, the pane
+    /// that the actions apply to
+    target_pane_id: Option<PaneId>,
+    /// Whether this menu was opened from a sub-menu entry of another
+    is_submenu: bool,
+    /// Whether the items were built by `ContextMenu::new`
+    is_pane_menu: bool,
+    /// The items from `context_menu_external_command`, which is run
+    /// in the background when the menu is opened; empty until it
+    /// has printed them
+    external_items: RefCell<Vec<MenuItem>>,
+    /// Identifies the run of `context_menu_external_command` whose
+    /// items belong in this menu
+    external_request: Option<usize>,
+    /// The first error from compute; while set, the menu renders
+    /// as nothing instead of failing, and logging, on every frame
+    error_state: RefCell<Option<String>>,
+    /// Whether the highlighted item is announced; set by
+    /// `TermWindow::push_modal`, and off in secure input mode
+    announce: RefCell<bool>,
 }
 
 impl ContextMenu {
-    pub fn new(term_window: &mut TermWindow, mouse_x: isize, mouse_y: isize) -> Self {
+    pub fn new(
+        term_window: &mut TermWindow,
+        mouse_x: isize,
+        mouse_y: isize,
+        target_pane_id: Option<PaneId>,
+    ) -> Self {
+        let items = Self::pane_items(term_window, target_pane_id);
+        let mut menu = Self::with_items(items, mouse_x, mouse_y, None);
+        menu.target_pane_id = target_pane_id;
+        menu.is_pane_menu = true;
+        menu.external_request = Self::start_external_command(term_window, target_pane_id);
+        menu
+    }
+
+    /// Starts `context_menu_external_command`, if it is set, on another
+    /// thread so that the menu can be shown without waiting for it.
+    /// The items that it prints are added to the menu by
+    /// `receive_external_items` once it finishes; when it fails, or
+    /// doesn't finish in time, the failure is logged and the menu
+    /// keeps just its own items.
+    /// Returns the request that the items will be delivered for.
+    fn start_external_command(
+        term_window: &TermWindow,
+        target_pane_id: Option<PaneId>,
+    ) -> Option<usize> {
+        static NEXT_REQUEST: AtomicUsize = AtomicUsize::new(0);
+
+        let command = term_window.config.context_menu_external_command.clone()?;
+        let window = term_window.window.clone()?;
+        let pane = target_pane_id
+            .and_then(|pane_id| mux::Mux::get().get_pane(pane_id))
+            .or_else(|| term_window.get_active_pane_no_overlay());
+        let context = serde_json::json!({
+            "pane_id": pane.as_ref().map(|pane| pane.pane_id()),
+            "cwd": pane
+                .as_ref()
+                .and_then(|pane| pane.get_current_working_dir(CachePolicy::AllowStale))
+                .map(|url| url.to_string()),
+            "foreground_process": pane
+                .as_ref()
+                .and_then(|pane| pane.get_foreground_process_name(CachePolicy::AllowStale)),
+        });
+        let timeout =
+            Duration::from_millis(term_window.config.context_menu_external_command_timeout_ms);
+        let request = NEXT_REQUEST.fetch_add(1, Ordering::Relaxed);
+
+        std::thread::spawn(move || {
+            let items = match Self::run_external_command(&command, &context, timeout) {
+                Ok(items) => items,
+                Err(err) => {
+                    log::warn!("context_menu_external_command: {err:#}");
+                    return;
+                }
+            };
+            promise::spawn::spawn_into_main_thread(async move {
+                window.notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+                    Self::receive_external_items(term_window, request, &items);
+                })));
+            })
+            .detach();
+        });
+        Some(request)
+    }
+
+    /// Adds the items printed for `request` to the menu that is waiting
+    /// for them; they are dropped if that menu has been closed already
+    fn receive_external_items(term_window: &mut TermWindow, request: usize, items: &[MenuItemDef]) {
+        for modal in term_window.get_modals() {
+            if let Some(menu) = modal.downcast_ref::<ContextMenu>() {
+                if menu.external_request == Some(request) {
+                    menu.add_external_items(items.iter().map(Self::item_from_def).collect());
+                    if let Some(window) = term_window.window.as_ref() {
+                        window.invalidate();
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Places `external_items` above the items of the menu, keeping
+    /// the same item selected and in view
+    fn add_external_items(&self, external_items: Vec<MenuItem>) {
+        let added = {
+            let mut items = self.items.borrow_mut();
+            let num_items = items.len();
+            *items = Self::prepend_external_items(&external_items, std::mem::take(&mut *items));
+            items.len() - num_items
+        };
+        *self.external_items.borrow_mut() = external_items;
+
+        let selected = *self.selected_row.borrow();
+        if selected >= 0 {
+            *self.selected_row.borrow_mut() = selected + added as i32;
+        }
+        let offset = *self.scroll_offset.borrow();
+        if offset > 0 {
+            *self.scroll_offset.borrow_mut() = offset + added;
+        }
+        self.element.borrow_mut().take();
+    }
+
+    fn run_external_command(
+        command: &str,
+        context: &serde_json::Value,
+        timeout: Duration,
+    ) -> anyhow::Result<Vec<MenuItemDef>> {
+        let output = super::pipe_through_command(command, &context.to_string(), Some(timeout))?;
+        MenuItemDef::list_from_json_str(&output)
+    }
+
+    /// Places the items from the external command above `items`,
+    /// with a separator between them
+    fn prepend_external_items(external_items: &[MenuItem], items: Vec<MenuItem>) -> Vec<MenuItem> {
+        if external_items.is_empty() {
+            return items;
+        }
+        external_items
+            .iter()
+            .cloned()
+            .chain(std::iter::once(MenuItem::Separator))
+            .chain(items)
+            .collect()
+    }
+
+    /// Builds the items of the menu shown over `target_pane_id`,
+    /// or over the active pane if that is None
+    fn pane_items(term_window: &mut TermWindow, target_pane_id: Option<PaneId>) -> Vec<MenuItem> {
+        // The pane that the pane specific entries describe
+        let target_pane = target_pane_id.and_then(|pane_id| mux::Mux::get().get_pane(pane_id));
+        let menu_pane = target_pane
+            .clone()
+            .or_else(|| term_window.get_active_pane_or_overlay());
+        let menu_pane_no_overlay = target_pane.or_else(|| term_window.get_active_pane_no_overlay());
+
+        // The priority of each built-in section, along with the
+        // index into items of its first item
+        let mut sections = vec![(PANE_ACTIONS_PRIORITY, 0)];
         let mut items = vec![
             // Split pane options
-            MenuItem::Entry {
-                label: "Split Pane Right",
-                icon: Some("cod_split_horizontal"),
-                action: KeyAssignment::SplitHorizontal(SpawnCommand {
+            MenuItem::entry(
+                "Split Pane Right",
+                Some(MenuIcon::NerdFont("cod_split_horizontal")),
+                KeyAssignment::SplitHorizontal(SpawnCommand {
                     domain: SpawnTabDomain::CurrentPaneDomain,
                     ..Default::default()
                 }),
-            },
-            MenuItem::Entry {
-                label: "Split Pane Down",
-                icon: Some("cod_split_vertical"),
-                action: KeyAssignment::SplitVertical(SpawnCommand {
+            ),
+            MenuItem::entry(
+                "Split Pane Down",
+                Some(MenuIcon::NerdFont("cod_split_vertical")),
+                KeyAssignment::SplitVertical(SpawnCommand {
                     domain: SpawnTabDomain::CurrentPaneDomain,
                     ..Default::default()
                 }),
-            },
+            ),
         ];
 
+        // Duplicate pane option, only if we know where the pane is so that
+        // the new pane can start in the same directory. The mux resolves
+        // the cwd from the current pane when spawning into its domain.
+        let has_cwd = menu_pane
+            .as_ref()
+            .and_then(|pane| pane.get_current_working_dir(CachePolicy::AllowStale))
+            .is_some();
+        if has_cwd {
+            items.push(MenuItem::entry(
+                "Duplicate Pane",
+                Some(MenuIcon::NerdFont("cod_copy")),
+                KeyAssignment::SplitPane(SplitPane {
+                    direction: PaneDirection::Right,
+                    size: Default::default(),
+                    command: SpawnCommand {
+                        domain: SpawnTabDomain::CurrentPaneDomain,
+                        ..Default::default()
+                    },
+                    top_level: false,
+                }),
+            ));
+        }
+
         // Add pane manipulation options if there are multiple panes
         if let Some(tab) = mux::Mux::get().get_active_tab_for_window(term_window.mux_window_id) {
             if tab.count_panes().unwrap_or(1) > 1 {
                 items.push(MenuItem::Separator);
-                items.push(MenuItem::Entry {
-                    label: "Swap Pane Up",
-                    icon: Some("cod_arrow_up"),
-                    action: KeyAssignment::RotatePanes(RotationDirection::CounterClockwise),
-                });
-                items.push(MenuItem::Entry {
-                    label: "Swap Pane Down",
-                    icon: Some("cod_arrow_down"),
-                    action: KeyAssignment::RotatePanes(RotationDirection::Clockwise),
-                });
-                items.push(MenuItem::Entry {
-                    label: "Select Pane to Swap",
-                    icon: Some("cod_replace"),
-                    action: KeyAssignment::PaneSelect(PaneSelectArguments {
+                items.push(MenuItem::entry(
+                    "Swap Pane Up",
+                    Some(MenuIcon::NerdFont("cod_arrow_up")),
+                    KeyAssignment::RotatePanes(RotationDirection::CounterClockwise),
+                ));
+                items.push(MenuItem::entry(
+                    "Swap Pane Down",
+                    Some(MenuIcon::NerdFont("cod_arrow_down")),
+                    KeyAssignment::RotatePanes(RotationDirection::Clockwise),
+                ));
+                items.push(MenuItem::entry(
+                    "Select Pane to Swap",
+                    Some(MenuIcon::NerdFont("cod_replace")),
+                    KeyAssignment::PaneSelect(PaneSelectArguments {
                         mode: PaneSelectMode::SwapWithActiveKeepFocus,
                         ..Default::default()
                     }),
+                ));
+                items.push(MenuItem::entry(
+                    "Paste to All Panes",
+                    Some(MenuIcon::NerdFont("cod_broadcast")),
+                    KeyAssignment::PasteToAllPanes,
+                ));
+
+                let source_pane_id =
+                    target_pane_id.or_else(|| tab.get_active_pane().map(|pane| pane.pane_id()));
+                let targets: Vec<MenuItem> = tab
+                    .iter_panes_ignoring_zoom()
+                    .into_iter()
+                    .filter(|pos| Some(pos.pane.pane_id()) != source_pane_id)
+                    .map(|pos| {
+                        MenuItem::entry(
+                            format!("{}: {}", pos.index, pos.pane.get_title()),
+                            Some(MenuIcon::NerdFont("cod_terminal")),
+                            KeyAssignment::SendInputToPane {
+                                pane_id: pos.pane.pane_id(),
+                            },
+                        )
+                    })
+                    .collect();
+                items.push(MenuItem::SubMenu {
+                    label: "Send Input To…".into(),
+                    icon: Some(MenuIcon::NerdFont("cod_send")),
+                    items: targets,
                 });
             }
         }
@@ -100,63 +616,1003 @@ impl ContextMenu {
         if let Some(tab) = mux::Mux::get().get_active_tab_for_window(term_window.mux_window_id) {
             if tab.count_panes().unwrap_or(1) > 1 {
                 items.push(MenuItem::Separator);
-                items.push(MenuItem::Entry {
-                    label: "Toggle Zoom",
-                    icon: Some("cod_screen_full"),
+                items.push(MenuItem::Checkmark {
+                    label: "Toggle Zoom".into(),
+                    icon: Some(MenuIcon::NerdFont("cod_screen_full")),
                     action: KeyAssignment::TogglePaneZoomState,
+                    checked: tab.get_zoomed_pane().is_some(),
+                });
+                items.push(MenuItem::entry(
+                    "Maximize Pane Height",
+                    Some(MenuIcon::NerdFont("cod_unfold")),
+                    KeyAssignment::MaximizePaneInDirection {
+                        direction: Axis::Vertical,
+                    },
+                ));
+                items.push(MenuItem::entry(
+                    "Maximize Pane Width",
+                    Some(MenuIcon::NerdFont("cod_arrow_both")),
+                    KeyAssignment::MaximizePaneInDirection {
+                        direction: Axis::Horizontal,
+                    },
+                ));
+                items.push(MenuItem::SubMenu {
+                    label: "Auto-tile Panes".into(),
+                    icon: Some(MenuIcon::NerdFont("cod_layout")),
+                    items: vec![
+                        MenuItem::entry(
+                            "Even Horizontal",
+                            Some(MenuIcon::NerdFont("cod_layout_panel_center")),
+                            KeyAssignment::ApplyPaneLayout(PaneLayout::EvenHorizontal),
+                        ),
+                        MenuItem::entry(
+                            "Even Vertical",
+                            Some(MenuIcon::NerdFont("cod_layout_centered")),
+                            KeyAssignment::ApplyPaneLayout(PaneLayout::EvenVertical),
+                        ),
+                        MenuItem::entry(
+                            "Main-Horizontal",
+                            Some(MenuIcon::NerdFont("cod_layout_panel")),
+                            KeyAssignment::ApplyPaneLayout(PaneLayout::MainHorizontal),
+                        ),
+                        MenuItem::entry(
+                            "Main-Vertical",
+                            Some(MenuIcon::NerdFont("cod_layout_sidebar_left")),
+                            KeyAssignment::ApplyPaneLayout(PaneLayout::MainVertical),
+                        ),
+                        MenuItem::entry(
+                            "Tiled Grid",
+                            Some(MenuIcon::NerdFont("cod_layout")),
+                            KeyAssignment::ApplyPaneLayout(PaneLayout::Tiled),
+                        ),
+                    ],
                 });
             }
         }
 
-        // New tab/window options
-        items.push(MenuItem::Separator);
-        items.push(MenuItem::Entry {
-            label: "New Tab",
-            icon: Some("cod_add"),
-            action: KeyAssignment::SpawnTab(SpawnTabDomain::CurrentPaneDomain),
+        // Recently focused panes, most recent first
+        let active_pane_id = term_window
+            .get_active_pane_no_overlay()
+            .map(|pane| pane.pane_id());
+        let history: Vec<MenuItem> = term_window
+            .recently_focused_panes()
+            .into_iter()
+            .filter_map(|pane_id| {
+                let pane = mux::Mux::get().get_pane(pane_id)?;
+                Some(MenuItem::entry(
+                    format!("{pane_id}: {}", pane.get_title()),
+                    if Some(pane_id) == active_pane_id {
+                        Some(MenuIcon::NerdFont("cod_check"))
+                    } else {
+                        Some(MenuIcon::NerdFont("cod_terminal"))
+                    },
+                    KeyAssignment::ActivatePaneById { pane_id },
+                ))
+            })
+            .collect();
+        if history.len() > 1 {
+            items.push(MenuItem::entry(
+                "Previous Pane",
+                Some(MenuIcon::NerdFont("cod_history")),
+                KeyAssignment::FocusPreviousPaneInHistory,
+            ));
+            items.push(MenuItem::SubMenu {
+                label: "Pane History".into(),
+                icon: Some(MenuIcon::NerdFont("cod_history")),
+                items: history,
+            });
+        }
+
+        if let Some(pane) = &menu_pane_no_overlay {
+            let row = term_window.flag_target_row(&pane);
+            let flagged = term_window.flagged_lines(pane.pane_id());
+            items.push(MenuItem::Entry {
+                label: if flagged.contains(&row) {
+                    "Unflag This Line".into()
+                } else {
+                    "Flag This Line".into()
+                },
+                icon: Some(MenuIcon::NerdFont("md_flag")),
+                action: KeyAssignment::FlagCurrentLine,
+                // How many lines of the pane are flagged
+                badge: Some(flagged.len() as u32),
+                preview_action: None,
+            });
+            items.push(MenuItem::Entry {
+                label: "Toggle Column Ruler at Cursor".into(),
+                icon: Some(MenuIcon::NerdFont("md_ruler")),
+                action: KeyAssignment::ToggleColumnRuler,
+                badge: Some(term_window.column_rulers(pane.pane_id()).len() as u32),
+                preview_action: None,
+            });
+
+            let mouse_disabled = term_window.is_pane_mouse_reporting_disabled(pane.pane_id());
+            items.push(MenuItem::Checkmark {
+                label: "Disable Mouse Reporting (this pane)".into(),
+                icon: Some(MenuIcon::NerdFont("md_mouse_off")),
+                action: KeyAssignment::SetPaneMouseReporting {
+                    enabled: mouse_disabled,
+                },
+                checked: mouse_disabled,
+            });
+        }
+
+        // How the pane and the menu look, and moving around the pane
+        let mut view_items = vec![];
+        view_items.push(MenuItem::entry(
+            "Filter View…",
+            Some(MenuIcon::NerdFont("cod_filter")),
+            KeyAssignment::ShowScrollbackFilter,
+        ));
+        // Fixed scroll distances, for those navigating with the mouse
+        let mut scroll_items: Vec<MenuItem> = [
+            ("Up 10 Lines", "cod_arrow_up", -10),
+            ("Down 10 Lines", "cod_arrow_down", 10),
+            ("Up 100 Lines", "md_chevron_double_up", -100),
+            ("Down 100 Lines", "md_chevron_double_down", 100),
+        ]
+        .iter()
+        .map(|&(label, icon, lines)| {
+            MenuItem::entry(
+                label,
+                Some(MenuIcon::NerdFont(icon)),
+                KeyAssignment::ScrollByLine(lines),
+            )
+        })
+        .collect();
+        scroll_items.push(MenuItem::entry(
+            "To Last Output",
+            Some(MenuIcon::NerdFont("md_format_vertical_align_bottom")),
+            KeyAssignment::ScrollToBottom,
+        ));
+        view_items.push(MenuItem::SubMenu {
+            label: "Scroll".into(),
+            icon: Some(MenuIcon::NerdFont("md_arrow_up_down")),
+            items: scroll_items,
         });
-        items.push(MenuItem::Entry {
-            label: "New Window",
-            icon: Some("cod_window"),
-            action: KeyAssignment::SpawnWindow,
+
+        // Semantic zones are only present when shell integration is set up,
+        // so only offer to jump between them when the pane has some
+        let has_zones = menu_pane_no_overlay.as_ref().map_or(false, |pane| {
+            pane.get_semantic_zones()
+                .map_or(false, |zones| !zones.is_empty())
         });
+        if has_zones {
+            view_items.push(MenuItem::SubMenu {
+                label: "Jump to Zone".into(),
+                icon: Some(MenuIcon::NerdFont("md_format_vertical_align_center")),
+                items: [
+                    ("Next Output", "cod_arrow_down", SemanticType::Output, 1),
+                    ("Previous Output", "cod_arrow_up", SemanticType::Output, -1),
+                    ("Next Prompt", "cod_chevron_down", SemanticType::Prompt, 1),
+                    (
+                        "Previous Prompt",
+                        "cod_chevron_up",
+                        SemanticType::Prompt,
+                        -1,
+                    ),
+                ]
+                .iter()
+                .map(|&(label, icon, zone_type, amount)| {
+                    MenuItem::entry(
+                        label,
+                        Some(MenuIcon::NerdFont(icon)),
+                        KeyAssignment::ScrollToSemanticZone { zone_type, amount },
+                    )
+                })
+                .collect(),
+            });
+        }
 
-        // Tab reordering options
-        items.push(MenuItem::Separator);
-        items.push(MenuItem::Entry {
-            label: "Move Tab Left",
-            icon: Some("cod_arrow_left"),
-            action: KeyAssignment::MoveTabRelative(-1),
+        // Cursor blink rate, where each rate is the time between
+        // the cursor being shown and being hidden again
+        let blink_rate = term_window.config.cursor_blink_rate;
+        view_items.push(MenuItem::SubMenu {
+            label: "Cursor Blink".into(),
+            icon: Some(MenuIcon::NerdFont("md_cursor_text")),
+            items: [
+                ("No Blink", 0),
+                ("Slow (1 Hz)", 500),
+                ("Normal (2 Hz)", 250),
+                ("Fast (4 Hz)", 125),
+            ]
+            .iter()
+            .map(|&(label, rate_ms)| {
+                MenuItem::entry(
+                    label,
+                    if blink_rate == u64::from(rate_ms) {
+                        Some(MenuIcon::NerdFont("cod_check"))
+                    } else {
+                        None
+                    },
+                    KeyAssignment::SetCursorBlinkRate { rate_ms },
+                )
+            })
+            .collect(),
+        });
+
+        view_items.push(MenuItem::entry(
+            if term_window.is_dim_on_unfocus() {
+                "Dim When Unfocused (on)"
+            } else {
+                "Dim When Unfocused"
+            },
+            Some(MenuIcon::NerdFont("md_opacity")),
+            KeyAssignment::ToggleDimOnUnfocus,
+        ));
+
+        // Switch between the opacities that have been used in this window
+        let opacities = term_window.recent_opacities();
+        if !opacities.is_empty() {
+            let current = term_window.config.window_background_opacity;
+            view_items.push(MenuItem::SubMenu {
+                label: "Recent Opacity".into(),
+                icon: Some(MenuIcon::NerdFont("md_opacity")),
+                items: opacities
+                    .into_iter()
+                    .filter_map(|opacity| NotNan::new(f64::from(opacity)).ok())
+                    .map(|opacity| {
+                        MenuItem::entry(
+                            format!("{:.0}%", *opacity * 100.),
+                            if *opacity as f32 == current {
+                                Some(MenuIcon::NerdFont("cod_check"))
+                            } else {
+                                None
+                            },
+                            KeyAssignment::SetWindowOpacity(opacity),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+
+        let current_theme = crate::menutheme::current_theme_name();
+        view_items.push(MenuItem::SubMenu {
+            label: "Menu Theme".into(),
+            icon: Some(MenuIcon::NerdFont("md_palette")),
+            items: crate::menutheme::THEME_NAMES
+                .iter()
+                .map(|&name| {
+                    MenuItem::entry(
+                        name,
+                        if name == current_theme {
+                            Some(MenuIcon::NerdFont("cod_check"))
+                        } else {
+                            None
+                        },
+                        KeyAssignment::SetContextMenuTheme(name.to_string()),
+                    )
+                })
+                .collect(),
+        });
+        items.push(MenuItem::SubMenu {
+            label: "View".into(),
+            icon: Some(MenuIcon::NerdFont("cod_eye")),
+            items: view_items,
+        });
+
+        // New tab/window options
+        sections.push((NEW_TAB_PRIORITY, items.len()));
+        items.push(MenuItem::entry(
+            "New Tab",
+            Some(MenuIcon::NerdFont("cod_add")),
+            KeyAssignment::SpawnTab(SpawnTabDomain::CurrentPaneDomain),
+        ));
+        if !term_window.config.tab_templates.is_empty() {
+            items.push(MenuItem::SubMenu {
+                label: "New Tab from Template".into(),
+                icon: Some(MenuIcon::NerdFont("cod_layout")),
+                items: term_window
+                    .config
+                    .tab_templates
+                    .iter()
+                    .map(|template| {
+                        MenuItem::entry(
+                            template.name.clone(),
+                            None,
+                            KeyAssignment::SpawnTabFromTemplate {
+                                name: template.name.clone(),
+                            },
+                        )
+                    })
+                    .collect(),
+            });
+        }
+        // Settings and sharing of this window
+        let mut window_items = vec![];
+        window_items.push(MenuItem::entry(
+            "New Window",
+            Some(MenuIcon::NerdFont("cod_window")),
+            KeyAssignment::SpawnWindow,
+        ));
+        // Workspace renaming, only for workspaces that the user named
+        let workspace = mux::Mux::get()
+            .get_window(term_window.mux_window_id)
+            .map(|window| window.get_workspace().to_string());
+        let default_workspace = term_window
+            .config
+            .default_workspace
+            .as_deref()
+            .unwrap_or(mux::DEFAULT_WORKSPACE);
+        if workspace.map_or(false, |name| name != default_workspace) {
+            window_items.push(MenuItem::entry(
+                "Rename Workspace…",
+                Some(MenuIcon::NerdFont("cod_edit")),
+                KeyAssignment::PromptRenameWorkspace,
+            ));
+        }
+
+        window_items.push(MenuItem::Checkmark {
+            label: "Lock Window Position".into(),
+            icon: Some(MenuIcon::NerdFont("md_lock")),
+            action: KeyAssignment::ToggleWindowPositionLock,
+            checked: term_window.is_window_position_locked(),
+        });
+        window_items.push(MenuItem::Checkmark {
+            label: "Performance Mode".into(),
+            icon: Some(MenuIcon::NerdFont("md_speedometer")),
+            action: KeyAssignment::TogglePerformanceMode,
+            checked: term_window.is_performance_mode(),
+        });
+        window_items.push(MenuItem::entry(
+            if term_window.is_secure_input() {
+                "Secure Input Mode (on)"
+            } else {
+                "Secure Input Mode"
+            },
+            Some(MenuIcon::NerdFont(if term_window.is_secure_input() {
+                "cod_lock"
+            } else {
+                "cod_unlock"
+            })),
+            KeyAssignment::ToggleSecureInput,
+        ));
+        if term_window.is_read_only_share_active() {
+            window_items.push(MenuItem::entry(
+                "Stop Sharing Window",
+                Some(MenuIcon::NerdFont("cod_debug_stop")),
+                KeyAssignment::StopReadOnlyShare,
+            ));
+        } else {
+            window_items.push(MenuItem::entry(
+                "Share Window Locally (read-only)…",
+                Some(MenuIcon::NerdFont("cod_live_share")),
+                KeyAssignment::StartReadOnlyShare,
+            ));
+        }
+        items.push(MenuItem::SubMenu {
+            label: "Window".into(),
+            icon: Some(MenuIcon::NerdFont("md_application_outline")),
+            items: window_items,
         });
-        items.push(MenuItem::Entry {
-            label: "Move Tab Right",
-            icon: Some("cod_arrow_right"),
-            action: KeyAssignment::MoveTabRelative(1),
+
+        // Less frequently used tools
+        let mut tools_items = vec![];
+        tools_items.push(MenuItem::entry(
+            "SSH to Host…",
+            Some(MenuIcon::NerdFont("md_ssh")),
+            KeyAssignment::PromptSshHost,
+        ));
+        tools_items.push(MenuItem::entry(
+            "Snapshot Pane",
+            Some(MenuIcon::NerdFont("md_camera")),
+            KeyAssignment::SnapshotPane,
+        ));
+        let snapshots = term_window.pane_snapshots();
+        if !snapshots.is_empty() {
+            tools_items.push(MenuItem::SubMenu {
+                label: "Restore Snapshot".into(),
+                icon: Some(MenuIcon::NerdFont("md_camera")),
+                items: snapshots
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, snapshot)| {
+                        MenuItem::entry(
+                            format!("{} ({})", snapshot.name, snapshot.taken.format("%H:%M:%S")),
+                            None,
+                            KeyAssignment::RestorePaneSnapshot(idx),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+
+        tools_items.push(MenuItem::entry(
+            "Clear Scrollback",
+            Some(MenuIcon::NerdFont("cod_clear_all")),
+            KeyAssignment::ClearScrollback(ScrollbackEraseMode::ScrollbackOnly),
+        ));
+        // Grow or shrink the scrollback of the pane
+        if let Some(pane) = &menu_pane_no_overlay {
+            let lines = pane
+                .get_config()
+                .map(|config| config.scrollback_size())
+                .unwrap_or(term_window.config.scrollback_lines);
+            let increased = lines.saturating_mul(2).min(config::MAX_SCROLLBACK_LINES);
+            let decreased = lines / 2;
+            let mut scrollback = vec![];
+            if increased > lines {
+                scrollback.push(MenuItem::entry(
+                    format!("Increase to {} lines", format_count(increased)),
+                    Some(MenuIcon::NerdFont("cod_add")),
+                    KeyAssignment::SetScrollbackLines { lines: increased },
+                ));
+            }
+            if decreased < lines {
+                scrollback.push(MenuItem::entry(
+                    format!("Decrease to {} lines", format_count(decreased)),
+                    Some(MenuIcon::NerdFont("cod_remove")),
+                    KeyAssignment::SetScrollbackLines { lines: decreased },
+                ));
+            }
+            tools_items.push(MenuItem::SubMenu {
+                label: format!("Scrollback: {} lines", format_count(lines)).into(),
+                icon: Some(MenuIcon::NerdFont("md_format_line_spacing")),
+                items: scrollback,
+            });
+        }
+
+        tools_items.push(MenuItem::entry(
+            "Export Effective Config…",
+            Some(MenuIcon::NerdFont("cod_settings_gear")),
+            KeyAssignment::ExportEffectiveConfig,
+        ));
+        tools_items.push(MenuItem::entry(
+            "Health Check",
+            Some(MenuIcon::NerdFont("md_stethoscope")),
+            KeyAssignment::RunHealthCheck,
+        ));
+        if term_window.config.debug_mode {
+            tools_items.push(MenuItem::entry(
+                if term_window.is_showing_font_metrics() {
+                    "Font Metrics Overlay (on)"
+                } else {
+                    "Font Metrics Overlay"
+                },
+                Some(MenuIcon::NerdFont("md_format_font")),
+                KeyAssignment::ShowFontMetricsOverlay,
+            ));
+            tools_items.push(MenuItem::entry(
+                "Show Glyph Atlas",
+                Some(MenuIcon::NerdFont("md_grid")),
+                KeyAssignment::ShowGlyphAtlas,
+            ));
+        }
+
+        items.push(MenuItem::SubMenu {
+            label: "Tools".into(),
+            icon: Some(MenuIcon::NerdFont("cod_tools")),
+            items: tools_items,
         });
 
+        // Tab reordering options
+        sections.push((TAB_ORDER_PRIORITY, items.len()));
+        items.push(MenuItem::entry(
+            "Move Tab Left",
+            Some(MenuIcon::NerdFont("cod_arrow_left")),
+            KeyAssignment::MoveTabRelative(-1),
+        ));
+        items.push(MenuItem::entry(
+            "Move Tab Right",
+            Some(MenuIcon::NerdFont("cod_arrow_right")),
+            KeyAssignment::MoveTabRelative(1),
+        ));
+
+        // Copy options when there is a selection
+        sections.push((COPY_PRIORITY, items.len()));
+        if let Some(pane) = &menu_pane {
+            if term_window.selection(pane.pane_id()).range.is_some() {
+                items.push(MenuItem::entry(
+                    "Copy as Rich Text",
+                    Some(MenuIcon::NerdFont("md_content_copy")),
+                    KeyAssignment::CopyAsHtmlTo(ClipboardCopyDestination::Clipboard),
+                ));
+                // Only offered when the selection looks like code
+                if term_window.selection_language(pane).is_some() {
+                    items.push(MenuItem::entry(
+                        "Copy with Syntax Highlighting (HTML)",
+                        Some(MenuIcon::NerdFont("md_code_braces")),
+                        KeyAssignment::CopyAsHighlightedHtmlTo(ClipboardCopyDestination::Clipboard),
+                    ));
+                }
+                items.push(MenuItem::entry(
+                    "Pipe Selection to…",
+                    Some(MenuIcon::NerdFont("cod_terminal_bash")),
+                    KeyAssignment::PipeSelectionToCommand,
+                ));
+            }
+        }
+        items.push(MenuItem::entry(
+            "Copy Visible Text",
+            Some(MenuIcon::NerdFont("md_content_copy")),
+            KeyAssignment::CopyVisibleText,
+        ));
+        items.push(MenuItem::entry(
+            "Paste with Prefix…",
+            Some(MenuIcon::NerdFont("md_content_paste")),
+            KeyAssignment::PasteWithPrefix,
+        ));
+        if menu_pane_no_overlay
+            .as_ref()
+            .and_then(super::partial_command_at_cursor)
+            .is_some()
+        {
+            items.push(MenuItem::entry(
+                "Complete Command",
+                Some(MenuIcon::NerdFont("md_auto_fix")),
+                KeyAssignment::CompleteCommand,
+            ));
+        }
+
+        // Describe the character under the mouse
+        sections.push((CHARACTER_INFO_PRIORITY, items.len()));
+        if let Some((label, info)) = menu_pane
+            .as_ref()
+            .and_then(|pane| Self::character_info_at_mouse(term_window, pane))
+        {
+            items.push(MenuItem::entry(
+                label,
+                Some(MenuIcon::NerdFont("cod_symbol_string")),
+                KeyAssignment::CopyTextTo {
+                    text: info,
+                    destination: ClipboardCopyDestination::ClipboardAndPrimarySelection,
+                },
+            ));
+        }
+
         // Close pane option if there are multiple panes
+        sections.push((CLOSE_PANE_PRIORITY, items.len()));
         if let Some(tab) = mux::Mux::get().get_active_tab_for_window(term_window.mux_window_id) {
-            if tab.count_panes().unwrap_or(1) > 1 {
-                items.push(MenuItem::Separator);
+            let num_panes = tab.count_panes().unwrap_or(1);
+            if num_panes > 1 {
                 items.push(MenuItem::Entry {
-                    label: "Close Pane",
-                    icon: Some("cod_close"),
+                    label: "Close Pane".into(),
+                    icon: Some(MenuIcon::NerdFont("cod_close")),
                     action: KeyAssignment::CloseCurrentPane { confirm: false },
+                    badge: None,
+                    preview_action: Some(KeyAssignment::CloseCurrentPane { confirm: false }),
                 });
             }
+            // With two panes this is the same as closing the other one
+            if num_panes > 2 {
+                items.push(MenuItem::entry(
+                    "Close All Other Panes",
+                    Some(MenuIcon::NerdFont("cod_close_all")),
+                    KeyAssignment::CloseAllOtherPanes,
+                ));
+            }
+        }
+
+        #[cfg(feature = "debug_menu")]
+        {
+            sections.push((DEBUG_PRIORITY, items.len()));
+            items.push(MenuItem::entry(
+                "Toggle Software Renderer",
+                Some(MenuIcon::NerdFont("cod_chip")),
+                KeyAssignment::ToggleSoftwareRenderer,
+            ));
+        }
+
+        Self::interleave_sections(
+            items,
+            &sections,
+            term_window.config.context_menu.sorted_sections(),
+        )
+    }
+
+    /// Discards the items and builds them again, so that a reloaded
+    /// configuration is reflected in the menu that is already open.
+    /// Only the pane menu is rebuilt; the others have items that
+    /// don't depend on the configuration, or that were supplied
+    /// by the caller and cannot be built again.
+    fn rebuild_items(&self, term_window: &mut TermWindow) {
+        if !self.is_pane_menu {
+            return;
+        }
+        let num_items = {
+            let new_items = Self::prepend_external_items(
+                &self.external_items.borrow(),
+                Self::pane_items(term_window, self.target_pane_id),
+            );
+            let mut items = self.items.borrow_mut();
+            *items = new_items;
+            items.len()
+        };
+
+        // Keep the selection and scroll position within the new items
+        if !self.is_selectable(*self.selected_row.borrow()) {
+            *self.selected_row.borrow_mut() = -1;
+        }
+        let offset = *self.scroll_offset.borrow();
+        *self.scroll_offset.borrow_mut() = offset.min(num_items.saturating_sub(1));
+    }
+
+    /// Builds the menu that would be shown over `pane_id`, without
+    /// showing it, and returns its items as JSON
+    pub fn describe(
+        term_window: &mut TermWindow,
+        pane_id: PaneId,
+    ) -> Result<String, ContextMenuError> {
+        let items = Self::pane_items(term_window, Some(pane_id));
+        Ok(serde_json::to_string(&items)?)
+    }
+
+    /// Splits `items` into the built-in sections that start at the
+    /// indices in `builtin`, and merges in `user_sections`, which must
+    /// be sorted by priority. A built-in section comes before a user
+    /// section of the same priority. Empty sections are dropped and
+    /// the rest are separated from each other.
+    fn interleave_sections(
+        mut items: Vec<MenuItem>,
+        builtin: &[(i32, usize)],
+        user_sections: Vec<MenuSection>,
+    ) -> Vec<MenuItem> {
+        let mut sections = vec![];
+        for &(priority, start) in builtin.iter().rev() {
+            sections.push((priority, items.split_off(start.min(items.len()))));
+        }
+        sections.reverse();
+
+        let mut user_sections = user_sections.into_iter().peekable();
+        let mut result = vec![];
+        let mut append = |section: Vec<MenuItem>| {
+            let mut section = section
+                .into_iter()
+                .skip_while(|item| matches!(item, MenuItem::Separator))
+                .peekable();
+            if section.peek().is_some() {
+                if !result.is_empty() {
+                    result.push(MenuItem::Separator);
+                }
+                result.extend(section);
+            }
+        };
+        for (priority, section) in sections {
+            while let Some(user) = user_sections.next_if(|user| user.priority < priority) {
+                append(user.items.iter().map(Self::item_from_def).collect());
+            }
+            append(section);
+        }
+        for user in user_sections {
+            append(user.items.iter().map(Self::item_from_def).collect());
+        }
+        result
+    }
+
+    /// Returns a label and a description of the character in the cell
+    /// under the mouse, which is the first character of the grapheme
+    /// in that cell.  The description lists each of its code points
+    /// along with their names, categories and blocks, and its
+    /// normalized forms.
+    fn character_info_at_mouse(
+        term_window: &TermWindow,
+        pane: &Arc<dyn Pane>,
+    ) -> Option<(String, String)> {
+        let (position, stable_row) = term_window
+            .pane_state(pane.pane_id())
+            .mouse_terminal_coords?;
+        let (_, lines) = pane.get_lines(stable_row..stable_row + 1);
+        let text = lines.get(0)?.get_cell(position.column)?.str().to_string();
+        let first = text.chars().next()?;
+        if first.is_whitespace() {
+            return None;
+        }
+
+        fn describe(c: char) -> String {
+            let name = CHAR_NAMES.get(&(c as u32)).copied().unwrap_or("<unnamed>");
+            format!(
+                "U+{:04X} {} ({:?}) in {}",
+                c as u32,
+                name,
+                c.get_minor_category(),
+                unicode_block(c).unwrap_or("No Block")
+            )
+        }
+
+        fn code_points(s: &str) -> String {
+            s.chars()
+                .map(|c| format!("U+{:04X}", c as u32))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+
+        let mut info: Vec<String> = text.chars().map(describe).collect();
+        info.push(format!(
+            "NFC: {}",
+            code_points(&text.nfc().collect::<String>())
+        ));
+        info.push(format!(
+            "NFD: {}",
+            code_points(&text.nfd().collect::<String>())
+        ));
+
+        Some((
+            format!("Character Info: U+{:04X}", first as u32),
+            info.join("\n"),
+        ))
+    }
+
+    /// Build the menu shown when right-clicking on a tab in the tab bar
+    pub fn for_tab(
+        term_window: &mut TermWindow,
+        tab_id: TabId,
+        mouse_x: isize,
+        mouse_y: isize,
+    ) -> Self {
+        let (tab_idx, num_tabs) = mux::Mux::get()
+            .get_window(term_window.mux_window_id)
+            .map(|window| (window.idx_by_id(tab_id).unwrap_or(0), window.len()))
+            .unwrap_or((0, 1));
+        let auto_name = mux::Mux::get()
+            .get_tab(tab_id)
+            .map(|tab| tab.get_auto_name())
+            .unwrap_or(false);
+
+        let mut items = vec![
+            MenuItem::entry(
+                "Rename Tab",
+                Some(MenuIcon::NerdFont("cod_edit")),
+                KeyAssignment::PromptRenameTab,
+            ),
+            MenuItem::entry(
+                "Auto-name Tab",
+                // Show a checkmark while auto-naming is enabled
+                if auto_name {
+                    Some(MenuIcon::NerdFont("cod_check"))
+                } else {
+                    None
+                },
+                KeyAssignment::ToggleAutoNameTab,
+            ),
+            MenuItem::Separator,
+        ];
+
+        if tab_idx > 0 {
+            items.push(MenuItem::entry(
+                "Move Tab Left",
+                Some(MenuIcon::NerdFont("cod_arrow_left")),
+                KeyAssignment::MoveTabRelative(-1),
+            ));
+        }
+        if tab_idx + 1 < num_tabs {
+            items.push(MenuItem::entry(
+                "Move Tab Right",
+                Some(MenuIcon::NerdFont("cod_arrow_right")),
+                KeyAssignment::MoveTabRelative(1),
+            ));
+        }
+
+        items.push(MenuItem::entry(
+            "Duplicate Tab",
+            Some(MenuIcon::NerdFont("cod_copy")),
+            KeyAssignment::SpawnTab(SpawnTabDomain::CurrentPaneDomain),
+        ));
+        items.push(MenuItem::Separator);
+        items.push(MenuItem::entry(
+            "Close Tab",
+            Some(MenuIcon::NerdFont("cod_close")),
+            KeyAssignment::CloseCurrentTab { confirm: true },
+        ));
+
+        Self::with_items(items, mouse_x, mouse_y, Some(tab_id))
+    }
+
+    /// Build the menu shown when right-clicking on the empty part of
+    /// the tab bar, listing every tab so that tabs whose titles have
+    /// been squeezed down to fit the bar can be picked by name
+    pub fn for_tab_list(term_window: &mut TermWindow, mouse_x: isize, mouse_y: isize) -> Self {
+        let mux = mux::Mux::get();
+        let mut items: Vec<MenuItem> = match mux.get_window(term_window.mux_window_id) {
+            Some(window) => {
+                let active_idx = window.get_active_idx();
+                window
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, tab)| {
+                        let mut title = tab.get_title();
+                        if title.is_empty() {
+                            if let Some(pane) = tab.get_active_pane() {
+                                title = pane.get_title();
+                            }
+                        }
+                        MenuItem::entry(
+                            format!("{}: {title}", idx + 1),
+                            if idx == active_idx {
+                                Some(MenuIcon::NerdFont("cod_check"))
+                            } else {
+                                None
+                            },
+                            KeyAssignment::ActivateTab(idx as isize),
+                        )
+                    })
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        items.push(MenuItem::Separator);
+        items.push(MenuItem::entry(
+            "Tab Gallery",
+            Some(MenuIcon::NerdFont("md_view_grid_outline")),
+            KeyAssignment::ShowTabGallery,
+        ));
+
+        Self::with_items(items, mouse_x, mouse_y, None)
+    }
+
+    /// Build the menu shown when right-clicking on the divider of `split`
+    pub fn for_split(
+        term_window: &mut TermWindow,
+        split: &PositionedSplit,
+        mouse_x: isize,
+        mouse_y: isize,
+    ) -> Self {
+        let panes = mux::Mux::get()
+            .get_active_tab_for_window(term_window.mux_window_id)
+            .map(|tab| tab.iter_panes())
+            .unwrap_or_default();
+        let (before, after) = Self::split_neighbours(split, &panes);
+
+        let mut items = vec![MenuItem::entry(
+            "Resize Pane…",
+            Some(MenuIcon::NerdFont(match split.direction {
+                SplitDirection::Horizontal => "md_arrow_left_right",
+                SplitDirection::Vertical => "md_arrow_up_down",
+            })),
+            KeyAssignment::StartSplitDrag(split.index),
+        )];
+
+        // The other actions apply to the pane on the left or top side
+        let mut target_pane_id = None;
+        if let (Some(before), Some(after)) = (before, after) {
+            target_pane_id = Some(before.pane.pane_id());
+            items.push(MenuItem::entry(
+                "Swap Adjacent Panes",
+                Some(MenuIcon::NerdFont("cod_arrow_swap")),
+                KeyAssignment::SwapActivePaneWithIndex(after.index),
+            ));
+            items.push(MenuItem::Separator);
+            items.push(MenuItem::Entry {
+                label: "Remove Split (Close Pane)".into(),
+                icon: Some(MenuIcon::NerdFont("cod_close")),
+                action: KeyAssignment::CloseCurrentPane { confirm: false },
+                badge: None,
+                preview_action: Some(KeyAssignment::CloseCurrentPane { confirm: false }),
+            });
+        }
+
+        let mut menu = Self::with_items(items, mouse_x, mouse_y, None);
+        menu.target_pane_id = target_pane_id;
+        menu
+    }
+
+    /// Returns the panes that border `split` on its left or top side,
+    /// and on its right or bottom side. Where several panes border the
+    /// same side, the one nearest the top or left end of it is returned.
+    fn split_neighbours<'a>(
+        split: &PositionedSplit,
+        panes: &'a [PositionedPane],
+    ) -> (Option<&'a PositionedPane>, Option<&'a PositionedPane>) {
+        let (start, divider) = match split.direction {
+            SplitDirection::Horizontal => (split.top, split.left),
+            SplitDirection::Vertical => (split.left, split.top),
+        };
+        let mut before: Option<(usize, &PositionedPane)> = None;
+        let mut after: Option<(usize, &PositionedPane)> = None;
+        for pane in panes {
+            let (along, offset, size) = match split.direction {
+                SplitDirection::Horizontal => (pane.top, pane.left, pane.width),
+                SplitDirection::Vertical => (pane.left, pane.top, pane.height),
+            };
+            if along < start || along >= start + split.size {
+                continue;
+            }
+            let side = if offset + size == divider {
+                &mut before
+            } else if offset == divider + 1 {
+                &mut after
+            } else {
+                continue;
+            };
+            if side.map_or(true, |(nearest, _)| along < nearest) {
+                *side = Some((along, pane));
+            }
+        }
+        (before.map(|(_, pane)| pane), after.map(|(_, pane)| pane))
+    }
+
+    /// Creates a menu from the items listed under `config_key`
+    /// in the `context_menus` configuration.
+    /// Returns None if there is no such menu.
+    pub fn from_config(
+        term_window: &mut TermWindow,
+        mouse_x: isize,
+        mouse_y: isize,
+        config_key: &str,
+    ) -> Option<Self> {
+        let items = term_window.config.context_menus.get(config_key)?;
+        Some(Self::from_item_defs(items, mouse_x, mouse_y, None))
+    }
+
+    /// Creates a menu with just the specified items, whose actions
+    /// apply to `target_pane_id` or else to the active pane
+    pub fn from_item_defs(
+        items: &[MenuItemDef],
+        mouse_x: isize,
+        mouse_y: isize,
+        target_pane_id: Option<PaneId>,
+    ) -> Self {
+        let items = items.iter().map(Self::item_from_def).collect();
+        let mut menu = Self::with_items(items, mouse_x, mouse_y, None);
+        menu.target_pane_id = target_pane_id;
+        menu
+    }
+
+    /// Returns a builder that assembles the items of a menu one at a time
+    pub fn builder() -> ContextMenuBuilder {
+        ContextMenuBuilder::default()
+    }
+
+    fn item_from_def(item: &MenuItemDef) -> MenuItem {
+        MenuItem::Entry {
+            label: item.label.clone().into(),
+            icon: item
+                .icon
+                .as_ref()
+                .and_then(|icon| Self::resolve_icon(&item.label, icon)),
+            action: item.action.clone(),
+            badge: item.badge,
+            preview_action: None,
+        }
+    }
+
+    /// Resolves the icon named for the item `label`, logging
+    /// a warning when there is no such icon
+    fn resolve_icon(label: &str, icon: &str) -> Option<MenuIcon> {
+        let resolved = MenuIcon::from_name(icon);
+        if resolved.is_none() {
+            log::warn!("context menu item {label}: nerdfont {icon} not found");
         }
+        resolved
+    }
 
+    fn with_items(
+        items: Vec<MenuItem>,
+        mouse_x: isize,
+        mouse_y: isize,
+        target_tab: Option<TabId>,
+    ) -> Self {
         Self {
             element: RefCell::new(None),
             selected_row: RefCell::new(0), // Start with first item selected
-            items,
+            items: RefCell::new(items),
             menu_x: RefCell::new(0.0),
             menu_y: RefCell::new(0.0),
-            initial_mouse_x: mouse_x as f32,
-            initial_mouse_y: mouse_y as f32,
+            initial_mouse_x: RefCell::new(mouse_x as f32),
+            initial_mouse_y: RefCell::new(mouse_y as f32),
             menu_width: RefCell::new(0.0),
             menu_height: RefCell::new(0.0),
             row_bounds: RefCell::new(Vec::new()),
+            scrollbar_left: RefCell::new(None),
+            scroll_offset: RefCell::new(0),
+            visible_rows: RefCell::new(0),
+            rows_per_column: RefCell::new(0),
+            row_colors: RefCell::new(RowColors::default()),
+            selection_animation: RefCell::new(None),
+            highlight_start: RefCell::new(None),
+            highlight_y: RefCell::new(0.),
+            target_y: RefCell::new(0.),
+            is_resizing: RefCell::new(false),
+            drag_start_x: RefCell::new(0.),
+            resized_width: RefCell::new(None),
+            target_tab,
+            target_pane_id: None,
+            is_submenu: false,
+            is_pane_menu: false,
+            external_items: RefCell::new(vec![]),
+            external_request: None,
+            error_state: RefCell::new(None),
+            announce: RefCell::new(false),
         }
     }
 
@@ -164,109 +1620,439 @@ impl ContextMenu {
         term_window: &mut TermWindow,
         items: &[MenuItem],
         selected_row: i32,
+        scroll_offset: usize,
         initial_mouse_x: f32,
         initial_mouse_y: f32,
-    ) -> anyhow::Result<(Vec<ComputedElement>, f32, f32, f32, f32, Vec<RowBounds>)> {
-        let font = term_window
-            .fonts
-            .command_palette_font()
-            .expect("to resolve command palette font");
-        let metrics = RenderMetrics::with_font_metrics(&font.metrics());
+        resized_width: Option<f32>,
+    ) -> Result<MenuLayout, ContextMenuError> {
+        let fonts = MenuFonts {
+            item: term_window
+                .fonts
+                .context_menu_font()
+                .map_err(ContextMenuError::Font)?,
+            breadcrumb: term_window
+                .fonts
+                .context_menu_breadcrumb_font()
+                .map_err(ContextMenuError::Font)?,
+        };
+        let metrics = RenderMetrics::with_font_metrics(&fonts.item.metrics());
         let row_height = metrics.cell_size.height as f32;
+        let item_padding = term_window.config.context_menu_item_padding_cells.max(0.) as f32;
+        // The height of a row, including its padding, in cells
+        let row_cells = 1. + 2. * item_padding;
 
-        let solid_bg_color: InheritableColor = term_window
-            .config
-            .command_palette_bg_color
-            .to_linear()
-            .into();
-        let solid_fg_color: InheritableColor = term_window
-            .config
-            .command_palette_fg_color
-            .to_linear()
-            .into();
+        let dimensions = term_window.dimensions;
+        let pixel_scale = dimensions.dpi as f32 / 96.;
 
-        let mut elements: Vec<Element> = vec![];
+        // Only render as many items as fit in the window; the rest can be
+        // reached by scrolling. Each row takes row_cells including its
+        // padding, plus ~1.5 cells for the outer margin/padding/border.
+        let max_rows = ((dimensions.pixel_height as f32 / row_height - 1.5) / row_cells)
+            .floor()
+            .max(1.) as usize;
+        // Items that don't fit in one column overflow into more of them,
+        // side by side, with the items spread evenly across the columns
+        let columns = term_window
+            .config
+            .context_menu_max_columns
+            .min((items.len() + max_rows - 1) / max_rows)
+            .max(1);
+        let rows_per_column = ((items.len() + columns - 1) / columns).min(max_rows);
+        let visible_rows = items.len().min(rows_per_column * columns);
+        let scroll_offset = scroll_offset.min(items.len() - visible_rows);
 
-        for (idx, item) in items.iter().enumerate() {
+        // Calculate menu dimensions
+        // Account for: items, per-item padding, outer margin/padding/border (~1.5 cells)
+        let cell_width = metrics.cell_size.width as f32;
+        let default_width = 25. * columns as f32 * cell_width;
+        // Once the right edge has been dragged, the extra width
+        // is shared out between the columns
+        let menu_width = resized_width.map_or(default_width, |width| {
+            width.min(dimensions.pixel_width as f32).max(default_width)
+        });
+        let row_width = 20. + (menu_width - default_width) / columns as f32 / cell_width;
+        let menu_height = (rows_per_column as f32 * row_cells + 1.5) * row_height;
+
+        let theme = crate::menutheme::current_theme(&term_window.config);
+        let style = MenuStyle {
+            bg: theme.bg.to_linear(),
+            fg: theme.fg.to_linear(),
+            border: theme.border.to_linear(),
+            badge_bg: term_window.config.context_menu_badge_bg_color.to_linear(),
+            badge_fg: LinearRgba::with_components(1., 1., 1., 1.),
+            breadcrumb_fg: theme.fg.to_linear().mul_alpha(0.6),
+            item_padding,
+            row_width: Dimension::Cells(row_width),
+            corner_radius: term_window.config.context_menu_corner_radius as f32,
+            shadow: if term_window.is_performance_mode() || !theme.shadow {
+                None
+            } else {
+                Some(BoxShadow {
+                    offset_x: 2. * pixel_scale,
+                    offset_y: 4. * pixel_scale,
+                    blur_radius: 2.
+                        * term_window.config.context_menu_shadow_blur_sigma.max(0.) as f32
+                        * pixel_scale,
+                    color: LinearRgba::with_components(0., 0., 0., 0.35),
+                })
+            },
+            backdrop_blur: if term_window.config.context_menu_blur_background
+                && !term_window.is_performance_mode()
+            {
+                let sigma = term_window.config.context_menu_background_blur_sigma as f32;
+                Some(sigma.max(0.) * pixel_scale)
+            } else {
+                None
+            },
+        };
+
+        let element = Self::build_element(
+            &fonts,
+            items,
+            selected_row,
+            scroll_offset,
+            visible_rows,
+            rows_per_column,
+            &style,
+        );
+
+        // Position the menu at the mouse location, but ensure it stays within the window
+        let menu_x = initial_mouse_x
+            .min(dimensions.pixel_width as f32 - menu_width)
+            .max(0.);
+        let menu_y = initial_mouse_y
+            .min(dimensions.pixel_height as f32 - menu_height)
+            .max(0.);
+
+        let gl_state = Self::require_render_state(term_window.render_state.as_ref())?;
+        let computed = term_window
+            .compute_element(
+                &LayoutContext {
+                    height: DimensionContext {
+                        dpi: dimensions.dpi as f32,
+                        pixel_max: dimensions.pixel_height as f32,
+                        pixel_cell: metrics.cell_size.height as f32,
+                    },
+                    width: DimensionContext {
+                        dpi: dimensions.dpi as f32,
+                        pixel_max: dimensions.pixel_width as f32,
+                        pixel_cell: metrics.cell_size.width as f32,
+                    },
+                    bounds: euclid::rect(menu_x, menu_y, menu_width, menu_height),
+                    metrics: &metrics,
+                    gl_state,
+                    zindex: 100,
+                },
+                &element,
+            )
+            .map_err(ContextMenuError::Layout)?;
+
+        // Extract actual row bounds from the computed element tree.
+        // The top-level computed element contains children, one per menu
+        // item, or one per column when there are several of them.
+        let rows = Self::computed_rows(&computed, columns > 1);
+        let row_bounds = Self::extract_row_bounds(&rows);
+        let scrollbar_left = if visible_rows < items.len() {
+            Self::extract_scrollbar_left(&rows)
+        } else {
+            None
+        };
+
+        Ok(MenuLayout {
+            element: vec![computed],
+            menu_x,
+            menu_y,
+            menu_width,
+            menu_height,
+            row_bounds,
+            scrollbar_left,
+            visible_rows,
+            rows_per_column,
+            row_colors: style.row_colors(),
+            selection_animation: match term_window.config.context_menu_selection_animation_ms {
+                _ if term_window.is_performance_mode() => None,
+                0 => None,
+                ms => Some(Duration::from_millis(ms)),
+            },
+        })
+    }
+
+    /// Fails, rather than panicking, when the window has no render
+    /// state to lay the menu out with
+    fn require_render_state(
+        render_state: Option<&RenderState>,
+    ) -> Result<&RenderState, ContextMenuError> {
+        render_state.ok_or(ContextMenuError::NoRenderState)
+    }
+
+    /// Lays the menu out with `compute` when it has no element yet,
+    /// and keeps the result.  The first error, other than a missing
+    /// render state, is latched in `error_state`, after which the
+    /// menu is an empty element and `compute` isn't called again.
+    fn update_element<F>(&self, compute: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&Self) -> Result<MenuLayout, ContextMenuError>,
+    {
+        if self.error_state.borrow().is_some() {
+            self.element.borrow_mut().get_or_insert_with(Vec::new);
+            return Ok(());
+        }
+        if self.element.borrow().is_some() {
+            return Ok(());
+        }
+        let layout = match compute(self) {
+            Ok(layout) => layout,
+            Err(err) => {
+                // The render state is only missing for a moment, so
+                // instead of giving up, try again on the next frame
+                let is_transient = matches!(err, ContextMenuError::NoRenderState);
+                let err = anyhow::Error::from(err);
+                if !is_transient {
+                    self.error_state.borrow_mut().replace(format!("{err:#}"));
+                }
+                return Err(err);
+            }
+        };
+        self.element.borrow_mut().replace(layout.element);
+        *self.menu_x.borrow_mut() = layout.menu_x;
+        *self.menu_y.borrow_mut() = layout.menu_y;
+        *self.menu_width.borrow_mut() = layout.menu_width;
+        *self.menu_height.borrow_mut() = layout.menu_height;
+        *self.row_bounds.borrow_mut() = layout.row_bounds;
+        *self.scrollbar_left.borrow_mut() = layout.scrollbar_left;
+        *self.visible_rows.borrow_mut() = layout.visible_rows;
+        *self.rows_per_column.borrow_mut() = layout.rows_per_column;
+        *self.row_colors.borrow_mut() = layout.row_colors;
+        *self.selection_animation.borrow_mut() = layout.selection_animation;
+        // The new layout has the highlight on the selected row
+        self.highlight_start.borrow_mut().take();
+        Ok(())
+    }
+
+    /// Builds the element tree for the visible portion of `items`, laid
+    /// out in columns of `rows_per_column` rows; `scroll_offset` and
+    /// `visible_rows` must already fit within `items`
+    fn build_element(
+        fonts: &MenuFonts,
+        items: &[MenuItem],
+        selected_row: i32,
+        scroll_offset: usize,
+        visible_rows: usize,
+        rows_per_column: usize,
+        style: &MenuStyle,
+    ) -> Element {
+        let font = &fonts.item;
+        let bg: InheritableColor = style.bg.into();
+        let fg: InheritableColor = style.fg.into();
+        let badge_bg: InheritableColor = style.badge_bg.into();
+        let badge_fg: InheritableColor = style.badge_fg.into();
+        let row_colors = style.row_colors();
+        let row_padding = BoxDimension {
+            left: Dimension::Cells(2. * style.item_padding),
+            right: Dimension::Cells(2. * style.item_padding),
+            top: Dimension::Cells(style.item_padding),
+            bottom: Dimension::Cells(style.item_padding),
+        };
+
+        // When not everything fits, draw a scrollbar down the right edge,
+        // one cell per row of the last column, with a thumb sized and
+        // placed to match the visible portion of the menu
+        let rows_per_column = rows_per_column.max(1);
+        let last_column = visible_rows.saturating_sub(1) / rows_per_column;
+        let scrollbar_thumb = if visible_rows < items.len() {
+            let thumb_len = ((rows_per_column * visible_rows) as f32 / items.len() as f32)
+                .round()
+                .max(1.) as usize;
+            let max_offset = items.len() - visible_rows;
+            let thumb_start = ((scroll_offset * (rows_per_column - thumb_len)) as f32
+                / max_offset as f32)
+                .round() as usize;
+            Some(thumb_start..thumb_start + thumb_len)
+        } else {
+            None
+        };
+        let scrollbar_cell = |visible_row: usize| {
+            if visible_row / rows_per_column != last_column {
+                return None;
+            }
+            scrollbar_thumb.as_ref().map(|thumb| {
+                let glyph = if thumb.contains(&(visible_row % rows_per_column)) {
+                    "█"
+                } else {
+                    "░"
+                };
+                Element::new(font, ElementContent::Text(glyph.to_string())).float(Float::Right)
+            })
+        };
+
+        let mut elements: Vec<Element> = vec![];
+
+        for (visible_row, (idx, item)) in items
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(visible_rows)
+            .enumerate()
+        {
             match item {
-                MenuItem::Entry { label, icon, .. } => {
+                MenuItem::Entry { label, icon, .. }
+                | MenuItem::Checkmark { label, icon, .. }
+                | MenuItem::SubMenu { label, icon, .. } => {
+                    let badge = match item {
+                        MenuItem::Entry {
+                            badge: Some(badge), ..
+                        } if *badge > 0 => Some(*badge),
+                        _ => None,
+                    };
+                    let label = match item {
+                        MenuItem::SubMenu { .. } => format!("{label} ▶"),
+                        _ => label.to_string(),
+                    };
+                    let icon = match item {
+                        MenuItem::Checkmark { checked: true, .. } => &Some(CHECKMARK_ICON),
+                        _ => icon,
+                    };
                     let icon_char = match icon {
-                        Some(nf) => NERD_FONTS.get(*nf).unwrap_or(&' '),
-                        None => &' ',
+                        Some(MenuIcon::NerdFont(nf)) => *NERD_FONTS.get(*nf).unwrap_or(&' '),
+                        Some(MenuIcon::Literal(c)) => *c,
+                        None => ' ',
                     };
 
-                    let (bg, text) = if idx as i32 == selected_row {
-                        (solid_fg_color.clone(), solid_bg_color.clone())
+                    let colors = if idx as i32 == selected_row {
+                        row_colors.selected.clone()
                     } else {
-                        (LinearRgba::TRANSPARENT.into(), solid_fg_color.clone())
+                        row_colors.normal.clone()
                     };
 
-                    let row = vec![
-                        Element::new(&font, ElementContent::Text(icon_char.to_string()))
+                    let mut row = vec![
+                        Element::new(font, ElementContent::Text(icon_char.to_string()))
                             .min_width(Some(Dimension::Cells(2.))),
-                        Element::new(&font, ElementContent::Text(label.to_string())),
+                        Element::new(font, ElementContent::Text(label)),
                     ];
+                    if let Some(badge) = badge {
+                        row.push(
+                            Element::new(font, ElementContent::Text(badge.to_string()))
+                                .colors(ElementColors {
+                                    border: BorderColor::new(style.badge_bg),
+                                    bg: badge_bg.clone(),
+                                    text: badge_fg.clone(),
+                                })
+                                .margin(BoxDimension {
+                                    left: Dimension::Cells(0.5),
+                                    right: Dimension::Cells(0.),
+                                    top: Dimension::Cells(0.),
+                                    bottom: Dimension::Cells(0.),
+                                })
+                                .padding(BoxDimension {
+                                    left: Dimension::Cells(0.4),
+                                    right: Dimension::Cells(0.4),
+                                    top: Dimension::Cells(0.),
+                                    bottom: Dimension::Cells(0.),
+                                })
+                                .border_corners(Some(Corners {
+                                    top_left: SizedPoly {
+                                        width: Dimension::Cells(0.4),
+                                        height: Dimension::Cells(0.5),
+                                        poly: TOP_LEFT_ROUNDED_CORNER,
+                                    },
+                                    top_right: SizedPoly {
+                                        width: Dimension::Cells(0.4),
+                                        height: Dimension::Cells(0.5),
+                                        poly: TOP_RIGHT_ROUNDED_CORNER,
+                                    },
+                                    bottom_left: SizedPoly {
+                                        width: Dimension::Cells(0.4),
+                                        height: Dimension::Cells(0.5),
+                                        poly: BOTTOM_LEFT_ROUNDED_CORNER,
+                                    },
+                                    bottom_right: SizedPoly {
+                                        width: Dimension::Cells(0.4),
+                                        height: Dimension::Cells(0.5),
+                                        poly: BOTTOM_RIGHT_ROUNDED_CORNER,
+                                    },
+                                })),
+                        );
+                    }
+                    row.extend(scrollbar_cell(visible_row));
 
                     elements.push(
-                        Element::new(&font, ElementContent::Children(row))
+                        Element::new(font, ElementContent::Children(row))
+                            .colors(colors)
+                            .padding(row_padding)
+                            .min_width(Some(style.row_width))
+                            .display(DisplayType::Block),
+                    );
+                }
+                MenuItem::Breadcrumb(path) => {
+                    let path = path.join(" › ");
+                    let mut row = vec![Element::new(&fonts.breadcrumb, ElementContent::Text(path))];
+                    row.extend(scrollbar_cell(visible_row));
+                    elements.push(
+                        Element::new(font, ElementContent::Children(row))
                             .colors(ElementColors {
                                 border: BorderColor::default(),
-                                bg,
-                                text,
-                            })
-                            .padding(BoxDimension {
-                                left: Dimension::Cells(0.5),
-                                right: Dimension::Cells(0.5),
-                                top: Dimension::Cells(0.1),
-                                bottom: Dimension::Cells(0.1),
+                                bg: LinearRgba::TRANSPARENT.into(),
+                                text: style.breadcrumb_fg.into(),
                             })
-                            .min_width(Some(Dimension::Cells(20.)))
+                            .padding(row_padding)
+                            .min_width(Some(style.row_width))
                             .display(DisplayType::Block),
                     );
                 }
-                MenuItem::Separator => {
-                    // Render a horizontal line for separator
+                MenuItem::SectionHeader(label) => {
+                    let mut row = vec![Element::new(font, ElementContent::Text(label.to_string()))];
+                    row.extend(scrollbar_cell(visible_row));
                     elements.push(
-                        Element::new(&font, ElementContent::Text("─".repeat(20)))
+                        Element::new(font, ElementContent::Children(row))
                             .colors(ElementColors {
                                 border: BorderColor::default(),
                                 bg: LinearRgba::TRANSPARENT.into(),
-                                text: solid_fg_color.clone(),
-                            })
-                            .padding(BoxDimension {
-                                left: Dimension::Cells(0.5),
-                                right: Dimension::Cells(0.5),
-                                top: Dimension::Cells(0.1),
-                                bottom: Dimension::Cells(0.1),
+                                text: style.breadcrumb_fg.into(),
                             })
-                            .min_width(Some(Dimension::Cells(20.)))
+                            .padding(row_padding)
+                            .min_width(Some(style.row_width))
+                            .display(DisplayType::Block),
+                    );
+                }
+                MenuItem::Separator => {
+                    // Render a horizontal line for separator
+                    let mut row = vec![Element::new(font, ElementContent::Text("─".repeat(20)))];
+                    row.extend(scrollbar_cell(visible_row));
+                    elements.push(
+                        Element::new(font, ElementContent::Children(row))
+                            .colors(row_colors.normal.clone())
+                            .padding(row_padding)
+                            .min_width(Some(style.row_width))
                             .display(DisplayType::Block),
                     );
                 }
             }
         }
 
-        let dimensions = term_window.dimensions;
+        if last_column > 0 {
+            let mut rows = elements.into_iter().peekable();
+            let mut columns = vec![];
+            while rows.peek().is_some() {
+                let column: Vec<Element> = rows.by_ref().take(rows_per_column).collect();
+                let mut column = Element::new(font, ElementContent::Children(column));
+                if !columns.is_empty() {
+                    column = column.margin(BoxDimension {
+                        left: Dimension::Cells(0.5),
+                        right: Dimension::Cells(0.),
+                        top: Dimension::Cells(0.),
+                        bottom: Dimension::Cells(0.),
+                    });
+                }
+                columns.push(column);
+            }
+            elements = columns;
+        }
 
-        let element = Element::new(&font, ElementContent::Children(elements))
+        Element::new(font, ElementContent::Children(elements))
             .colors(ElementColors {
-                border: BorderColor::new(
-                    term_window
-                        .config
-                        .command_palette_bg_color
-                        .to_linear()
-                        .into(),
-                ),
-                bg: term_window
-                    .config
-                    .command_palette_bg_color
-                    .to_linear()
-                    .into(),
-                text: term_window
-                    .config
-                    .command_palette_fg_color
-                    .to_linear()
-                    .into(),
+                border: BorderColor::new(style.border),
+                bg: bg.clone(),
+                text: fg.clone(),
             })
             .margin(BoxDimension {
                 left: Dimension::Cells(0.25),
@@ -281,125 +2067,255 @@ impl ContextMenu {
                 bottom: Dimension::Cells(0.25),
             })
             .border(BoxDimension::new(Dimension::Pixels(1.)))
-            .border_corners(Some(Corners {
-                top_left: SizedPoly {
-                    width: Dimension::Cells(0.25),
-                    height: Dimension::Cells(0.25),
-                    poly: TOP_LEFT_ROUNDED_CORNER,
-                },
-                top_right: SizedPoly {
-                    width: Dimension::Cells(0.25),
-                    height: Dimension::Cells(0.25),
-                    poly: TOP_RIGHT_ROUNDED_CORNER,
-                },
-                bottom_left: SizedPoly {
-                    width: Dimension::Cells(0.25),
-                    height: Dimension::Cells(0.25),
-                    poly: BOTTOM_LEFT_ROUNDED_CORNER,
-                },
-                bottom_right: SizedPoly {
-                    width: Dimension::Cells(0.25),
-                    height: Dimension::Cells(0.25),
-                    poly: BOTTOM_RIGHT_ROUNDED_CORNER,
-                },
-            }));
-
-        // Calculate menu dimensions
-        // Account for: items, per-item padding (0.2 cells each), outer margin/padding/border (~1.5 cells)
-        let menu_width = 25. * metrics.cell_size.width as f32;
-        let menu_height = (items.len() as f32 * 1.2 + 1.5) * row_height;
-
-        // Position the menu at the mouse location, but ensure it stays within the window
-        let menu_x = initial_mouse_x
-            .min(dimensions.pixel_width as f32 - menu_width)
-            .max(0.);
-        let menu_y = initial_mouse_y
-            .min(dimensions.pixel_height as f32 - menu_height)
-            .max(0.);
+            .border_corners(if style.corner_radius > 0. {
+                Some(Corners {
+                    top_left: SizedPoly {
+                        width: Dimension::Cells(style.corner_radius),
+                        height: Dimension::Cells(style.corner_radius),
+                        poly: TOP_LEFT_ROUNDED_CORNER,
+                    },
+                    top_right: SizedPoly {
+                        width: Dimension::Cells(style.corner_radius),
+                        height: Dimension::Cells(style.corner_radius),
+                        poly: TOP_RIGHT_ROUNDED_CORNER,
+                    },
+                    bottom_left: SizedPoly {
+                        width: Dimension::Cells(style.corner_radius),
+                        height: Dimension::Cells(style.corner_radius),
+                        poly: BOTTOM_LEFT_ROUNDED_CORNER,
+                    },
+                    bottom_right: SizedPoly {
+                        width: Dimension::Cells(style.corner_radius),
+                        height: Dimension::Cells(style.corner_radius),
+                        poly: BOTTOM_RIGHT_ROUNDED_CORNER,
+                    },
+                })
+            } else {
+                None
+            })
+            .shadow(style.shadow)
+            .backdrop_blur(style.backdrop_blur)
+    }
 
-        let computed = term_window.compute_element(
-            &LayoutContext {
-                height: DimensionContext {
-                    dpi: dimensions.dpi as f32,
-                    pixel_max: dimensions.pixel_height as f32,
-                    pixel_cell: metrics.cell_size.height as f32,
-                },
-                width: DimensionContext {
-                    dpi: dimensions.dpi as f32,
-                    pixel_max: dimensions.pixel_width as f32,
-                    pixel_cell: metrics.cell_size.width as f32,
-                },
-                bounds: euclid::rect(menu_x, menu_y, menu_width, menu_height),
-                metrics: &metrics,
-                gl_state: term_window.render_state.as_ref().unwrap(),
-                zindex: 100,
-            },
-            &element,
-        )?;
+    /// Returns the rows of the computed element, in item order,
+    /// looking inside each of the columns when there are several
+    fn computed_rows(computed: &ComputedElement, multi_column: bool) -> Vec<&ComputedElement> {
+        let kids = match &computed.content {
+            ComputedElementContent::Children(kids) => kids,
+            _ => return vec![],
+        };
+        if !multi_column {
+            return kids.iter().collect();
+        }
+        kids.iter()
+            .flat_map(|column| match &column.content {
+                ComputedElementContent::Children(rows) => rows.as_slice(),
+                _ => &[][..],
+            })
+            .collect()
+    }
 
-        // Extract actual row bounds from the computed element tree.
-        // The top-level computed element contains children, one per menu item.
-        let row_bounds = Self::extract_row_bounds(&computed);
+    /// Returns the computed row of item `row`, if it is visible
+    fn computed_row_mut(
+        computed: &mut ComputedElement,
+        row: i32,
+        scroll_offset: usize,
+        visible_rows: usize,
+        rows_per_column: usize,
+    ) -> Option<&mut ComputedElement> {
+        let visible_row = usize::try_from(row).ok()?.checked_sub(scroll_offset)?;
+        if visible_row >= visible_rows {
+            return None;
+        }
+        let kids = match &mut computed.content {
+            ComputedElementContent::Children(kids) => kids,
+            _ => return None,
+        };
+        if rows_per_column == 0 || visible_rows <= rows_per_column {
+            return kids.get_mut(visible_row);
+        }
+        match &mut kids.get_mut(visible_row / rows_per_column)?.content {
+            ComputedElementContent::Children(rows) => rows.get_mut(visible_row % rows_per_column),
+            _ => None,
+        }
+    }
 
-        Ok((
-            vec![computed],
-            menu_x,
-            menu_y,
-            menu_width,
-            menu_height,
-            row_bounds,
-        ))
+    /// Extract the actual pixel bounds of each of the computed rows.
+    fn extract_row_bounds(rows: &[&ComputedElement]) -> Vec<RowBounds> {
+        rows.iter()
+            .map(|row| RowBounds {
+                x: row.bounds.min_x(),
+                y: row.bounds.min_y(),
+                height: row.bounds.height(),
+            })
+            .collect()
     }
 
-    /// Extract the actual pixel bounds of each child row from the computed element.
-    fn extract_row_bounds(computed: &ComputedElement) -> Vec<RowBounds> {
-        let mut bounds = Vec::new();
-        if let ComputedElementContent::Children(ref kids) = computed.content {
-            for kid in kids {
-                bounds.push(RowBounds {
-                    y: kid.bounds.min_y(),
-                    height: kid.bounds.height(),
-                });
-            }
+    /// The scrollbar is drawn as the last cell of each row of the
+    /// last column, so its left edge is that of the last child of
+    /// the last row
+    fn extract_scrollbar_left(rows: &[&ComputedElement]) -> Option<f32> {
+        match &rows.last()?.content {
+            ComputedElementContent::Children(cells) => Some(cells.last()?.bounds.min_x()),
+            _ => None,
         }
-        bounds
     }
 
     /// Check if a given row index is a selectable entry (not a separator)
     fn is_selectable(&self, row: i32) -> bool {
-        if row < 0 || row >= self.items.len() as i32 {
-            return false;
-        }
-        matches!(self.items[row as usize], MenuItem::Entry { .. })
+        let items = self.items.borrow();
+        matches!(
+            usize::try_from(row).ok().and_then(|row| items.get(row)),
+            Some(MenuItem::Entry { .. } | MenuItem::Checkmark { .. } | MenuItem::SubMenu { .. })
+        )
     }
 
     fn move_up(&self) {
-        let mut row = self.selected_row.borrow_mut();
-        let mut new_row = *row - 1;
+        let previous = *self.selected_row.borrow();
+        let mut new_row = previous - 1;
         // Skip over separators
         while new_row >= 0 && !self.is_selectable(new_row) {
             new_row -= 1;
         }
         if new_row >= 0 {
-            *row = new_row;
+            *self.selected_row.borrow_mut() = new_row;
+            self.announce_selection();
         }
-        // Clear cached element to force re-render with new selection
-        self.element.borrow_mut().take();
+        self.ensure_selection_visible();
+        self.restyle_selection(previous);
     }
 
     fn move_down(&self) {
-        let limit = self.items.len() as i32;
-        let mut row = self.selected_row.borrow_mut();
-        let mut new_row = *row + 1;
+        let limit = self.items.borrow().len() as i32;
+        let previous = *self.selected_row.borrow();
+        let mut new_row = previous + 1;
         // Skip over separators
         while new_row < limit && !self.is_selectable(new_row) {
             new_row += 1;
         }
         if new_row < limit {
-            *row = new_row;
+            *self.selected_row.borrow_mut() = new_row;
+            self.announce_selection();
+        }
+        self.ensure_selection_visible();
+        self.restyle_selection(previous);
+    }
+
+    /// Select the next selectable item in the direction of `delta`,
+    /// skipping separators like move_down and move_up do, but wrapping
+    /// around from one end of the menu to the other
+    fn move_cyclic(&self, delta: i32) {
+        let limit = self.items.borrow().len() as i32;
+        let selected = *self.selected_row.borrow();
+        let start = match selected {
+            row if row >= 0 => row,
+            _ if delta > 0 => -1,
+            _ => limit,
+        };
+        let row = (1..=limit)
+            .map(|step| (start + step * delta).rem_euclid(limit))
+            .find(|&row| self.is_selectable(row));
+        if let Some(row) = row {
+            self.set_selection(row);
+            self.ensure_selection_visible();
+        }
+    }
+
+    /// Move the selection and the scroll offset by a page of
+    /// `pages * visible_rows` items, clamped to the ends of the list
+    fn move_by_page(&self, pages: isize) {
+        let visible_rows = (*self.visible_rows.borrow()).max(1) as isize;
+        let limit = self.items.borrow().len() as i32;
+        if limit == 0 {
+            return;
+        }
+        let selected = *self.selected_row.borrow();
+        let target =
+            (selected.max(0) as isize + pages * visible_rows).clamp(0, limit as isize - 1) as i32;
+        // Land on the nearest item in the direction of travel,
+        // falling back to the other direction at the ends
+        let forwards = (target..limit).find(|&row| self.is_selectable(row));
+        let backwards = (0..=target).rev().find(|&row| self.is_selectable(row));
+        let row = if pages > 0 {
+            forwards.or(backwards)
+        } else {
+            backwards.or(forwards)
+        };
+        self.scroll_by(pages * visible_rows);
+        if let Some(row) = row {
+            self.set_selection(row);
+        }
+        self.ensure_selection_visible();
+    }
+
+    /// Adjust the scroll offset so that the selected row is on screen
+    fn ensure_selection_visible(&self) {
+        let visible_rows = *self.visible_rows.borrow();
+        let selected = *self.selected_row.borrow();
+        if visible_rows == 0 || selected < 0 {
+            return;
+        }
+        let selected = selected as usize;
+        let offset = *self.scroll_offset.borrow();
+        if selected < offset {
+            self.scroll_to(selected);
+        } else if selected >= offset + visible_rows {
+            self.scroll_to(selected + 1 - visible_rows);
+        }
+    }
+
+    /// Scroll the visible window of items by `delta` rows, clamped so
+    /// that the menu never scrolls past the first or last item
+    fn scroll_by(&self, delta: isize) {
+        let offset = *self.scroll_offset.borrow();
+        self.scroll_to((offset as isize + delta).max(0) as usize);
+    }
+
+    /// Select the item beside the selected one in the column `delta`
+    /// columns over, or the nearest selectable item to that one within
+    /// the same column. Returns false if there is no such column.
+    fn move_column(&self, delta: isize) -> bool {
+        let rows_per_column = *self.rows_per_column.borrow();
+        let visible_rows = *self.visible_rows.borrow();
+        let selected = *self.selected_row.borrow();
+        // There is nothing beside the selection in a single column
+        if rows_per_column == 0 || rows_per_column >= visible_rows || selected < 0 {
+            return false;
+        }
+        let target = selected as isize + delta * rows_per_column as isize;
+        let limit = self.items.borrow().len() as isize;
+        if target < 0 || target >= limit {
+            return false;
+        }
+        // Columns are counted from the first visible item
+        let scroll_offset = *self.scroll_offset.borrow() as isize;
+        let column_start = target - (target - scroll_offset).rem_euclid(rows_per_column as isize);
+        let column_end = (column_start + rows_per_column as isize).min(limit);
+        let nearest = (0..rows_per_column as isize)
+            .flat_map(|distance| [target - distance, target + distance])
+            .filter(|&row| row >= column_start && row < column_end)
+            .find(|&row| self.is_selectable(row as i32));
+        match nearest {
+            Some(row) => {
+                self.set_selection(row as i32);
+                self.ensure_selection_visible();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scroll so that `offset` is the first visible item, clamped so
+    /// that the menu never scrolls past the last item
+    fn scroll_to(&self, offset: usize) {
+        let visible_rows = *self.visible_rows.borrow();
+        let max_offset = self.items.borrow().len().saturating_sub(visible_rows);
+        let new_offset = offset.min(max_offset);
+        if new_offset != *self.scroll_offset.borrow() {
+            *self.scroll_offset.borrow_mut() = new_offset;
+            // Clear cached element to force re-render with the new offset
+            self.element.borrow_mut().take();
         }
-        // Clear cached element to force re-render with new selection
-        self.element.borrow_mut().take();
     }
 
     fn set_selection(&self, row: i32) {
@@ -407,20 +2323,215 @@ impl ContextMenu {
         if !self.is_selectable(row) {
             return;
         }
-        let mut selected = self.selected_row.borrow_mut();
-        if *selected != row {
-            *selected = row;
-            // Clear cached element to force re-render with new selection
-            self.element.borrow_mut().take();
+        let previous = self.selected_row.replace(row);
+        if previous != row {
+            self.announce_selection();
+            self.restyle_selection(previous);
+        }
+    }
+
+    /// Move the highlight from `previous` to the selected row in the
+    /// cached element, rather than discarding it; laying the menu out
+    /// again would shape every one of its labels. Once the cache has
+    /// been discarded, eg: by scrolling, there is nothing to do here.
+    fn restyle_selection(&self, previous: i32) {
+        let selected = *self.selected_row.borrow();
+        if selected == previous {
+            return;
+        }
+        let mut element = self.element.borrow_mut();
+        let computed = match element.as_mut().and_then(|element| element.first_mut()) {
+            Some(computed) => computed,
+            None => return,
+        };
+        let scroll_offset = *self.scroll_offset.borrow();
+        let visible_rows = *self.visible_rows.borrow();
+        let rows_per_column = *self.rows_per_column.borrow();
+        let row_colors = self.row_colors.borrow();
+
+        let previous_top_left = Self::computed_row_mut(
+            computed,
+            previous,
+            scroll_offset,
+            visible_rows,
+            rows_per_column,
+        )
+        .map(|row| {
+            row.colors = row_colors.normal.clone();
+            (row.bounds.min_x(), row.bounds.min_y())
+        });
+        let row = match Self::computed_row_mut(
+            computed,
+            selected,
+            scroll_offset,
+            visible_rows,
+            rows_per_column,
+        ) {
+            Some(row) => row,
+            None => {
+                self.highlight_start.borrow_mut().take();
+                return;
+            }
+        };
+
+        // Slide the highlight within a column; it jumps between columns
+        match (*self.selection_animation.borrow(), previous_top_left) {
+            (Some(_), Some((x, y))) if x == row.bounds.min_x() => {
+                let mut start = self.highlight_start.borrow_mut();
+                // Carry on from wherever an unfinished slide got to
+                let from = if start.is_some() {
+                    *self.highlight_y.borrow()
+                } else {
+                    y
+                };
+                start.replace((Instant::now(), from));
+                *self.highlight_y.borrow_mut() = from;
+                *self.target_y.borrow_mut() = row.bounds.min_y();
+                // animate_highlight draws the background in its place
+                row.colors = ElementColors {
+                    bg: LinearRgba::TRANSPARENT.into(),
+                    ..row_colors.selected.clone()
+                };
+            }
+            _ => {
+                self.highlight_start.borrow_mut().take();
+                row.colors = row_colors.selected.clone();
+            }
+        }
+    }
+
+    /// Moves the highlight along its slide towards the selected row,
+    /// and asks for another frame until it gets there. The highlight
+    /// is a copy of the background of the selected row, following the
+    /// menu in `element`, so that it is drawn over the background of
+    /// the menu but under the text of the rows.
+    fn animate_highlight(&self, term_window: &mut TermWindow) {
+        let mut element = self.element.borrow_mut();
+        let element = match element.as_mut() {
+            Some(element) if !element.is_empty() => element,
+            _ => return,
+        };
+        element.truncate(1);
+        let (start, from) = match *self.highlight_start.borrow() {
+            Some(start) => start,
+            None => return,
+        };
+        let duration = match *self.selection_animation.borrow() {
+            Some(duration) => duration,
+            None => return,
+        };
+        let row = match Self::computed_row_mut(
+            &mut element[0],
+            *self.selected_row.borrow(),
+            *self.scroll_offset.borrow(),
+            *self.visible_rows.borrow(),
+            *self.rows_per_column.borrow(),
+        ) {
+            Some(row) => row,
+            None => return,
+        };
+        let row_colors = self.row_colors.borrow();
+
+        let target = *self.target_y.borrow();
+        let progress = start.elapsed().as_secs_f32() / duration.as_secs_f32();
+        if progress >= 1. {
+            row.colors = row_colors.selected.clone();
+            *self.highlight_y.borrow_mut() = target;
+            self.highlight_start.borrow_mut().take();
+            return;
+        }
+
+        // Ease out, so that the highlight settles onto the row
+        let eased = 1. - (1. - progress).powi(2);
+        let y = from + (target - from) * eased;
+        *self.highlight_y.borrow_mut() = y;
+
+        let mut highlight = row.clone();
+        highlight.content = ComputedElementContent::Children(vec![]);
+        highlight.colors = ElementColors {
+            border: BorderColor::default(),
+            bg: row_colors.selected.bg.clone(),
+            text: InheritableColor::Inherited,
+        };
+        highlight.translate(euclid::vec2(0., y - highlight.bounds.min_y()));
+        element.push(highlight);
+
+        let fps = term_window.config.animation_fps.max(1) as u64;
+        term_window
+            .update_next_frame_time(Some(Instant::now() + Duration::from_millis(1000 / fps)));
+    }
+
+    /// Sets whether the items are announced as they are highlighted;
+    /// they are not in secure input mode
+    pub fn set_announce(&self, announce: bool) {
+        *self.announce.borrow_mut() = announce;
+    }
+
+    /// Announce the label of the newly highlighted item.
+    /// There is no platform accessibility integration yet, so this
+    /// is emitted on a dedicated log target that assistive tooling
+    /// can follow via `WEZTERM_LOG=wezterm_gui::accessibility=info`.
+    fn announce_selection(&self) {
+        let selected = *self.selected_row.borrow();
+        if selected < 0 || !*self.announce.borrow() {
+            return;
+        }
+        match self.items.borrow().get(selected as usize) {
+            Some(MenuItem::Entry { label, .. } | MenuItem::SubMenu { label, .. }) => {
+                log::info!(target: ACCESSIBILITY_LOG_TARGET, "context menu: {label}");
+            }
+            Some(MenuItem::Checkmark { label, checked, .. }) => {
+                let state = if *checked { "checked" } else { "not checked" };
+                log::info!(target: ACCESSIBILITY_LOG_TARGET, "context menu: {label}, {state}");
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the label of the highlighted item, or None when no item,
+    /// or a separator, is selected. The items may be built again while
+    /// the menu is open, so the label is copied out of them.
+    pub fn selected_item_label(&self) -> Option<String> {
+        let selected = usize::try_from(*self.selected_row.borrow()).ok()?;
+        match self.items.borrow().get(selected)? {
+            MenuItem::Entry { label, .. }
+            | MenuItem::Checkmark { label, .. }
+            | MenuItem::SubMenu { label, .. } => Some(label.to_string()),
+            MenuItem::Separator | MenuItem::Breadcrumb(_) | MenuItem::SectionHeader(_) => None,
         }
     }
 
     fn activate_selected(&self, term_window: &mut TermWindow) {
         let selected_idx = *self.selected_row.borrow();
         if selected_idx >= 0 {
-            if let Some(MenuItem::Entry { action, .. }) = self.items.get(selected_idx as usize) {
-                let action = action.clone();
-                term_window.cancel_modal();
+            let item = self.items.borrow().get(selected_idx as usize).cloned();
+            if let Some(MenuItem::SubMenu { label, items, .. }) = item {
+                self.open_submenu(term_window, selected_idx as usize, label, items);
+                return;
+            }
+            if let Some(MenuItem::Entry { action, .. } | MenuItem::Checkmark { action, .. }) = item
+            {
+                Self::close_menus(term_window);
+
+                if term_window.config.context_menu_usage_stats {
+                    crate::menustats::record_context_menu_action(&action);
+                }
+
+                if let Some(tab_id) = self.target_tab {
+                    // The tab actions operate on the active tab, so switch
+                    // to the tab that was clicked on first
+                    let tab_idx = mux::Mux::get()
+                        .get_window(term_window.mux_window_id)
+                        .and_then(|window| window.idx_by_id(tab_id));
+                    if let Some(tab_idx) = tab_idx {
+                        term_window.activate_tab(tab_idx as isize).ok();
+                    }
+                }
+
+                if let Some(pane_id) = self.target_pane_id {
+                    // Likewise for the pane that the menu was opened over
+                    term_window.activate_pane_by_id(pane_id);
+                }
 
                 if let Some(pane) = term_window.get_active_pane_or_overlay() {
                     if let Err(err) = term_window.perform_key_assignment(&pane, &action) {
@@ -431,29 +2542,186 @@ impl ContextMenu {
         }
     }
 
+    /// Open a nested menu above this one, placed alongside the
+    /// row at `idx`
+    fn open_submenu(
+        &self,
+        term_window: &mut TermWindow,
+        idx: usize,
+        label: Cow<'static, str>,
+        items: Vec<MenuItem>,
+    ) {
+        let x = *self.menu_x.borrow() + *self.menu_width.borrow();
+        let y = self
+            .row_bounds
+            .borrow()
+            .get(idx.saturating_sub(*self.scroll_offset.borrow()))
+            .map(|rb| rb.y)
+            .unwrap_or_else(|| *self.menu_y.borrow());
+        let items = std::iter::once(MenuItem::Breadcrumb(self.submenu_path(label)))
+            .chain(items)
+            .collect();
+        let mut submenu = Self::with_items(items, x as isize, y as isize, self.target_tab);
+        submenu.target_pane_id = self.target_pane_id;
+        submenu.is_submenu = true;
+        // Start on the first item after the breadcrumb
+        let limit = submenu.items.borrow().len() as i32;
+        let first = (0..limit).find(|&row| submenu.is_selectable(row));
+        *submenu.selected_row.borrow_mut() = first.unwrap_or(-1);
+        term_window.push_modal(Rc::new(submenu));
+    }
+
+    /// The breadcrumb of a sub-menu opened from the entry `label` of
+    /// this menu: the breadcrumb of this menu, followed by `label`
+    fn submenu_path(&self, label: Cow<'static, str>) -> Vec<Cow<'static, str>> {
+        let mut path = match self.items.borrow().first() {
+            Some(MenuItem::Breadcrumb(path)) => path.clone(),
+            _ => vec![BREADCRUMB_ROOT.into()],
+        };
+        path.push(label);
+        path
+    }
+
+    /// Previews the action of the selected entry, if it has one,
+    /// in place of that of the previously selected entry
+    fn update_preview(&self, term_window: &mut TermWindow) {
+        // This menu may have just been closed by the key press
+        let is_open = term_window.get_modal().map_or(false, |modal| {
+            Rc::as_ptr(&modal) as *const () == self as *const Self as *const ()
+        });
+        if !is_open {
+            term_window.preview_key_assignment(None, None);
+            return;
+        }
+
+        let selected = *self.selected_row.borrow();
+        let preview =
+            usize::try_from(selected)
+                .ok()
+                .and_then(|idx| match self.items.borrow().get(idx) {
+                    Some(MenuItem::Entry { preview_action, .. }) => preview_action.clone(),
+                    _ => None,
+                });
+        let pane_id = self.target_pane_id.or_else(|| {
+            term_window
+                .get_active_pane_no_overlay()
+                .map(|pane| pane.pane_id())
+        });
+        term_window.preview_key_assignment(pane_id, preview.as_ref());
+    }
+
+    /// Close this menu along with any menus that it was opened from
+    fn close_menus(term_window: &mut TermWindow) {
+        term_window.preview_key_assignment(None, None);
+        while term_window.context_menu_active() {
+            term_window.cancel_modal();
+        }
+    }
+
     /// Calculate which menu row is at the given pixel coordinates.
     /// Uses actual rendered bounds from the computed element tree.
     /// Returns -1 if outside the menu.
     fn row_at_coords(&self, x: f32, y: f32) -> i32 {
+        let row_bounds = self.row_bounds.borrow();
+        crate::termwindow::menuhit::row_at_coords(
+            &MenuBounds {
+                x: *self.menu_x.borrow(),
+                y: *self.menu_y.borrow(),
+                width: *self.menu_width.borrow(),
+                height: *self.menu_height.borrow(),
+                scrollbar_left: *self.scrollbar_left.borrow(),
+                scroll_offset: *self.scroll_offset.borrow(),
+                rows: &row_bounds,
+            },
+            x,
+            y,
+        )
+    }
+
+    /// Whether (x, y) is close enough to the right edge of the
+    /// menu to start dragging it
+    fn is_on_right_edge(&self, x: f32, y: f32, pixel_scale: f32) -> bool {
+        let right = *self.menu_x.borrow() + *self.menu_width.borrow();
+        let top = *self.menu_y.borrow();
+        let bottom = top + *self.menu_height.borrow();
+        (x - right).abs() <= RESIZE_GRIP_WIDTH * pixel_scale && y >= top && y <= bottom
+    }
+
+    /// Moves the right edge of the menu by as far as the mouse has
+    /// moved since the last call, keeping it within the window
+    fn resize_to(&self, x: f32, window_width: f32) {
+        let delta = x - std::mem::replace(&mut *self.drag_start_x.borrow_mut(), x);
         let menu_x = *self.menu_x.borrow();
-        let menu_y = *self.menu_y.borrow();
-        let menu_width = *self.menu_width.borrow();
-        let menu_height = *self.menu_height.borrow();
+        let mut menu_width = self.menu_width.borrow_mut();
+        *menu_width = (*menu_width + delta).min(window_width - menu_x).max(0.);
+        self.resized_width.borrow_mut().replace(*menu_width);
+        self.element.borrow_mut().take();
+    }
 
-        // Check if coordinates are within menu bounds
-        if x < menu_x || x > menu_x + menu_width || y < menu_y || y > menu_y + menu_height {
-            return -1;
+    /// When (x, y) is on the scrollbar, returns the scroll offset that
+    /// corresponds to that point of the track: the same fraction of the
+    /// way through the items as y is of the way down the track
+    fn scroll_offset_at_coords(&self, x: f32, y: f32) -> Option<usize> {
+        let left = (*self.scrollbar_left.borrow())?;
+        let right = *self.menu_x.borrow() + *self.menu_width.borrow();
+        let row_bounds = self.row_bounds.borrow();
+        let top = row_bounds.first()?.y;
+        let bottom = row_bounds.last().map(|rb| rb.y + rb.height)?;
+        if x < left || x > right || y < top || y >= bottom {
+            return None;
         }
+        let fraction = (y - top) / (bottom - top);
+        Some((fraction * self.items.borrow().len() as f32) as usize)
+    }
+}
 
-        // Use actual computed row bounds for hit testing
-        let row_bounds = self.row_bounds.borrow();
-        for (idx, rb) in row_bounds.iter().enumerate() {
-            if y >= rb.y && y < rb.y + rb.height {
-                return idx as i32;
-            }
+/// Assembles the items of a context menu one at a time, eg:
+/// `ContextMenu::builder().entry("Copy", None, action).separator()`
+#[derive(Clone, Default)]
+pub struct ContextMenuBuilder {
+    items: Vec<MenuItem>,
+}
+
+impl ContextMenuBuilder {
+    /// Adds an entry that performs `action`, with the named icon
+    pub fn entry(
+        mut self,
+        label: impl Into<Cow<'static, str>>,
+        icon: Option<&str>,
+        action: KeyAssignment,
+    ) -> Self {
+        let label = label.into();
+        let icon = icon.and_then(|icon| ContextMenu::resolve_icon(&label, icon));
+        self.items.push(MenuItem::entry(label, icon, action));
+        self
+    }
+
+    /// Adds a separator after the items so far. Repeated separators,
+    /// and those at the start or end of the menu, are left out.
+    pub fn separator(mut self) -> Self {
+        if !matches!(self.items.last(), None | Some(MenuItem::Separator)) {
+            self.items.push(MenuItem::Separator);
         }
+        self
+    }
+
+    /// Adds a title for the items that follow
+    pub fn section_header(mut self, label: impl Into<Cow<'static, str>>) -> Self {
+        self.items.push(MenuItem::SectionHeader(label.into()));
+        self
+    }
 
-        -1
+    /// Creates the menu, with its top left corner at the specified
+    /// position, and with its first entry selected
+    pub fn build(mut self, mouse_x: isize, mouse_y: isize) -> ContextMenu {
+        if matches!(self.items.last(), Some(MenuItem::Separator)) {
+            self.items.pop();
+        }
+        let menu = ContextMenu::with_items(self.items, mouse_x, mouse_y, None);
+        let limit = menu.items.borrow().len() as i32;
+        let first = (0..limit).find(|&row| menu.is_selectable(row));
+        *menu.selected_row.borrow_mut() = first.unwrap_or(-1);
+        menu
     }
 }
 
@@ -466,7 +2734,11 @@ impl Modal for ContextMenu {
         false
     }
 
-    fn mouse_event(&self, event: MouseEvent, term_window: &mut TermWindow) -> anyhow::Result<()> {
+    fn mouse_event(
+        &self,
+        event: MouseEvent,
+        term_window: &mut TermWindow,
+    ) -> anyhow::Result<EventPropagation> {
         // Get actual pixel coordinates from the stored window event
         let (mouse_x, mouse_y) = term_window
             .current_mouse_event
@@ -475,27 +2747,71 @@ impl Modal for ContextMenu {
             .unwrap_or((0.0, 0.0));
 
         let row = self.row_at_coords(mouse_x, mouse_y);
+        let scrollbar_offset = self.scroll_offset_at_coords(mouse_x, mouse_y);
+
+        if *self.is_resizing.borrow() {
+            match event.kind {
+                wezterm_term::input::MouseEventKind::Move => {
+                    self.resize_to(mouse_x, term_window.dimensions.pixel_width as f32);
+                    if let Some(window) = term_window.window.as_ref() {
+                        window.invalidate();
+                    }
+                }
+                wezterm_term::input::MouseEventKind::Release => {
+                    *self.is_resizing.borrow_mut() = false;
+                }
+                wezterm_term::input::MouseEventKind::Press => {}
+            }
+            return Ok(EventPropagation::Consumed);
+        }
+
+        let pixel_scale = term_window.dimensions.dpi as f32 / 96.;
+        if event.kind == wezterm_term::input::MouseEventKind::Press
+            && event.button == MouseButton::Left
+            && self.is_on_right_edge(mouse_x, mouse_y, pixel_scale)
+        {
+            *self.is_resizing.borrow_mut() = true;
+            *self.drag_start_x.borrow_mut() = mouse_x;
+            return Ok(EventPropagation::Consumed);
+        }
 
         match event.kind {
             wezterm_term::input::MouseEventKind::Move => {
                 // Update selection on hover
                 if row >= 0 {
                     self.set_selection(row);
+                    self.update_preview(term_window);
                 }
             }
-            wezterm_term::input::MouseEventKind::Press => {
-                if row >= 0 {
-                    self.set_selection(row);
-                    self.activate_selected(term_window);
-                } else {
-                    // Click outside menu - close it
-                    term_window.cancel_modal();
+            wezterm_term::input::MouseEventKind::Press => match event.button {
+                // Scroll by one item per wheel notch
+                MouseButton::WheelUp(n) => self.scroll_by(-(n as isize)),
+                MouseButton::WheelDown(n) => self.scroll_by(n as isize),
+                MouseButton::WheelLeft(_) | MouseButton::WheelRight(_) => {}
+                // Right-clicking on the scrollbar jumps to that point of
+                // the track; other clicks on it are ignored, rather than
+                // closing the menu as a click outside of it would
+                button if scrollbar_offset.is_some() => {
+                    if let (MouseButton::Right, Some(offset)) = (button, scrollbar_offset) {
+                        self.scroll_to(offset);
+                    }
                 }
-            }
+                _ => {
+                    if row >= 0 {
+                        self.set_selection(row);
+                        self.activate_selected(term_window);
+                    } else {
+                        // Click outside menu - close it and let the click
+                        // through to whatever is underneath
+                        term_window.cancel_modal();
+                        return Ok(EventPropagation::Propagate);
+                    }
+                }
+            },
             _ => {}
         }
 
-        Ok(())
+        Ok(EventPropagation::Consumed)
     }
 
     fn key_down(
@@ -520,13 +2836,69 @@ impl Modal for ContextMenu {
             | (KeyCode::Char('n'), KeyModifiers::CTRL) => {
                 self.move_down();
             }
+            (KeyCode::Tab, KeyModifiers::NONE) => {
+                self.move_cyclic(1);
+            }
+            (KeyCode::Tab, KeyModifiers::SHIFT) => {
+                self.move_cyclic(-1);
+            }
+            (KeyCode::PageUp, KeyModifiers::NONE) => {
+                self.move_by_page(-1);
+            }
+            (KeyCode::PageDown, KeyModifiers::NONE) => {
+                self.move_by_page(1);
+            }
+            (KeyCode::Home, KeyModifiers::NONE) => {
+                let limit = self.items.borrow().len() as i32;
+                if let Some(row) = (0..limit).find(|&row| self.is_selectable(row)) {
+                    self.set_selection(row);
+                    self.ensure_selection_visible();
+                }
+            }
+            (KeyCode::End, KeyModifiers::NONE) => {
+                let limit = self.items.borrow().len() as i32;
+                if let Some(row) = (0..limit).rev().find(|&row| self.is_selectable(row)) {
+                    self.set_selection(row);
+                    self.ensure_selection_visible();
+                }
+            }
             (KeyCode::Enter, KeyModifiers::NONE) => {
                 self.activate_selected(term_window);
                 return Ok(true);
             }
+            // Back to the menu that this sub-menu was opened from
+            (KeyCode::Backspace, KeyModifiers::NONE) if self.is_submenu => {
+                term_window.cancel_modal();
+            }
+            (KeyCode::LeftArrow, KeyModifiers::NONE) | (KeyCode::Char('h'), KeyModifiers::NONE) => {
+                if !self.move_column(-1) {
+                    if !self.is_submenu {
+                        return Ok(false);
+                    }
+                    term_window.cancel_modal();
+                }
+            }
+            (KeyCode::RightArrow, KeyModifiers::NONE)
+            | (KeyCode::Char('l'), KeyModifiers::NONE) => {
+                let selected = *self.selected_row.borrow();
+                let is_submenu_entry = matches!(
+                    self.items.borrow().get(selected as usize),
+                    Some(MenuItem::SubMenu { .. })
+                );
+                if is_submenu_entry {
+                    self.activate_selected(term_window);
+                    return Ok(true);
+                }
+                self.move_column(1);
+            }
             _ => return Ok(false),
         }
-        term_window.invalidate_modal();
+        self.update_preview(term_window);
+        // Only the selection or scroll position has changed, which
+        // doesn't need the items to be built again by reconfigure
+        if let Some(window) = term_window.window.as_ref() {
+            window.invalidate();
+        }
         Ok(true)
     }
 
@@ -534,27 +2906,1065 @@ impl Modal for ContextMenu {
         &self,
         term_window: &mut TermWindow,
     ) -> anyhow::Result<Ref<'_, [ComputedElement]>> {
-        if self.element.borrow().is_none() {
-            let (element, menu_x, menu_y, menu_width, menu_height, row_bounds) = Self::compute(
+        self.update_element(|menu| {
+            Self::compute(
                 term_window,
-                &self.items,
-                *self.selected_row.borrow(),
-                self.initial_mouse_x,
-                self.initial_mouse_y,
-            )?;
-            self.element.borrow_mut().replace(element);
-            *self.menu_x.borrow_mut() = menu_x;
-            *self.menu_y.borrow_mut() = menu_y;
-            *self.menu_width.borrow_mut() = menu_width;
-            *self.menu_height.borrow_mut() = menu_height;
-            *self.row_bounds.borrow_mut() = row_bounds;
-        }
+                &menu.items.borrow(),
+                *menu.selected_row.borrow(),
+                *menu.scroll_offset.borrow(),
+                *menu.initial_mouse_x.borrow(),
+                *menu.initial_mouse_y.borrow(),
+                *menu.resized_width.borrow(),
+            )
+        })?;
+        self.animate_highlight(term_window);
         Ok(Ref::map(self.element.borrow(), |v| {
             v.as_ref().unwrap().as_slice()
         }))
     }
 
-    fn reconfigure(&self, _term_window: &mut TermWindow) {
+    fn dump(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::json!({
+            "kind": "ContextMenu",
+            "items": serde_json::to_value(&*self.items.borrow())?,
+            "selected_row": *self.selected_row.borrow(),
+        }))
+    }
+
+    fn reconfigure(&self, term_window: &mut TermWindow) {
+        // The window may have been resized; move the anchor so that the
+        // menu stays where it is now, rather than jumping back to where
+        // it was opened if the window grows again
+        let dimensions = term_window.dimensions;
+        let menu_width = *self.menu_width.borrow();
+        let menu_height = *self.menu_height.borrow();
+        let mut x = self.initial_mouse_x.borrow_mut();
+        *x = x.min(dimensions.pixel_width as f32 - menu_width).max(0.);
+        let mut y = self.initial_mouse_y.borrow_mut();
+        *y = y.min(dimensions.pixel_height as f32 - menu_height).max(0.);
+
+        // The configuration may have changed, so build the items
+        // again and give compute another go
+        self.rebuild_items(term_window);
+        self.error_state.borrow_mut().take();
         self.element.borrow_mut().take();
     }
 }
+
+/// Formats `n` with thousands separators, eg: 3,500
+fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut result = String::new();
+    for (idx, c) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod selection_test {
+    use super::*;
+
+    /// Entry / Separator / Entry / Entry / Separator / Entry
+    fn menu() -> ContextMenu {
+        ContextMenu::with_items(
+            vec![
+                MenuItem::nop_entry("one"),
+                MenuItem::Separator,
+                MenuItem::nop_entry("two"),
+                MenuItem::nop_entry("three"),
+                MenuItem::Separator,
+                MenuItem::nop_entry("four"),
+            ],
+            0,
+            0,
+            None,
+        )
+    }
+
+    fn selected(menu: &ContextMenu) -> i32 {
+        *menu.selected_row.borrow()
+    }
+
+    #[test]
+    fn selectable_rows() {
+        let menu = menu();
+        assert_eq!(
+            (-1..7)
+                .map(|row| menu.is_selectable(row))
+                .collect::<Vec<_>>(),
+            vec![false, true, false, true, true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn move_down_skips_separators() {
+        let menu = menu();
+        assert_eq!(selected(&menu), 0);
+        menu.move_down();
+        assert_eq!(selected(&menu), 2);
+        menu.move_down();
+        assert_eq!(selected(&menu), 3);
+        menu.move_down();
+        assert_eq!(selected(&menu), 5);
+        // Stays on the last item
+        menu.move_down();
+        assert_eq!(selected(&menu), 5);
+    }
+
+    #[test]
+    fn move_up_skips_separators() {
+        let menu = menu();
+        menu.set_selection(5);
+        menu.move_up();
+        assert_eq!(selected(&menu), 3);
+        menu.move_up();
+        assert_eq!(selected(&menu), 2);
+        menu.move_up();
+        assert_eq!(selected(&menu), 0);
+    }
+
+    #[test]
+    fn move_column() {
+        let menu = menu();
+        *menu.visible_rows.borrow_mut() = 6;
+        // A single column has nothing beside it
+        *menu.rows_per_column.borrow_mut() = 6;
+        assert!(!menu.move_column(1));
+
+        // one, - | two, three | -, four
+        *menu.rows_per_column.borrow_mut() = 2;
+        assert!(menu.move_column(1));
+        assert_eq!(selected(&menu), 2);
+        // Lands on the nearest selectable item in the column
+        assert!(menu.move_column(1));
+        assert_eq!(selected(&menu), 5);
+        assert!(!menu.move_column(1));
+        assert_eq!(selected(&menu), 5);
+        assert!(menu.move_column(-1));
+        assert_eq!(selected(&menu), 3);
+    }
+
+    #[test]
+    fn move_up_from_first_item() {
+        let menu = menu();
+        menu.move_up();
+        assert_eq!(selected(&menu), 0);
+    }
+
+    #[test]
+    fn selected_item_label() {
+        let menu = menu();
+        assert_eq!(menu.selected_item_label().as_deref(), Some("one"));
+        *menu.selected_row.borrow_mut() = 1;
+        assert_eq!(menu.selected_item_label().as_deref(), None);
+        *menu.selected_row.borrow_mut() = -1;
+        assert_eq!(menu.selected_item_label().as_deref(), None);
+        *menu.selected_row.borrow_mut() = 3;
+        assert_eq!(menu.selected_item_label().as_deref(), Some("three"));
+    }
+
+    #[test]
+    fn move_cyclic_wraps_around() {
+        let menu = menu();
+        *menu.selected_row.borrow_mut() = 5;
+        menu.move_cyclic(1);
+        assert_eq!(selected(&menu), 0);
+        menu.move_cyclic(-1);
+        assert_eq!(selected(&menu), 5);
+        // Separators are skipped on the way
+        menu.move_cyclic(-1);
+        assert_eq!(selected(&menu), 3);
+        menu.move_cyclic(1);
+        assert_eq!(selected(&menu), 5);
+    }
+
+    #[test]
+    fn move_cyclic_without_selection() {
+        let menu = menu();
+        *menu.selected_row.borrow_mut() = -1;
+        menu.move_cyclic(-1);
+        assert_eq!(selected(&menu), 5);
+        *menu.selected_row.borrow_mut() = -1;
+        menu.move_cyclic(1);
+        assert_eq!(selected(&menu), 0);
+    }
+
+    fn computed(colors: ElementColors, content: ComputedElementContent) -> ComputedElement {
+        let rect = euclid::rect(0., 0., 0., 0.);
+        ComputedElement {
+            item_type: None,
+            zindex: 0,
+            bounds: rect,
+            border_rect: rect,
+            border: PixelDimension::default(),
+            border_corners: None,
+            colors,
+            hover_colors: None,
+            shadow: None,
+            backdrop_blur: None,
+            padding: rect,
+            content_rect: rect,
+            baseline: 0.,
+            content,
+        }
+    }
+
+    /// Which of the cached rows are drawn as selected
+    fn highlighted(menu: &ContextMenu) -> Vec<usize> {
+        let element = menu.element.borrow();
+        let selected = menu.row_colors.borrow().selected.bg.clone();
+        match &element.as_ref().expect("cached element was discarded")[0].content {
+            ComputedElementContent::Children(rows) => rows
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| row.colors.bg == selected)
+                .map(|(idx, _)| idx)
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn selection_keeps_cached_element() {
+        let menu = menu();
+        let row_colors = RowColors {
+            normal: ElementColors::default(),
+            selected: ElementColors {
+                border: BorderColor::default(),
+                bg: LinearRgba::with_components(1., 1., 1., 1.).into(),
+                text: LinearRgba::with_components(0., 0., 0., 1.).into(),
+            },
+        };
+        let rows = (0..6)
+            .map(|idx| {
+                let colors = if idx == 0 {
+                    row_colors.selected.clone()
+                } else {
+                    row_colors.normal.clone()
+                };
+                computed(colors, ComputedElementContent::Children(vec![]))
+            })
+            .collect();
+        menu.element.borrow_mut().replace(vec![computed(
+            ElementColors::default(),
+            ComputedElementContent::Children(rows),
+        )]);
+        *menu.row_colors.borrow_mut() = row_colors;
+        *menu.visible_rows.borrow_mut() = 6;
+        *menu.rows_per_column.borrow_mut() = 6;
+
+        menu.move_down();
+        assert_eq!(highlighted(&menu), vec![2]);
+        menu.set_selection(5);
+        assert_eq!(highlighted(&menu), vec![5]);
+        menu.move_up();
+        assert_eq!(highlighted(&menu), vec![3]);
+    }
+
+    #[test]
+    fn selection_starts_slide() {
+        let menu = menu();
+        let selected = ElementColors {
+            border: BorderColor::default(),
+            bg: LinearRgba::with_components(1., 1., 1., 1.).into(),
+            text: LinearRgba::with_components(0., 0., 0., 1.).into(),
+        };
+        let rows = (0..6)
+            .map(|idx| {
+                let mut row = computed(
+                    ElementColors::default(),
+                    ComputedElementContent::Children(vec![]),
+                );
+                row.translate(euclid::vec2(0., idx as f32 * 10.));
+                row
+            })
+            .collect();
+        menu.element.borrow_mut().replace(vec![computed(
+            ElementColors::default(),
+            ComputedElementContent::Children(rows),
+        )]);
+        *menu.row_colors.borrow_mut() = RowColors {
+            normal: ElementColors::default(),
+            selected: selected.clone(),
+        };
+        *menu.selection_animation.borrow_mut() = Some(Duration::from_millis(80));
+        *menu.visible_rows.borrow_mut() = 6;
+        *menu.rows_per_column.borrow_mut() = 6;
+
+        menu.move_down();
+        let (_, from) = menu
+            .highlight_start
+            .borrow()
+            .expect("the highlight to slide");
+        assert_eq!(from, 0.);
+        assert_eq!(*menu.target_y.borrow(), 20.);
+        // The row keeps its text colors, and the sliding
+        // highlight stands in for its background
+        let element = menu.element.borrow();
+        match &element.as_ref().unwrap()[0].content {
+            ComputedElementContent::Children(rows) => {
+                assert_eq!(rows[2].colors.text, selected.text);
+                assert_eq!(
+                    rows[2].colors.bg,
+                    InheritableColor::from(LinearRgba::TRANSPARENT)
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod row_at_coords_test {
+    use super::*;
+
+    /// Six rows of 10px each, starting 5px below the top of a menu that
+    /// spans (10, 20) to (110, 90), as compute would lay them out
+    fn menu() -> ContextMenu {
+        let items = vec![
+            MenuItem::nop_entry("one"),
+            MenuItem::Separator,
+            MenuItem::nop_entry("two"),
+            MenuItem::nop_entry("three"),
+            MenuItem::Separator,
+            MenuItem::nop_entry("four"),
+        ];
+        let menu = ContextMenu::with_items(items, 0, 0, None);
+        *menu.menu_x.borrow_mut() = 10.;
+        *menu.menu_y.borrow_mut() = 20.;
+        *menu.menu_width.borrow_mut() = 100.;
+        *menu.menu_height.borrow_mut() = 70.;
+        *menu.row_bounds.borrow_mut() = (0..6)
+            .map(|idx| RowBounds {
+                x: 15.,
+                y: 25. + idx as f32 * 10.,
+                height: 10.,
+            })
+            .collect();
+        menu
+    }
+
+    #[test]
+    fn columns() {
+        let menu = menu();
+        // Two columns of three rows, the second starting at x = 60
+        *menu.row_bounds.borrow_mut() = (0..6)
+            .map(|idx| RowBounds {
+                x: if idx < 3 { 15. } else { 60. },
+                y: 25. + (idx % 3) as f32 * 10.,
+                height: 10.,
+            })
+            .collect();
+        assert_eq!(menu.row_at_coords(30., 40.), 1);
+        assert_eq!(menu.row_at_coords(70., 40.), 4);
+        // The gap between columns belongs to the one on its left
+        assert_eq!(menu.row_at_coords(59., 30.), 0);
+        assert_eq!(menu.row_at_coords(12., 50.), 2);
+        // Below the last row of the columns
+        assert_eq!(menu.row_at_coords(70., 60.), -1);
+    }
+
+    #[test]
+    fn row_boundaries() {
+        let menu = menu();
+        for row in 0..6 {
+            let top = 25. + row as f32 * 10.;
+            assert_eq!(menu.row_at_coords(50., top), row);
+            assert_eq!(menu.row_at_coords(50., top + 9.99), row);
+        }
+        // The bottom edge of one row is the top of the next
+        assert_eq!(menu.row_at_coords(50., 35.), 1);
+    }
+
+    #[test]
+    fn separator_rows() {
+        let menu = menu();
+        assert_eq!(menu.row_at_coords(50., 40.), 1);
+        assert_eq!(menu.row_at_coords(50., 70.), 4);
+        assert!(!menu.is_selectable(1));
+        assert!(!menu.is_selectable(4));
+    }
+
+    #[test]
+    fn padding() {
+        let menu = menu();
+        // Above the first row and below the last, but inside the menu
+        assert_eq!(menu.row_at_coords(50., 20.), -1);
+        assert_eq!(menu.row_at_coords(50., 24.99), -1);
+        assert_eq!(menu.row_at_coords(50., 85.), -1);
+        assert_eq!(menu.row_at_coords(50., 90.), -1);
+    }
+
+    #[test]
+    fn outside_menu() {
+        let menu = menu();
+        assert_eq!(menu.row_at_coords(9.99, 30.), -1);
+        assert_eq!(menu.row_at_coords(110.01, 30.), -1);
+        assert_eq!(menu.row_at_coords(50., 19.99), -1);
+        assert_eq!(menu.row_at_coords(50., 90.01), -1);
+        // The left and right edges are part of the menu
+        assert_eq!(menu.row_at_coords(10., 30.), 0);
+        assert_eq!(menu.row_at_coords(110., 30.), 0);
+    }
+
+    #[test]
+    fn sweep_stays_in_range() {
+        let menu = menu();
+        let num_items = menu.items.borrow().len() as i32;
+        for scroll_offset in 0..=2 {
+            // compute only records bounds for the rows that are shown
+            *menu.scroll_offset.borrow_mut() = scroll_offset;
+            menu.row_bounds
+                .borrow_mut()
+                .truncate(menu.items.borrow().len() - scroll_offset);
+            for x in (-20..=140).map(|x| x as f32 * 0.75) {
+                for y in (-20..=140).map(|y| y as f32 * 0.75) {
+                    let row = menu.row_at_coords(x, y);
+                    assert!(
+                        row >= -1 && row < num_items,
+                        "row {row} at ({x}, {y}) with offset {scroll_offset}"
+                    );
+                    menu.is_selectable(row);
+                }
+            }
+        }
+        for row in [i32::MIN, -2, num_items, i32::MAX] {
+            assert!(!menu.is_selectable(row));
+        }
+    }
+
+    #[test]
+    fn scrolled() {
+        let menu = menu();
+        *menu.scroll_offset.borrow_mut() = 2;
+        // The row bounds only cover the visible rows
+        assert_eq!(menu.row_at_coords(50., 25.), 2);
+        assert_eq!(menu.row_at_coords(50., 45.), 4);
+    }
+
+    #[test]
+    fn scrollbar() {
+        let menu = menu();
+        *menu.scrollbar_left.borrow_mut() = Some(100.);
+        *menu.visible_rows.borrow_mut() = 3;
+        // Clicks on the scrollbar don't select a row
+        assert_eq!(menu.row_at_coords(105., 30.), -1);
+        assert_eq!(menu.row_at_coords(50., 30.), 0);
+
+        assert_eq!(menu.scroll_offset_at_coords(105., 25.), Some(0));
+        assert_eq!(menu.scroll_offset_at_coords(105., 55.), Some(3));
+        assert_eq!(menu.scroll_offset_at_coords(105., 84.), Some(5));
+        assert_eq!(menu.scroll_offset_at_coords(50., 55.), None);
+        assert_eq!(menu.scroll_offset_at_coords(105., 85.), None);
+
+        // The offset is clamped so that the last rows stay filled
+        menu.scroll_to(5);
+        assert_eq!(*menu.scroll_offset.borrow(), 3);
+    }
+
+    #[test]
+    fn resize_right_edge() {
+        let menu = menu();
+        // The edge is at x=110, and can be grabbed from either side
+        assert!(menu.is_on_right_edge(107., 50., 1.));
+        assert!(menu.is_on_right_edge(113., 50., 1.));
+        assert!(!menu.is_on_right_edge(100., 50., 1.));
+        assert!(!menu.is_on_right_edge(110., 95., 1.));
+        assert!(menu.is_on_right_edge(102., 50., 2.));
+
+        *menu.drag_start_x.borrow_mut() = 110.;
+        menu.resize_to(140., 200.);
+        assert_eq!(*menu.menu_width.borrow(), 130.);
+        menu.resize_to(125., 200.);
+        assert_eq!(*menu.resized_width.borrow(), Some(115.));
+        assert!(menu.element.borrow().is_none());
+
+        // The menu can't be dragged past the edge of the window
+        menu.resize_to(400., 200.);
+        assert_eq!(*menu.menu_width.borrow(), 190.);
+    }
+}
+
+#[cfg(test)]
+mod interleave_sections_test {
+    use super::*;
+
+    fn section(priority: i32, label: &str) -> MenuSection {
+        MenuSection {
+            priority,
+            items: vec![MenuItemDef {
+                label: label.to_string(),
+                icon: None,
+                action: KeyAssignment::Nop,
+                badge: None,
+            }],
+        }
+    }
+
+    fn labels(items: &[MenuItem]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match item {
+                MenuItem::Entry { label, .. }
+                | MenuItem::Checkmark { label, .. }
+                | MenuItem::SubMenu { label, .. } => label.to_string(),
+                MenuItem::Separator => "-".to_string(),
+                MenuItem::Breadcrumb(path) => path.join(" › "),
+                MenuItem::SectionHeader(label) => format!("[{label}]"),
+            })
+            .collect()
+    }
+
+    /// Two built-in sections: "a" at 100, and "b" at 200 which
+    /// starts with a separator of its own
+    fn builtin() -> Vec<MenuItem> {
+        vec![
+            MenuItem::nop_entry("a"),
+            MenuItem::Separator,
+            MenuItem::nop_entry("b"),
+        ]
+    }
+
+    #[test]
+    fn no_user_sections() {
+        let items = ContextMenu::interleave_sections(builtin(), &[(100, 0), (200, 1)], vec![]);
+        assert_eq!(labels(&items), vec!["a", "-", "b"]);
+    }
+
+    #[test]
+    fn ordered_by_priority() {
+        let items = ContextMenu::interleave_sections(
+            builtin(),
+            &[(100, 0), (200, 1)],
+            vec![
+                section(50, "first"),
+                section(150, "middle"),
+                section(300, "last"),
+            ],
+        );
+        assert_eq!(
+            labels(&items),
+            vec!["first", "-", "a", "-", "middle", "-", "b", "-", "last"]
+        );
+    }
+
+    #[test]
+    fn builtin_first_on_ties() {
+        let items = ContextMenu::interleave_sections(
+            builtin(),
+            &[(100, 0), (200, 1)],
+            vec![section(100, "tie")],
+        );
+        assert_eq!(labels(&items), vec!["a", "-", "tie", "-", "b"]);
+    }
+
+    #[test]
+    fn empty_sections_are_dropped() {
+        let items = ContextMenu::interleave_sections(
+            vec![MenuItem::nop_entry("a")],
+            &[(100, 0), (200, 1), (300, 1)],
+            vec![section(250, "user")],
+        );
+        assert_eq!(labels(&items), vec!["a", "-", "user"]);
+    }
+
+    #[test]
+    fn builder() {
+        let menu = ContextMenu::builder()
+            .separator()
+            .section_header("Panes")
+            .entry("a", None, KeyAssignment::Nop)
+            .separator()
+            .separator()
+            .entry("b", Some("cod_check"), KeyAssignment::Nop)
+            .separator()
+            .build(0, 0);
+        assert_eq!(labels(&menu.items.borrow()), vec!["[Panes]", "a", "-", "b"]);
+        // The header can't be selected, so the first entry is
+        assert_eq!(*menu.selected_row.borrow(), 1);
+        assert!(matches!(
+            &menu.items.borrow()[3],
+            MenuItem::Entry {
+                icon: Some(MenuIcon::NerdFont("cod_check")),
+                ..
+            }
+        ));
+    }
+}
+
+#[cfg(test)]
+mod render_state_test {
+    use super::*;
+
+    #[test]
+    fn missing_render_state_is_an_error() {
+        let err = ContextMenu::require_render_state(None)
+            .err()
+            .expect("there is no render state");
+        assert!(matches!(err, ContextMenuError::NoRenderState));
+        assert_eq!(err.to_string(), "render state not available");
+    }
+
+    fn layout() -> MenuLayout {
+        MenuLayout {
+            element: vec![],
+            menu_x: 10.,
+            menu_y: 20.,
+            menu_width: 100.,
+            menu_height: 50.,
+            row_bounds: vec![],
+            scrollbar_left: None,
+            visible_rows: 1,
+            rows_per_column: 1,
+            row_colors: RowColors::default(),
+            selection_animation: None,
+        }
+    }
+
+    #[test]
+    fn missing_render_state_is_retried() {
+        let menu = ContextMenu::with_items(vec![MenuItem::nop_entry("one")], 0, 0, None);
+        let err = menu
+            .update_element(|_| ContextMenu::require_render_state(None).map(|_| layout()))
+            .err()
+            .expect("there is no render state");
+        assert_eq!(err.to_string(), "render state not available");
+        assert!(menu.error_state.borrow().is_none());
+        assert!(menu.element.borrow().is_none());
+
+        // Once the render state is there, the menu is laid out
+        menu.update_element(|_| Ok(layout())).unwrap();
+        assert!(menu.element.borrow().is_some());
+        assert_eq!(*menu.menu_width.borrow(), 100.);
+    }
+
+    #[test]
+    fn other_errors_are_latched() {
+        let menu = ContextMenu::with_items(vec![MenuItem::nop_entry("one")], 0, 0, None);
+        let err = menu
+            .update_element(|_| Err(ContextMenuError::Layout(anyhow::anyhow!("no room"))))
+            .err()
+            .expect("the layout fails");
+        assert_eq!(err.to_string(), "failed to lay out the context menu");
+        assert!(menu.error_state.borrow().is_some());
+
+        // From now on the menu is empty, without laying it out again
+        menu.update_element(|_| panic!("laid out again")).unwrap();
+        assert_eq!(menu.element.borrow().as_ref().map(Vec::len), Some(0));
+    }
+}
+
+#[cfg(test)]
+mod character_info_test {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(CHAR_NAMES.get(&0x41), Some(&"LATIN CAPITAL LETTER A"));
+        // Of the aliases of a control character, the first one
+        assert_eq!(CHAR_NAMES.get(&6), Some(&"ACK"));
+        assert_eq!(CHAR_NAMES.get(&0x378), None);
+    }
+
+    #[test]
+    fn blocks() {
+        assert_eq!(unicode_block('A'), Some("Basic Latin"));
+        assert_eq!(unicode_block('\u{7f}'), Some("Basic Latin"));
+        assert_eq!(unicode_block('\u{80}'), Some("Latin-1 Supplement"));
+        assert_eq!(unicode_block('\u{1F600}'), Some("Emoticons"));
+        assert_eq!(
+            unicode_block('\u{10FFFF}'),
+            Some("Supplementary Private Use Area-B")
+        );
+        // The gap between Kangxi Radicals and Ideographic Description Characters
+        assert_eq!(unicode_block('\u{2FE0}'), None);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod external_command_test {
+    use super::*;
+
+    fn run(command: &str, timeout_ms: u64) -> anyhow::Result<Vec<MenuItemDef>> {
+        ContextMenu::run_external_command(
+            command,
+            &serde_json::json!({"pane_id": 3, "cwd": null, "foreground_process": "vim"}),
+            Duration::from_millis(timeout_ms),
+        )
+    }
+
+    #[test]
+    fn items_from_stdout() {
+        // Only prints the items when it was passed the pane id
+        let items = run(
+            r#"grep -q '"pane_id":3' && echo '[{"label": "Three", "action": {"SendString": "3"}}]'"#,
+            5000,
+        )
+        .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "Three");
+        assert_eq!(items[0].action, KeyAssignment::SendString("3".to_string()));
+    }
+
+    #[test]
+    fn invalid_output() {
+        assert!(run("echo not json", 5000).is_err());
+        assert!(run("exit 1", 5000).is_err());
+    }
+
+    #[test]
+    fn timeout() {
+        let started = Instant::now();
+        let err = run("sleep 10", 50).unwrap_err();
+        assert!(format!("{err:#}").contains("did not finish"), "{err:#}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn prepended_with_separator() {
+        let items = ContextMenu::prepend_external_items(
+            &[MenuItem::nop_entry("ext")],
+            vec![MenuItem::nop_entry("a")],
+        );
+        assert!(matches!(
+            items.as_slice(),
+            [
+                MenuItem::Entry { label: ext, .. },
+                MenuItem::Separator,
+                MenuItem::Entry { label: a, .. },
+            ] if ext == "ext" && a == "a"
+        ));
+        assert_eq!(
+            ContextMenu::prepend_external_items(&[], vec![MenuItem::nop_entry("a")]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn late_items_keep_the_selection() {
+        let menu = ContextMenu::with_items(
+            vec![MenuItem::nop_entry("a"), MenuItem::nop_entry("b")],
+            0,
+            0,
+            None,
+        );
+        *menu.selected_row.borrow_mut() = 1;
+        menu.add_external_items(vec![MenuItem::nop_entry("ext")]);
+
+        let items = menu.items.borrow();
+        assert_eq!(items.len(), 4);
+        let selected = *menu.selected_row.borrow();
+        assert!(matches!(&items[selected as usize], MenuItem::Entry { label, .. } if label == "b"));
+    }
+}
+
+#[cfg(test)]
+mod build_element_test {
+    use super::*;
+    use wezterm_font::FontConfiguration;
+
+    fn style() -> MenuStyle {
+        MenuStyle {
+            bg: LinearRgba::with_components(0.1, 0.1, 0.1, 1.),
+            fg: LinearRgba::with_components(0.9, 0.9, 0.9, 1.),
+            border: LinearRgba::with_components(0.5, 0.5, 0.5, 1.),
+            badge_bg: LinearRgba::with_components(0.8, 0.1, 0.1, 1.),
+            badge_fg: LinearRgba::with_components(1., 1., 1., 1.),
+            breadcrumb_fg: LinearRgba::with_components(0.9, 0.9, 0.9, 0.6),
+            item_padding: 0.1,
+            row_width: Dimension::Cells(20.),
+            corner_radius: 0.5,
+            shadow: Some(BoxShadow {
+                offset_x: 2.,
+                offset_y: 4.,
+                blur_radius: 8.,
+                color: LinearRgba::with_components(0., 0., 0., 0.35),
+            }),
+            backdrop_blur: None,
+        }
+    }
+
+    fn fonts() -> MenuFonts {
+        config::use_test_configuration();
+        let fonts = FontConfiguration::new(None, 96).unwrap();
+        let font = fonts.default_font().unwrap();
+        MenuFonts {
+            item: Rc::clone(&font),
+            breadcrumb: font,
+        }
+    }
+
+    fn dim(d: &Dimension) -> String {
+        match d {
+            Dimension::Cells(n) => format!("{n}c"),
+            Dimension::Pixels(n) => format!("{n}px"),
+            Dimension::Points(n) => format!("{n}pt"),
+            Dimension::Percent(n) => format!("{}%", n * 100.),
+        }
+    }
+
+    /// Top, right, bottom and left; collapsed to one value when they
+    /// are all the same
+    fn box_dim(b: &BoxDimension) -> String {
+        if b.top == b.right && b.top == b.bottom && b.top == b.left {
+            dim(&b.top)
+        } else {
+            format!(
+                "{} {} {} {}",
+                dim(&b.top),
+                dim(&b.right),
+                dim(&b.bottom),
+                dim(&b.left)
+            )
+        }
+    }
+
+    /// Names the colors of `style`, so that the outline doesn't
+    /// change when the color values used by the tests do
+    fn color_name(color: &LinearRgba, style: &MenuStyle) -> String {
+        [
+            (style.bg, "bg"),
+            (style.fg, "fg"),
+            (style.border, "border"),
+            (style.badge_bg, "badge_bg"),
+            (style.badge_fg, "badge_fg"),
+            (style.breadcrumb_fg, "breadcrumb_fg"),
+            (LinearRgba::TRANSPARENT, "transparent"),
+        ]
+        .iter()
+        .find(|(candidate, _)| candidate == color)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("{color:?}"))
+    }
+
+    fn inheritable_name(color: &InheritableColor, style: &MenuStyle) -> Option<String> {
+        match color {
+            InheritableColor::Inherited => None,
+            InheritableColor::Color(color) => Some(color_name(color, style)),
+            InheritableColor::Animated { .. } => Some("animated".to_string()),
+        }
+    }
+
+    /// Describes each element of the tree on its own line, listing
+    /// only the properties that differ from those of `Element::new`
+    fn outline(element: &Element, style: &MenuStyle, depth: usize, lines: &mut Vec<String>) {
+        let mut line = "  ".repeat(depth);
+        match &element.content {
+            ElementContent::Text(text) => line.push_str(&format!("'{text}'")),
+            ElementContent::Children(_) => line.push_str("children"),
+            ElementContent::Poly { .. } => line.push_str("poly"),
+        }
+        if element.display == DisplayType::Block {
+            line.push_str(" display=block");
+        }
+        if element.float == Float::Right {
+            line.push_str(" float=right");
+        }
+        if let Some(min_width) = &element.min_width {
+            line.push_str(&format!(" min_width={}", dim(min_width)));
+        }
+        if let Some(bg) = inheritable_name(&element.colors.bg, style) {
+            line.push_str(&format!(" bg={bg}"));
+        }
+        if let Some(fg) = inheritable_name(&element.colors.text, style) {
+            line.push_str(&format!(" fg={fg}"));
+        }
+        if element.border != BoxDimension::default() {
+            line.push_str(&format!(
+                " border={} {}",
+                box_dim(&element.border),
+                color_name(&element.colors.border.top, style)
+            ));
+        }
+        if element.margin != BoxDimension::default() {
+            line.push_str(&format!(" margin={}", box_dim(&element.margin)));
+        }
+        if element.padding != BoxDimension::default() {
+            line.push_str(&format!(" padding={}", box_dim(&element.padding)));
+        }
+        if let Some(corners) = &element.border_corners {
+            line.push_str(&format!(
+                " corners={}x{}",
+                dim(&corners.top_left.width),
+                dim(&corners.top_left.height)
+            ));
+        }
+        if element.shadow.is_some() {
+            line.push_str(" shadow");
+        }
+        if let Some(sigma) = element.backdrop_blur {
+            line.push_str(&format!(" backdrop_blur={sigma}"));
+        }
+        lines.push(line);
+
+        if let ElementContent::Children(kids) = &element.content {
+            for kid in kids {
+                outline(kid, style, depth + 1, lines);
+            }
+        }
+    }
+
+    fn build(
+        items: &[MenuItem],
+        selected_row: i32,
+        scroll_offset: usize,
+        visible_rows: usize,
+        rows_per_column: usize,
+        style: &MenuStyle,
+    ) -> Vec<String> {
+        let element = ContextMenu::build_element(
+            &fonts(),
+            items,
+            selected_row,
+            scroll_offset,
+            visible_rows,
+            rows_per_column,
+            style,
+        );
+        let mut lines = vec![];
+        outline(&element, style, 0, &mut lines);
+        lines
+    }
+
+    #[test]
+    fn all_item_kinds() {
+        let items = vec![
+            MenuItem::Entry {
+                label: "Copy".into(),
+                icon: Some(MenuIcon::Literal('c')),
+                action: KeyAssignment::Nop,
+                badge: Some(3),
+                preview_action: None,
+            },
+            MenuItem::Separator,
+            MenuItem::SubMenu {
+                label: "Split".into(),
+                icon: None,
+                items: vec![],
+            },
+            MenuItem::Checkmark {
+                label: "Zoom".into(),
+                icon: Some(MenuIcon::Literal('z')),
+                action: KeyAssignment::Nop,
+                checked: false,
+            },
+        ];
+        let style = style();
+        k9::snapshot!(
+            build(&items, 0, 0, items.len(), items.len(), &style),
+            r#"
+[
+    "children bg=bg fg=fg border=1px border margin=0.25c padding=0.25c corners=0.5cx0.5c shadow",
+    "  children display=block min_width=20c bg=fg fg=bg padding=0.1c 0.2c 0.1c 0.2c",
+    "    'c' min_width=2c",
+    "    'Copy'",
+    "    '3' bg=badge_bg fg=badge_fg margin=0c 0c 0c 0.5c padding=0c 0.4c 0c 0.4c corners=0.4cx0.5c",
+    "  children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "    '────────────────────'",
+    "  children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "    ' ' min_width=2c",
+    "    'Split ▶'",
+    "  children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "    'z' min_width=2c",
+    "    'Zoom'",
+]
+"#
+        );
+    }
+
+    #[test]
+    fn breadcrumb() {
+        let items = vec![
+            MenuItem::Breadcrumb(vec!["Context Menu".into(), "Split Pane".into()]),
+            MenuItem::nop_entry("Right"),
+        ];
+        let style = style();
+        k9::snapshot!(
+            build(&items, 1, 0, items.len(), items.len(), &style),
+            r#"
+[
+    "children bg=bg fg=fg border=1px border margin=0.25c padding=0.25c corners=0.5cx0.5c shadow",
+    "  children display=block min_width=20c bg=transparent fg=breadcrumb_fg padding=0.1c 0.2c 0.1c 0.2c",
+    "    'Context Menu › Split Pane'",
+    "  children display=block min_width=20c bg=fg fg=bg padding=0.1c 0.2c 0.1c 0.2c",
+    "    ' ' min_width=2c",
+    "    'Right'",
+]
+"#
+        );
+    }
+
+    #[test]
+    fn scrolled_with_scrollbar() {
+        let items = vec![
+            MenuItem::nop_entry("One"),
+            MenuItem::nop_entry("Two"),
+            MenuItem::nop_entry("Three"),
+            MenuItem::nop_entry("Four"),
+            MenuItem::nop_entry("Five"),
+        ];
+        let style = MenuStyle {
+            corner_radius: 0.,
+            shadow: None,
+            backdrop_blur: Some(8.),
+            ..style()
+        };
+        k9::snapshot!(
+            build(&items, 3, 2, 2, 2, &style),
+            r#"
+[
+    "children bg=bg fg=fg border=1px border margin=0.25c padding=0.25c backdrop_blur=8",
+    "  children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "    ' ' min_width=2c",
+    "    'Three'",
+    "    '░' float=right",
+    "  children display=block min_width=20c bg=fg fg=bg padding=0.1c 0.2c 0.1c 0.2c",
+    "    ' ' min_width=2c",
+    "    'Four'",
+    "    '█' float=right",
+]
+"#
+        );
+    }
+
+    #[test]
+    fn columns() {
+        let items = vec![
+            MenuItem::nop_entry("One"),
+            MenuItem::nop_entry("Two"),
+            MenuItem::nop_entry("Three"),
+            MenuItem::nop_entry("Four"),
+            MenuItem::nop_entry("Five"),
+        ];
+        let style = style();
+        k9::snapshot!(
+            build(&items, 0, 0, 5, 3, &style),
+            r#"
+[
+    "children bg=bg fg=fg border=1px border margin=0.25c padding=0.25c corners=0.5cx0.5c shadow",
+    "  children",
+    "    children display=block min_width=20c bg=fg fg=bg padding=0.1c 0.2c 0.1c 0.2c",
+    "      ' ' min_width=2c",
+    "      'One'",
+    "    children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "      ' ' min_width=2c",
+    "      'Two'",
+    "    children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "      ' ' min_width=2c",
+    "      'Three'",
+    "  children margin=0c 0c 0c 0.5c",
+    "    children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "      ' ' min_width=2c",
+    "      'Four'",
+    "    children display=block min_width=20c bg=transparent fg=fg padding=0.1c 0.2c 0.1c 0.2c",
+    "      ' ' min_width=2c",
+    "      'Five'",
+]
+"#
+        );
+    }
+}
@@ -0,0 +1,352 @@
+//! A small tokenizer used to syntax highlight selected code when it is
+//! copied as HTML. It knows just enough about each language to pick out
+//! keywords, strings, numbers and comments; anything it is unsure about
+//! is left as plain text.
+use mux::pane::{CachePolicy, Pane};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    Shell,
+    JavaScript,
+    C,
+    Go,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Number,
+    Comment,
+}
+
+impl Language {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "rs" => Some(Self::Rust),
+            "py" => Some(Self::Python),
+            "sh" | "bash" | "zsh" => Some(Self::Shell),
+            "js" | "jsx" | "ts" | "tsx" | "mjs" => Some(Self::JavaScript),
+            "c" | "h" | "cc" | "cpp" | "hpp" => Some(Self::C),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// Guesses the language from the file names passed to the program
+    /// running in the pane, eg: `vim src/main.rs`
+    pub fn from_pane(pane: &Arc<dyn Pane>) -> Option<Self> {
+        let info = pane.get_foreground_process_info(CachePolicy::AllowStale)?;
+        info.argv.iter().skip(1).rev().find_map(|arg| {
+            let (_, ext) = arg.rsplit_once('.')?;
+            Self::from_extension(ext)
+        })
+    }
+
+    /// Guesses the language from the code itself, by looking for
+    /// constructs that are distinctive of each one
+    pub fn detect(text: &str) -> Option<Self> {
+        const MARKERS: &[(Language, &[&str])] = &[
+            (
+                Language::Rust,
+                &["fn ", "let mut ", "impl ", "pub fn", "use std::", "-> "],
+            ),
+            (Language::Go, &["func ", "package ", ":= ", "import ("]),
+            (
+                Language::Python,
+                &["def ", "import ", "elif ", "self.", "    return"],
+            ),
+            (
+                Language::JavaScript,
+                &["function ", "const ", "=> ", "console.", "export "],
+            ),
+            (
+                Language::C,
+                &["#include", "int main", "->", "printf(", "NULL"],
+            ),
+            (Language::Shell, &["#!/bin/", "$(", "fi\n", "then", "echo "]),
+        ];
+
+        let (language, score) = MARKERS
+            .iter()
+            .map(|(language, markers)| {
+                let score = markers
+                    .iter()
+                    .filter(|marker| text.contains(*marker))
+                    .count();
+                (*language, score)
+            })
+            .max_by_key(|&(_, score)| score)?;
+        // A single match is too easily a coincidence in prose
+        if score >= 2 {
+            Some(language)
+        } else {
+            None
+        }
+    }
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+                "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+                "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+            ],
+            Self::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def",
+                "del", "elif", "else", "except", "False", "finally", "for", "from", "global", "if",
+                "import", "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise",
+                "return", "True", "try", "while", "with", "yield",
+            ],
+            Self::Shell => &[
+                "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function",
+                "if", "in", "local", "return", "then", "until", "while",
+            ],
+            Self::JavaScript => &[
+                "async",
+                "await",
+                "break",
+                "case",
+                "catch",
+                "class",
+                "const",
+                "continue",
+                "default",
+                "delete",
+                "else",
+                "export",
+                "extends",
+                "false",
+                "finally",
+                "for",
+                "function",
+                "if",
+                "import",
+                "in",
+                "instanceof",
+                "let",
+                "new",
+                "null",
+                "return",
+                "switch",
+                "this",
+                "throw",
+                "true",
+                "try",
+                "typeof",
+                "undefined",
+                "var",
+                "while",
+                "yield",
+            ],
+            Self::C => &[
+                "auto", "break", "case", "char", "const", "continue", "default", "do", "double",
+                "else", "enum", "extern", "float", "for", "goto", "if", "int", "long", "return",
+                "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union",
+                "unsigned", "void", "volatile", "while",
+            ],
+            Self::Go => &[
+                "break",
+                "case",
+                "chan",
+                "const",
+                "continue",
+                "default",
+                "defer",
+                "else",
+                "fallthrough",
+                "for",
+                "func",
+                "go",
+                "goto",
+                "if",
+                "import",
+                "interface",
+                "map",
+                "nil",
+                "package",
+                "range",
+                "return",
+                "select",
+                "struct",
+                "switch",
+                "type",
+                "var",
+            ],
+        }
+    }
+
+    fn line_comment(self) -> &'static str {
+        match self {
+            Self::Python | Self::Shell => "#",
+            Self::Rust | Self::JavaScript | Self::C | Self::Go => "//",
+        }
+    }
+
+    fn has_block_comments(self) -> bool {
+        !matches!(self, Self::Python | Self::Shell)
+    }
+}
+
+/// Splits `text` into tokens; joining the token text
+/// back together gives back the original text
+pub fn tokenize(text: &str, language: Language) -> Vec<(TokenKind, &str)> {
+    let mut tokens = vec![];
+    let mut plain_start = 0;
+    let mut pos = 0;
+
+    let end_of = |kind: TokenKind, start: usize| -> usize {
+        let rest = &text[start..];
+        match kind {
+            TokenKind::Comment if rest.starts_with("/*") => rest
+                .find("*/")
+                .map(|end| start + end + 2)
+                .unwrap_or(text.len()),
+            TokenKind::Comment => rest.find('\n').map(|end| start + end).unwrap_or(text.len()),
+            TokenKind::String => {
+                let quote = rest.chars().next().unwrap();
+                let mut escaped = false;
+                for (idx, c) in rest.char_indices().skip(1) {
+                    if escaped {
+                        escaped = false;
+                    } else if c == '\\' {
+                        escaped = true;
+                    } else if c == quote {
+                        return start + idx + c.len_utf8();
+                    } else if c == '\n' && quote != '`' {
+                        return start + idx;
+                    }
+                }
+                text.len()
+            }
+            _ => {
+                start
+                    + rest
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                        .unwrap_or(rest.len())
+            }
+        }
+    };
+
+    while pos < text.len() {
+        let rest = &text[pos..];
+        let c = rest.chars().next().unwrap();
+        let prev_is_word = text[..pos]
+            .chars()
+            .next_back()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_');
+
+        let token = if rest.starts_with(language.line_comment())
+            || (language.has_block_comments() && rest.starts_with("/*"))
+        {
+            Some((TokenKind::Comment, end_of(TokenKind::Comment, pos)))
+        } else if c == '"' || c == '`' || (c == '\'' && language != Language::Rust) {
+            Some((TokenKind::String, end_of(TokenKind::String, pos)))
+        } else if c.is_ascii_digit() && !prev_is_word {
+            Some((TokenKind::Number, end_of(TokenKind::Number, pos)))
+        } else if (c.is_alphabetic() || c == '_') && !prev_is_word {
+            let end = pos
+                + rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+            if !language.keywords().contains(&&text[pos..end]) {
+                pos = end;
+                continue;
+            }
+            Some((TokenKind::Keyword, end))
+        } else {
+            None
+        };
+
+        match token {
+            Some((kind, end)) => {
+                if plain_start < pos {
+                    tokens.push((TokenKind::Plain, &text[plain_start..pos]));
+                }
+                tokens.push((kind, &text[pos..end]));
+                pos = end;
+                plain_start = end;
+            }
+            None => pos += c.len_utf8(),
+        }
+    }
+    if plain_start < text.len() {
+        tokens.push((TokenKind::Plain, &text[plain_start..]));
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod highlight_test {
+    use super::*;
+
+    fn kinds(text: &str, language: Language) -> Vec<(TokenKind, &str)> {
+        tokenize(text, language)
+            .into_iter()
+            .filter(|(kind, _)| *kind != TokenKind::Plain)
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "fn main() {\n    let s = \"hi \\\"there\\\"\"; // greet\n    /* 42 */ 7\n}";
+        let joined: String = tokenize(text, Language::Rust)
+            .into_iter()
+            .map(|(_, s)| s)
+            .collect();
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn rust_tokens() {
+        assert_eq!(
+            kinds("let x = 42; // answer\nfoo(\"a\")", Language::Rust),
+            vec![
+                (TokenKind::Keyword, "let"),
+                (TokenKind::Number, "42"),
+                (TokenKind::Comment, "// answer"),
+                (TokenKind::String, "\"a\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn keywords_inside_identifiers() {
+        assert_eq!(
+            kinds("letter = iffy + x1", Language::Python),
+            Vec::<(TokenKind, &str)>::new()
+        );
+    }
+
+    #[test]
+    fn shell_comment() {
+        assert_eq!(
+            kinds("if true; then echo 'yes' # ok\nfi", Language::Shell),
+            vec![
+                (TokenKind::Keyword, "if"),
+                (TokenKind::Keyword, "then"),
+                (TokenKind::String, "'yes'"),
+                (TokenKind::Comment, "# ok"),
+                (TokenKind::Keyword, "fi"),
+            ]
+        );
+    }
+
+    #[test]
+    fn detection() {
+        assert_eq!(
+            Language::detect("pub fn main() -> Result<()> {\n    let mut x = 1;\n}"),
+            Some(Language::Rust)
+        );
+        assert_eq!(
+            Language::detect("def foo(self):\n    return self.x\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(Language::detect("just some words here"), None);
+    }
+}
@@ -270,8 +270,10 @@ impl super::TermWindow {
 
         if is_down {
             if only_key_bindings == OnlyKeyBindings::No {
-                if let Some(modal) = self.get_modal() {
-                    if let Key::Code(term_key) = self.win_key_code_to_termwiz_key_code(keycode) {
+                // Offer the key to each modal in turn, starting with
+                // the topmost, until one of them handles it
+                if let Key::Code(term_key) = self.win_key_code_to_termwiz_key_code(keycode) {
+                    for modal in self.get_modals().into_iter().rev() {
                         match modal.key_down(term_key, raw_modifiers.remove_positional_mods(), self)
                         {
                             Ok(true) => return true,
@@ -664,9 +666,14 @@ impl super::TermWindow {
                     self.key_table_state.did_process_key();
                 }
 
-                if let Some(modal) = self.get_modal() {
+                let modals = self.get_modals();
+                if !modals.is_empty() {
                     if window_key.key_is_down {
-                        modal.key_down(key, modifiers, self).ok();
+                        for modal in modals.into_iter().rev() {
+                            if let Ok(true) = modal.key_down(key, modifiers, self) {
+                                break;
+                            }
+                        }
                     }
                     return;
                 }
@@ -0,0 +1,63 @@
+//! Hit testing for the rows of a context menu.
+//! This only uses std, so that fuzz/fuzz_targets/context_menu_coords.rs
+//! can build it without the rest of wezterm-gui.
+use std::convert::TryFrom;
+
+/// Actual pixel bounds of a rendered row, extracted from the computed element tree
+#[derive(Clone, Debug, Default)]
+pub struct RowBounds {
+    /// The left edge of the column that the row is in
+    pub x: f32,
+    pub y: f32,
+    pub height: f32,
+}
+
+/// Where a menu and its visible rows were laid out
+pub struct MenuBounds<'a> {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// The left edge of the scrollbar, when the menu has one
+    pub scrollbar_left: Option<f32>,
+    /// Index of the item in the first visible row
+    pub scroll_offset: usize,
+    /// The visible rows, in item order
+    pub rows: &'a [RowBounds],
+}
+
+/// Returns the index of the item whose row contains (x, y), or -1
+/// when there is none there.
+/// This is within `scroll_offset + rows.len()`, so it is a valid item
+/// index as long as the rows don't run past the end of the items.
+pub fn row_at_coords(menu: &MenuBounds, x: f32, y: f32) -> i32 {
+    // Check if coordinates are within menu bounds
+    if x < menu.x || x > menu.x + menu.width || y < menu.y || y > menu.y + menu.height {
+        return -1;
+    }
+
+    // The scrollbar is handled by scroll_offset_at_coords
+    if menu.scrollbar_left.is_some_and(|left| x >= left) {
+        return -1;
+    }
+
+    // Use actual computed row bounds for hit testing; these only
+    // cover the visible rows, so translate back to an item index.
+    // Each column extends from its left edge to that of the next one
+    let column_x = match menu.rows.first() {
+        Some(first) => menu
+            .rows
+            .iter()
+            .map(|rb| rb.x)
+            .filter(|&left| left <= x)
+            .fold(first.x, f32::max),
+        None => return -1,
+    };
+    for (idx, rb) in menu.rows.iter().enumerate() {
+        if rb.x == column_x && y >= rb.y && y < rb.y + rb.height {
+            return i32::try_from(menu.scroll_offset + idx).unwrap_or(-1);
+        }
+    }
+
+    -1
+}
@@ -5,9 +5,9 @@ use crate::colorease::ColorEase;
 use crate::frontend::{front_end, try_front_end};
 use crate::inputmap::InputMap;
 use crate::overlay::{
-    confirm_close_pane, confirm_close_tab, confirm_close_window, confirm_quit_program, launcher,
-    start_overlay, start_overlay_pane, CopyModeParams, CopyOverlay, LauncherArgs, LauncherFlags,
-    QuickSelectOverlay,
+    confirm_close_other_panes, confirm_close_pane, confirm_close_tab, confirm_close_window,
+    confirm_quit_program, launcher, start_overlay, start_overlay_pane, CopyModeParams, CopyOverlay,
+    LauncherArgs, LauncherFlags, QuickSelectOverlay,
 };
 use crate::resize_increment_calculator::ResizeIncrementCalculator;
 use crate::scripting::guiwin::GuiWin;
@@ -30,8 +30,9 @@ use ::wezterm_term::input::{ClickPosition, MouseButton as TMB};
 use ::window::*;
 use anyhow::{anyhow, ensure, Context};
 use config::keyassignment::{
-    Confirmation, KeyAssignment, LauncherActionArgs, PaneDirection, Pattern, PromptInputLine,
-    QuickSelectArguments, RotationDirection, SpawnCommand, SplitSize,
+    Axis, ClipboardCopyDestination, Confirmation, KeyAssignment, LauncherActionArgs, MenuItemDef,
+    PaneDirection, Pattern, PromptInputLine, QuickSelectArguments, RotationDirection, SpawnCommand,
+    SplitSize,
 };
 use config::window::WindowLevel;
 use config::{
@@ -40,6 +41,7 @@ use config::{
 };
 use lfucache::*;
 use mlua::{FromLua, LuaSerdeExt, UserData, UserDataFields};
+use mux::domain::DomainState;
 use mux::pane::{
     CachePolicy, CloseReason, Pane, PaneId, Pattern as MuxPattern, PerformAssignmentResult,
 };
@@ -54,26 +56,32 @@ use mux_lua::MuxPane;
 use smol::channel::Sender;
 use smol::Timer;
 use std::cell::{RefCell, RefMut};
-use std::collections::{HashMap, LinkedList};
+use std::collections::{HashMap, LinkedList, VecDeque};
 use std::ops::Add;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use termwiz::hyperlink::Hyperlink;
 use termwiz::surface::SequenceNo;
-use wezterm_dynamic::Value;
+use wezterm_dynamic::{ToDynamic, Value};
 use wezterm_font::FontConfiguration;
 use wezterm_term::color::ColorPalette;
 use wezterm_term::input::LastMouseClick;
-use wezterm_term::{Alert, Progress, StableRowIndex, TerminalConfiguration, TerminalSize};
+use wezterm_term::{
+    Alert, Progress, SemanticType, StableRowIndex, TerminalConfiguration, TerminalSize,
+};
 
 pub mod background;
+pub mod blur;
 pub mod box_model;
 pub mod charselect;
 pub mod clipboard;
 pub mod contextmenu;
+mod highlight;
 pub mod keyevent;
+mod menuhit;
 pub mod modal;
 mod mouseevent;
 pub mod palette;
@@ -205,6 +213,23 @@ pub struct PaneState {
 
     bell_start: Option<Instant>,
     pub mouse_terminal_coords: Option<(ClickPosition, StableRowIndex)>,
+    /// Set by SetScrollbackLines to override scrollback_lines
+    /// for this pane
+    scrollback_lines: Option<usize>,
+    /// The lines flagged by FlagCurrentLine, in ascending order
+    flagged_lines: Vec<StableRowIndex>,
+    /// The columns marked by ToggleColumnRuler, in ascending order
+    column_rulers: Vec<usize>,
+    /// Set by SetPaneMouseReporting to keep mouse events from the
+    /// application in this pane, even when it asks for them
+    mouse_reporting_disabled: bool,
+}
+
+/// The visible content of a pane, as captured by SnapshotPane
+pub struct PaneSnapshot {
+    pub name: String,
+    pub taken: chrono::DateTime<chrono::Local>,
+    lines: Vec<termwiz::surface::Line>,
 }
 
 /// Data used when synchronously formatting pane and window titles
@@ -418,6 +443,56 @@ pub struct TermWindow {
     /// Keeps track of double and triple clicks
     last_mouse_click: Option<LastMouseClick>,
 
+    /// When and where the left button went down, while it may
+    /// yet turn into a long-press
+    long_press: Option<(Instant, Point, PaneId)>,
+
+    /// The socket of the read-only mux server started by
+    /// StartReadOnlyShare, and the handle that stops it, if any
+    read_only_share: Option<(PathBuf, wezterm_mux_server_impl::local::StopHandle)>,
+
+    /// Whether ToggleSecureInput is in effect for this window.
+    /// This blocks the clipboard and the context menu announcements,
+    /// and stops the panes from adding to their scrollback.
+    secure_input: bool,
+
+    /// The panes that stopped adding to their scrollback when
+    /// secure input mode was turned on
+    secure_input_panes: Vec<PaneId>,
+
+    /// Whether this window currently holds an enable of the system
+    /// wide secure event input, which the system reference counts
+    secure_input_held: bool,
+
+    /// Whether ToggleDimOnUnfocus is in effect for this window
+    dim_on_unfocus: bool,
+
+    /// Set by ShowFontMetricsOverlay to draw font guide lines
+    show_font_metrics: bool,
+
+    /// Whether TogglePerformanceMode is in effect for this window
+    performance_mode: bool,
+
+    /// Set by ToggleWindowPositionLock to keep the window where it is
+    window_position_locked: bool,
+
+    /// The pane that the selected context menu entry would act on;
+    /// the other panes are dimmed while it is set
+    preview_pane_highlight: Option<PaneId>,
+
+    /// The panes of this window in the order that they were
+    /// focused, most recent first
+    recently_focused_panes: VecDeque<PaneId>,
+
+    /// The values applied by SetWindowOpacity, most recent first
+    recent_opacities: VecDeque<f32>,
+
+    /// Taken by SnapshotPane, oldest first
+    pane_snapshots: Vec<PaneSnapshot>,
+
+    /// The prefix last used by PasteWithPrefix
+    paste_prefix: String,
+
     /// The URL over which we are currently hovering
     current_highlight: Option<Arc<Hyperlink>>,
 
@@ -441,7 +516,9 @@ pub struct TermWindow {
     ui_items: Vec<UIItem>,
     dragging: Option<(UIItem, MouseEvent)>,
 
-    modal: RefCell<Option<Rc<dyn Modal>>>,
+    /// The active modals; the last one is on top and
+    /// receives input first
+    modal_stack: RefCell<Vec<Rc<dyn Modal>>>,
 
     event_states: HashMap<String, EventState>,
     pub current_event: Option<Value>,
@@ -539,6 +616,8 @@ impl TermWindow {
             }
         }
 
+        self.sync_secure_input(window);
+
         // Reset the cursor blink phase
         self.prev_cursor.bump();
 
@@ -724,6 +803,20 @@ impl TermWindow {
             current_mouse_buttons: vec![],
             current_mouse_capture: None,
             last_mouse_click: None,
+            long_press: None,
+            read_only_share: None,
+            secure_input: false,
+            secure_input_panes: vec![],
+            secure_input_held: false,
+            dim_on_unfocus: false,
+            show_font_metrics: false,
+            performance_mode: false,
+            window_position_locked: false,
+            preview_pane_highlight: None,
+            recently_focused_panes: VecDeque::new(),
+            recent_opacities: VecDeque::new(),
+            pane_snapshots: vec![],
+            paste_prefix: String::new(),
             current_highlight: None,
             quad_generation: 0,
             shape_generation: 0,
@@ -785,7 +878,7 @@ impl TermWindow {
             last_ui_item: None,
             is_click_to_focus_window: false,
             key_table_state: KeyTableState::default(),
-            modal: RefCell::new(None),
+            modal_stack: RefCell::new(vec![]),
             opengl_info: None,
         };
 
@@ -1296,10 +1389,18 @@ impl TermWindow {
                 MuxNotification::SaveToDownloads { .. } => {
                     // Handled by frontend
                 }
-                MuxNotification::PaneFocused(_) => {
+                MuxNotification::PaneFocused(pane_id) => {
                     // Also handled by clientpane
+                    self.record_pane_focus(pane_id);
                     self.update_title_post_status();
                 }
+                MuxNotification::PaneRemoved(pane_id) => {
+                    // Flags and rulers don't outlive the pane
+                    if let Some(state) = self.pane_state.borrow_mut().get_mut(&pane_id) {
+                        state.flagged_lines.clear();
+                        state.column_rulers.clear();
+                    }
+                }
                 MuxNotification::TabResized(_) => {
                     // Also handled by wezterm-client
                     self.update_title_post_status();
@@ -1307,9 +1408,37 @@ impl TermWindow {
                 MuxNotification::TabTitleChanged { .. } => {
                     self.update_title_post_status();
                 }
+                MuxNotification::ShowContextMenu { pane_id, items } => {
+                    if self.window_contains_pane(pane_id) {
+                        self.open_context_menu_for_pane(pane_id, items);
+                    }
+                }
+                MuxNotification::DescribeContextMenu { pane_id, reply } => {
+                    if self.window_contains_pane(pane_id) {
+                        match crate::termwindow::contextmenu::ContextMenu::describe(self, pane_id) {
+                            Ok(items_json) => {
+                                reply.try_send(items_json).ok();
+                            }
+                            Err(err) => log::error!(
+                                "describing context menu: {:#}",
+                                anyhow::Error::from(err)
+                            ),
+                        }
+                    }
+                }
+                MuxNotification::DumpModal { reply, .. } => match self.get_modal() {
+                    Some(modal) => match modal.dump() {
+                        Ok(state) => {
+                            reply.try_send(Some(state.to_string())).ok();
+                        }
+                        Err(err) => log::error!("dumping modal: {err:#}"),
+                    },
+                    None => {
+                        reply.try_send(None).ok();
+                    }
+                },
                 MuxNotification::PaneAdded(_)
                 | MuxNotification::WorkspaceRenamed { .. }
-                | MuxNotification::PaneRemoved(_)
                 | MuxNotification::WindowWorkspaceChanged(_)
                 | MuxNotification::ActiveWorkspaceChanged(_)
                 | MuxNotification::Empty
@@ -1324,7 +1453,12 @@ impl TermWindow {
                     .get_pane(pane_id)
                     .ok_or_else(|| anyhow!("pane id {} is not valid", pane_id))?;
 
-                tx.try_send(self.selection_text(&pane))
+                let text = if self.secure_input {
+                    String::new()
+                } else {
+                    self.selection_text(&pane)
+                };
+                tx.try_send(text)
                     .map_err(chan_err)
                     .context("send GetSelectionForPane response")?;
             }
@@ -1465,6 +1599,8 @@ impl TermWindow {
             }
             | MuxNotification::PaneFocused(pane_id)
             | MuxNotification::PaneRemoved(pane_id)
+            | MuxNotification::ShowContextMenu { pane_id, .. }
+            | MuxNotification::DescribeContextMenu { pane_id, .. }
             | MuxNotification::PaneOutput(pane_id) => {
                 // Ideally we'd check to see if pane_id is part of this window,
                 // but overlays may not be 100% associated with the window
@@ -1492,6 +1628,7 @@ impl TermWindow {
             }
             MuxNotification::TabAddedToWindow { window_id, .. }
             | MuxNotification::WindowTitleChanged { window_id, .. }
+            | MuxNotification::DumpModal { window_id, .. }
             | MuxNotification::WindowInvalidated(window_id) => {
                 if window_id != mux_window_id {
                     return true;
@@ -1804,7 +1941,15 @@ impl TermWindow {
                 Arc::new(TermConfig::with_config(config.clone()));
             for tab in window.iter() {
                 for pane in tab.iter_panes_ignoring_zoom() {
-                    pane.pane.set_config(Arc::clone(&term_config));
+                    let scrollback_lines = self.pane_state(pane.pane.pane_id()).scrollback_lines;
+                    match scrollback_lines {
+                        Some(lines) => {
+                            let pane_config = TermConfig::with_config(config.clone());
+                            pane_config.set_scrollback_lines(lines);
+                            pane.pane.set_config(Arc::new(pane_config));
+                        }
+                        None => pane.pane.set_config(Arc::clone(&term_config)),
+                    }
                 }
             }
             for state in self.pane_state.borrow().values() {
@@ -1841,30 +1986,66 @@ impl TermWindow {
     }
 
     fn invalidate_modal(&mut self) {
-        if let Some(modal) = self.get_modal() {
+        let modals = self.get_modals();
+        if modals.is_empty() {
+            return;
+        }
+        for modal in modals {
             modal.reconfigure(self);
-            if let Some(window) = self.window.as_ref() {
-                window.invalidate();
-            }
+        }
+        if let Some(window) = self.window.as_ref() {
+            window.invalidate();
         }
     }
 
+    /// Removes the topmost modal, revealing the one beneath it, if any
     pub fn cancel_modal(&self) {
-        self.modal.borrow_mut().take();
+        self.pop_modal();
+    }
+
+    pub fn cancel_all_modals(&self) {
+        self.modal_stack.borrow_mut().clear();
+        if let Some(window) = self.window.as_ref() {
+            window.invalidate();
+        }
+    }
+
+    pub fn push_modal(&self, modal: Rc<dyn Modal>) {
+        if let Some(menu) = modal.downcast_ref::<crate::termwindow::contextmenu::ContextMenu>() {
+            menu.set_announce(!self.secure_input);
+        }
+        self.modal_stack.borrow_mut().push(modal);
         if let Some(window) = self.window.as_ref() {
             window.invalidate();
         }
     }
 
-    pub fn set_modal(&self, modal: Rc<dyn Modal>) {
-        self.modal.borrow_mut().replace(modal);
+    pub fn pop_modal(&self) -> Option<Rc<dyn Modal>> {
+        let modal = self.modal_stack.borrow_mut().pop();
         if let Some(window) = self.window.as_ref() {
             window.invalidate();
         }
+        modal
     }
 
+    /// Returns the topmost modal
     fn get_modal(&self) -> Option<Rc<dyn Modal>> {
-        self.modal.borrow().as_ref().map(|m| Rc::clone(&m))
+        self.modal_stack.borrow().last().map(Rc::clone)
+    }
+
+    /// Returns true if the topmost modal is a context menu,
+    /// including a sub-menu of one
+    pub fn context_menu_active(&self) -> bool {
+        self.modal_stack.borrow().last().map_or(false, |modal| {
+            modal
+                .downcast_ref::<crate::termwindow::contextmenu::ContextMenu>()
+                .is_some()
+        })
+    }
+
+    /// Returns all of the modals, from the bottom of the stack to the top
+    fn get_modals(&self) -> Vec<Rc<dyn Modal>> {
+        self.modal_stack.borrow().clone()
     }
 
     fn update_scrollbar(&mut self) {
@@ -1947,7 +2128,34 @@ impl TermWindow {
         self.update_title_impl();
     }
 
+    /// Set the title of each auto-named tab to the name of the
+    /// foreground process of its active pane
+    fn update_auto_named_tabs(&self) {
+        let tabs: Vec<Arc<Tab>> = match Mux::get().get_window(self.mux_window_id) {
+            Some(window) => window.iter().cloned().collect(),
+            None => return,
+        };
+        for tab in tabs {
+            if !tab.get_auto_name() {
+                continue;
+            }
+            let name = tab.get_active_pane().and_then(|pane| {
+                pane.get_foreground_process_name(CachePolicy::AllowStale)
+                    .and_then(|path| {
+                        std::path::Path::new(&path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                    })
+            });
+            if let Some(name) = name {
+                tab.set_title(&name);
+            }
+        }
+    }
+
     fn update_title_impl(&mut self) {
+        self.update_auto_named_tabs();
+
         let mux = Mux::get();
         let window = match mux.get_window(self.mux_window_id) {
             Some(window) => window,
@@ -2060,6 +2268,12 @@ impl TermWindow {
             }
         };
 
+        let title = if self.secure_input {
+            format!("🔒 {title}")
+        } else {
+            title
+        };
+
         if let Some(window) = self.window.as_ref() {
             window.set_title(&title);
 
@@ -2313,206 +2527,1256 @@ impl TermWindow {
         promise::spawn::spawn(future).detach();
     }
 
-    fn show_confirmation(&mut self, args: &Confirmation) {
+    fn show_rename_tab_prompt(&mut self) {
         let mux = Mux::get();
         let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
             Some(tab) => tab,
             None => return,
         };
 
-        let pane = match self.get_active_pane_or_overlay() {
-            Some(pane) => pane,
-            None => return,
-        };
-
-        let args = args.clone();
-
-        let gui_win = GuiWin::new(self);
-        let pane = MuxPane(pane.pane_id());
+        let tab_id = tab.tab_id();
+        let title = tab.get_title();
 
         let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
-            crate::overlay::confirm::show_confirmation_overlay(term, args, gui_win, pane)
+            crate::overlay::prompt::show_text_prompt_overlay(
+                term,
+                "Enter new name for tab",
+                title,
+                move |name| {
+                    if let Some(tab) = Mux::get().get_tab(tab_id) {
+                        tab.set_title(&name);
+                    }
+                },
+            )
         });
-        self.assign_overlay(tab.tab_id(), overlay);
+        self.assign_overlay(tab_id, overlay);
         promise::spawn::spawn(future).detach();
     }
 
-    fn show_debug_overlay(&mut self) {
+    fn show_pipe_selection_prompt(&mut self, pane: &Arc<dyn Pane>) {
         let mux = Mux::get();
         let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
             Some(tab) => tab,
             None => return,
         };
 
-        let gui_win = GuiWin::new(self);
-
-        let opengl_info = self.opengl_info.as_deref().unwrap_or("Unknown").to_string();
-        let connection_info = self.connection_name.clone();
+        let pane_id = pane.pane_id();
+        let selection = self.selection_text(pane);
 
         let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
-            crate::overlay::show_debug_overlay(term, gui_win, opengl_info, connection_info)
+            crate::overlay::prompt::show_text_prompt_overlay(
+                term,
+                "Enter a command to pipe the selection through",
+                String::new(),
+                move |command| {
+                    std::thread::spawn(move || {
+                        let output = match pipe_through_command(&command, &selection, None) {
+                            Ok(output) => output,
+                            Err(err) => {
+                                log::error!("Failed to pipe the selection to {command}: {err:#}");
+                                return;
+                            }
+                        };
+                        promise::spawn::spawn_into_main_thread(async move {
+                            if let Some(pane) = Mux::get().get_pane(pane_id) {
+                                pane.send_paste(&output).ok();
+                            }
+                        })
+                        .detach();
+                    });
+                },
+            )
         });
         self.assign_overlay(tab.tab_id(), overlay);
         promise::spawn::spawn(future).detach();
     }
 
-    fn show_tab_navigator(&mut self) {
+    fn show_export_config_prompt(&mut self) {
         let mux = Mux::get();
-        let active_tab_idx = match mux.get_window(self.mux_window_id) {
-            Some(mux_window) => mux_window.get_active_idx(),
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
             None => return,
         };
-        let title = "Tab Navigator".to_string();
-        let args = LauncherActionArgs {
-            title: Some(title),
-            flags: LauncherFlags::TABS,
-            help_text: None,
-            fuzzy_help_text: None,
-            alphabet: None,
-        };
-        self.show_launcher_impl(args, active_tab_idx);
-    }
 
-    fn show_launcher(&mut self) {
-        let title = "Launcher".to_string();
-        let args = LauncherActionArgs {
-            title: Some(title),
-            flags: LauncherFlags::LAUNCH_MENU_ITEMS
-                | LauncherFlags::WORKSPACES
-                | LauncherFlags::DOMAINS
-                | LauncherFlags::KEY_ASSIGNMENTS
-                | LauncherFlags::COMMANDS,
-            help_text: None,
-            fuzzy_help_text: None,
-            alphabet: None,
+        let json = config::dynamic_to_json(&self.config.to_dynamic());
+        let json = match serde_json::to_string_pretty(&json) {
+            Ok(json) => json,
+            Err(err) => {
+                log::error!("Failed to serialize the effective config: {err:#}");
+                return;
+            }
         };
-        self.show_launcher_impl(args, 0);
-    }
+        let initial_value = config::HOME_DIR
+            .join("wezterm-effective-config.json")
+            .display()
+            .to_string();
 
-    fn show_launcher_impl(&mut self, args: LauncherActionArgs, initial_choice_idx: usize) {
-        let mux_window_id = self.mux_window_id;
-        let window = self.window.as_ref().unwrap().clone();
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::prompt::show_text_prompt_overlay(
+                term,
+                "Enter the path of the file to write the effective config to",
+                initial_value,
+                move |path| {
+                    let path = match path.strip_prefix("~/") {
+                        Some(rest) => config::HOME_DIR.join(rest),
+                        None => PathBuf::from(path),
+                    };
+                    match std::fs::write(&path, json) {
+                        Ok(()) => log::info!("Wrote effective config to {}", path.display()),
+                        Err(err) => log::error!(
+                            "Failed to write effective config to {}: {err:#}",
+                            path.display()
+                        ),
+                    }
+                },
+            )
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
 
+    fn show_rename_workspace_prompt(&mut self) {
         let mux = Mux::get();
         let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
             Some(tab) => tab,
             None => return,
         };
-
-        let pane = match self.get_active_pane_or_overlay() {
-            Some(pane) => pane,
+        let workspace = match mux.get_window(self.mux_window_id) {
+            Some(window) => window.get_workspace().to_string(),
+            None => return,
+        };
+        let window = match self.window.clone() {
+            Some(window) => window,
             None => return,
         };
 
-        let domain_id_of_current_pane = tab
-            .get_active_pane()
-            .expect("tab has no panes!")
-            .domain_id();
-        let pane_id = pane.pane_id();
-        let tab_id = tab.tab_id();
-        let title = args.title.unwrap();
-        let flags = args.flags;
-        let help_text = args.help_text.unwrap_or(
-            "Select an item and press Enter=launch  \
-             Esc=cancel  /=filter"
-                .to_string(),
-        );
-        let fuzzy_help_text = args
-            .fuzzy_help_text
-            .unwrap_or("Fuzzy matching: ".to_string());
-
-        let config = &self.config;
-        let alphabet = args.alphabet.unwrap_or(config.launcher_alphabet.clone());
-
-        promise::spawn::spawn(async move {
-            let args = LauncherArgs::new(
-                &title,
-                flags,
-                mux_window_id,
-                pane_id,
-                domain_id_of_current_pane,
-                &help_text,
-                &fuzzy_help_text,
-                &alphabet,
+        let initial_value = workspace.clone();
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::prompt::show_text_prompt_overlay(
+                term,
+                "Enter new name for workspace",
+                initial_value,
+                move |name| {
+                    Mux::get().rename_workspace(&workspace, &name);
+                    // The window title may include the workspace name
+                    window.notify(TermWindowNotif::Apply(Box::new(|term_window| {
+                        term_window.update_title();
+                    })));
+                },
             )
-            .await;
-
-            let win = window.clone();
-            win.notify(TermWindowNotif::Apply(Box::new(move |term_window| {
-                let mux = Mux::get();
-                if let Some(tab) = mux.get_tab(tab_id) {
-                    let window = window.clone();
-                    let (overlay, future) =
-                        start_overlay(term_window, &tab, move |_tab_id, term| {
-                            launcher(args, term, window, initial_choice_idx)
-                        });
-
-                    term_window.assign_overlay(tab_id, overlay);
-                    promise::spawn::spawn(future).detach();
-                }
-            })));
-        })
-        .detach();
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
     }
 
-    /// Returns the Prompt semantic zones
-    fn get_semantic_prompt_zones(&mut self, pane: &Arc<dyn Pane>) -> &[StableRowIndex] {
-        let cache = self
-            .semantic_zones
-            .entry(pane.pane_id())
-            .or_insert_with(SemanticZoneCache::default);
+    fn record_pane_focus(&mut self, pane_id: PaneId) {
+        const MAX_PANE_HISTORY: usize = 10;
 
-        let seqno = pane.get_current_seqno();
-        if cache.seqno != seqno {
-            let zones = pane.get_semantic_zones().unwrap_or_else(|_| vec![]);
-            let mut zones: Vec<StableRowIndex> = zones
-                .into_iter()
-                .filter_map(|zone| {
-                    if zone.semantic_type == wezterm_term::SemanticType::Prompt {
-                        Some(zone.start_y)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            // dedup to avoid issues where both left and right prompts are
-            // defined: we only care if there were 1+ prompts on a line,
-            // not about how many prompts are on a line.
-            // <https://github.com/wezterm/wezterm/issues/1121>
-            zones.dedup();
-            cache.zones = zones;
-            cache.seqno = seqno;
+        let mux = Mux::get();
+        match mux.resolve_pane_id(pane_id) {
+            Some((_domain_id, window_id, _tab_id)) if window_id == self.mux_window_id => {}
+            _ => return,
         }
-        &cache.zones
+        self.recently_focused_panes.retain(|&id| id != pane_id);
+        self.recently_focused_panes.push_front(pane_id);
+        self.recently_focused_panes.truncate(MAX_PANE_HISTORY);
     }
 
-    fn scroll_to_prompt(&mut self, amount: isize, pane: &Arc<dyn Pane>) -> anyhow::Result<()> {
-        let dims = pane.get_dimensions();
-        let position = self
-            .get_viewport(pane.pane_id())
-            .unwrap_or(dims.physical_top);
-        let zone = {
-            let zones = self.get_semantic_prompt_zones(&pane);
-            let idx = match zones.binary_search(&position) {
-                Ok(idx) | Err(idx) => idx,
-            };
-            let idx = ((idx as isize) + amount).max(0) as usize;
-            zones.get(idx).cloned()
+    /// Returns the panes that are still part of this window,
+    /// in the order that they were focused, most recent first
+    pub fn recently_focused_panes(&self) -> Vec<PaneId> {
+        let mux = Mux::get();
+        self.recently_focused_panes
+            .iter()
+            .copied()
+            .filter(|&pane_id| {
+                matches!(
+                    mux.resolve_pane_id(pane_id),
+                    Some((_domain_id, window_id, _tab_id)) if window_id == self.mux_window_id
+                )
+            })
+            .collect()
+    }
+
+    pub fn activate_pane_by_id(&mut self, pane_id: PaneId) {
+        let mux = Mux::get();
+        let (pane, tab_id) = match (mux.get_pane(pane_id), mux.resolve_pane_id(pane_id)) {
+            (Some(pane), Some((_domain_id, window_id, tab_id)))
+                if window_id == self.mux_window_id =>
+            {
+                (pane, tab_id)
+            }
+            _ => return,
         };
-        if let Some(zone) = zone {
-            self.set_viewport(pane.pane_id(), Some(zone), dims);
+        if let Some(mut window) = mux.get_window_mut(self.mux_window_id) {
+            if let Some(tab_idx) = window.idx_by_id(tab_id) {
+                window.save_and_then_set_active(tab_idx);
+            }
         }
-
-        if let Some(win) = self.window.as_ref() {
-            win.invalidate();
+        if let Some(tab) = mux.get_tab(tab_id) {
+            tab.set_active_pane(&pane);
         }
-        Ok(())
     }
 
-    fn scroll_by_page(&mut self, amount: f64, pane: &Arc<dyn Pane>) -> anyhow::Result<()> {
-        let dims = pane.get_dimensions();
-        let position = self
-            .get_viewport(pane.pane_id())
+    /// Opens a context menu made up of just `items`, with its
+    /// top left corner at the specified position. Its actions apply
+    /// to `target_pane_id`, or to the active pane if that is None.
+    pub fn open_context_menu(
+        &mut self,
+        items: Vec<MenuItemDef>,
+        x: isize,
+        y: isize,
+        target_pane_id: Option<PaneId>,
+    ) {
+        let modal = crate::termwindow::contextmenu::ContextMenu::from_item_defs(
+            &items,
+            x,
+            y,
+            target_pane_id,
+        );
+        self.push_modal(Rc::new(modal));
+    }
+
+    /// Opens a context menu made up of `items` just below the cursor
+    /// of the specified pane, whose actions then apply to that pane.
+    /// This is how `wezterm cli context-menu` shows its menus.
+    fn open_context_menu_for_pane(&mut self, pane_id: PaneId, items: Vec<MenuItemDef>) {
+        let (x, y) = match self
+            .get_panes_to_render()
+            .into_iter()
+            .find(|pos| pos.pane.pane_id() == pane_id)
+        {
+            Some(pos) => {
+                let cursor = pos.pane.get_cursor_position();
+                let top = pos.pane.get_dimensions().physical_top;
+                let tab_bar_height = if self.show_tab_bar && !self.config.tab_bar_at_bottom {
+                    self.tab_bar_pixel_height().unwrap_or(0.)
+                } else {
+                    0.
+                };
+                let (padding_left, padding_top) = self.padding_left_top();
+                (
+                    ((cursor.x + pos.left) as isize).max(0) * self.render_metrics.cell_size.width
+                        + padding_left as isize,
+                    ((cursor.y + 1 + pos.top as isize - top).max(0)
+                        * self.render_metrics.cell_size.height)
+                        + tab_bar_height as isize
+                        + padding_top as isize,
+                )
+            }
+            None => self.context_menu_position(),
+        };
+        let modal = crate::termwindow::contextmenu::ContextMenu::from_item_defs(
+            &items,
+            x,
+            y,
+            Some(pane_id),
+        );
+        self.push_modal(Rc::new(modal));
+        if let Some(window) = self.window.as_ref() {
+            window.invalidate();
+        }
+    }
+
+    /// Get the mouse position from the current event, or use a default position
+    pub fn context_menu_position(&self) -> (isize, isize) {
+        self.current_mouse_event
+            .as_ref()
+            .map(|e| (e.coords.x, e.coords.y))
+            .unwrap_or((100, 100))
+    }
+
+    fn toggle_secure_input(&mut self) {
+        self.secure_input = !self.secure_input;
+        let mux = Mux::get();
+        if self.secure_input {
+            // Keep what is shown while a password is entered out of the
+            // scrollback; the lines scrolled off the top are discarded
+            if let Some(window) = mux.get_window(self.mux_window_id) {
+                for tab in window.iter() {
+                    for pos in tab.iter_panes_ignoring_zoom() {
+                        pos.pane.set_retain_scrollback(false);
+                        self.secure_input_panes.push(pos.pane.pane_id());
+                    }
+                }
+            }
+        } else {
+            for pane_id in self.secure_input_panes.drain(..) {
+                if let Some(pane) = mux.get_pane(pane_id) {
+                    pane.set_retain_scrollback(true);
+                }
+            }
+        }
+        log::info!(
+            "secure input mode {}",
+            if self.secure_input {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+        if let Some(window) = self.window.clone() {
+            self.sync_secure_input(&window);
+        }
+        self.update_title();
+    }
+
+    /// Secure event input is system wide, so only hold it while
+    /// this window has the focus.  The system counts enables, so
+    /// only tell it about changes to whether we hold one.
+    fn sync_secure_input(&mut self, window: &Window) {
+        let wanted = self.secure_input && self.focused.is_some();
+        if wanted != self.secure_input_held {
+            window.set_secure_input(wanted);
+            self.secure_input_held = wanted;
+        }
+    }
+
+    pub fn is_secure_input(&self) -> bool {
+        self.secure_input
+    }
+
+    pub fn is_dim_on_unfocus(&self) -> bool {
+        self.dim_on_unfocus
+    }
+
+    pub fn is_showing_font_metrics(&self) -> bool {
+        self.show_font_metrics
+    }
+
+    pub fn is_performance_mode(&self) -> bool {
+        self.performance_mode
+    }
+
+    pub fn is_window_position_locked(&self) -> bool {
+        self.window_position_locked
+    }
+
+    /// Shows a preview of what `assignment` would do to `pane_id`,
+    /// replacing any previous preview, without performing it.
+    /// Only some assignments have a preview; for the others, and for
+    /// None, the current preview is removed.
+    pub fn preview_key_assignment(
+        &mut self,
+        pane_id: Option<PaneId>,
+        assignment: Option<&KeyAssignment>,
+    ) {
+        let highlight = match assignment {
+            Some(KeyAssignment::CloseCurrentPane { .. }) => pane_id,
+            _ => None,
+        };
+        if highlight != self.preview_pane_highlight {
+            self.preview_pane_highlight = highlight;
+            if let Some(window) = self.window.as_ref() {
+                window.invalidate();
+            }
+        }
+    }
+
+    /// The preview is shown from a context menu, so it is
+    /// ignored once that menu has been closed
+    pub fn preview_pane_highlight(&self) -> Option<PaneId> {
+        if self.context_menu_active() {
+            self.preview_pane_highlight
+        } else {
+            None
+        }
+    }
+
+    /// Drags that we handle ourselves are refused while locked;
+    /// the native title bar is only locked on macOS
+    fn toggle_window_position_lock(&mut self) {
+        self.window_position_locked = !self.window_position_locked;
+        self.window_drag_position.take();
+        if let Some(window) = self.window.as_ref() {
+            window.set_movable(!self.window_position_locked);
+        }
+    }
+
+    /// Animations are turned off by overriding animation_fps, and the
+    /// frame rate is capped by overriding max_fps; transparency and
+    /// shadows are left out when rendering
+    fn toggle_performance_mode(&mut self) {
+        self.performance_mode = !self.performance_mode;
+        if self.performance_mode {
+            let max_fps = self.config.performance_mode_max_fps.max(1);
+            self.update_config_overrides(|overrides| {
+                overrides.insert(Value::String("max_fps".to_string()), Value::U64(max_fps));
+                overrides.insert(Value::String("animation_fps".to_string()), Value::U64(1));
+            });
+        } else {
+            self.update_config_overrides(|overrides| {
+                overrides.remove(&Value::String("max_fps".to_string()));
+                overrides.remove(&Value::String("animation_fps".to_string()));
+            });
+        }
+        self.quad_generation += 1;
+        if let Some(window) = self.window.as_ref() {
+            window.invalidate();
+        }
+    }
+
+    /// The opacity to use for the window background, taking
+    /// ToggleDimOnUnfocus and TogglePerformanceMode into account
+    pub fn window_background_opacity(&self) -> f32 {
+        if self.performance_mode {
+            1.0
+        } else if self.dim_on_unfocus && self.focused.is_none() {
+            self.config.dim_when_unfocused_opacity
+        } else {
+            self.config.window_background_opacity
+        }
+    }
+
+    /// Overrides cursor_blink_rate for this window only, in the same
+    /// way that window:set_config_overrides would
+    fn set_cursor_blink_rate(&mut self, rate_ms: u32) {
+        self.set_config_override("cursor_blink_rate", Value::U64(rate_ms.into()));
+    }
+
+    /// Overrides window_background_opacity for this window only,
+    /// remembering both the old and new values so that the user
+    /// can switch back and forth between them
+    fn set_window_opacity(&mut self, opacity: f32) {
+        const MAX_RECENT_OPACITIES: usize = 5;
+
+        let opacity = opacity.clamp(0., 1.);
+        for value in [self.config.window_background_opacity, opacity] {
+            self.recent_opacities.retain(|&v| v != value);
+            self.recent_opacities.push_front(value);
+        }
+        self.recent_opacities.truncate(MAX_RECENT_OPACITIES);
+
+        self.set_config_override(
+            "window_background_opacity",
+            Value::F64(f64::from(opacity).into()),
+        );
+    }
+
+    /// Returns the opacities applied to this window, most recent first
+    pub fn recent_opacities(&self) -> Vec<f32> {
+        self.recent_opacities.iter().copied().collect()
+    }
+
+    /// Sets `key` in the config overrides for this window, in
+    /// the same way that window:set_config_overrides would
+    fn set_config_override(&mut self, key: &str, value: Value) {
+        self.update_config_overrides(|overrides| {
+            overrides.insert(Value::String(key.to_string()), value);
+        });
+    }
+
+    fn update_config_overrides<F: FnOnce(&mut wezterm_dynamic::Object)>(&mut self, func: F) {
+        let mut overrides = match &self.config_overrides {
+            Value::Object(obj) => obj.clone(),
+            _ => wezterm_dynamic::Object::default(),
+        };
+        func(&mut overrides);
+        let overrides = Value::Object(overrides);
+        if overrides != self.config_overrides {
+            self.config_overrides = overrides;
+            self.config_was_reloaded();
+        }
+    }
+
+    fn show_send_input_prompt(&mut self, pane_id: PaneId) {
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+        let title = match mux.get_pane(pane_id) {
+            Some(pane) => pane.get_title(),
+            None => return,
+        };
+
+        let description = format!("Enter text to send to pane {pane_id} ({title})");
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::prompt::show_text_prompt_overlay(
+                term,
+                &description,
+                String::new(),
+                move |line| {
+                    use std::io::Write;
+                    if let Some(pane) = Mux::get().get_pane(pane_id) {
+                        // Send it as though it was typed, including
+                        // the return key so that the command runs
+                        let text = format!("{line}\r");
+                        if let Err(err) = pane.writer().write_all(text.as_bytes()) {
+                            log::error!("Error sending input to pane {pane_id}: {err:#}");
+                        }
+                    }
+                },
+            )
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn start_read_only_share(&mut self) {
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let socket_path = match &self.read_only_share {
+            Some((path, _stop)) => path.clone(),
+            None => {
+                let path = config::RUNTIME_DIR.join(format!(
+                    "share-{}-{}",
+                    std::process::id(),
+                    self.mux_window_id
+                ));
+                let listener = wezterm_mux_server_impl::local::LocalListener::with_domain(
+                    &config::UnixDomain {
+                        socket_path: Some(path.clone()),
+                        ..Default::default()
+                    },
+                );
+                let stop = match listener {
+                    Ok(mut listener) => {
+                        listener.set_read_only(Some(self.mux_window_id));
+                        let stop = listener.stop_handle(&path);
+                        let sock = path.clone();
+                        std::thread::spawn(move || {
+                            listener.run();
+                            std::fs::remove_file(sock).ok();
+                        });
+                        stop
+                    }
+                    Err(err) => {
+                        log::error!("Unable to start read-only share: {:#}", err);
+                        return;
+                    }
+                };
+                self.read_only_share.replace((path.clone(), stop));
+                path
+            }
+        };
+
+        let path = socket_path.display();
+        let message = [
+            format!("This window is being shared read-only via {path}"),
+            String::new(),
+            "Clients can observe the panes in this window, but cannot".to_string(),
+            "type into, resize or otherwise change them, and cannot".to_string(),
+            "see the other windows.".to_string(),
+            String::new(),
+            "The share is a local socket, so it can only be reached".to_string(),
+            "from this machine. Use \"Stop Sharing Window\" to end it".to_string(),
+            "and disconnect its clients.".to_string(),
+            String::new(),
+            "To view it from another session on this machine, add".to_string(),
+            "this to the wezterm configuration there:".to_string(),
+            String::new(),
+            "config.unix_domains = {".to_string(),
+            format!("  {{ name = 'share', socket_path = '{path}',"),
+            "    no_serve_automatically = true },".to_string(),
+            "}".to_string(),
+            String::new(),
+            "and then run: wezterm connect share".to_string(),
+        ]
+        .join("\n");
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::prompt::show_message_overlay(term, &message)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn stop_read_only_share(&mut self) {
+        if let Some((path, stop)) = self.read_only_share.take() {
+            log::info!("Stopping read-only share via {}", path.display());
+            stop.stop();
+        }
+    }
+
+    pub fn is_read_only_share_active(&self) -> bool {
+        self.read_only_share.is_some()
+    }
+
+    fn show_confirmation(&mut self, args: &Confirmation) {
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let pane = match self.get_active_pane_or_overlay() {
+            Some(pane) => pane,
+            None => return,
+        };
+
+        let args = args.clone();
+
+        let gui_win = GuiWin::new(self);
+        let pane = MuxPane(pane.pane_id());
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::confirm::show_confirmation_overlay(term, args, gui_win, pane)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_scrollback_filter(&mut self, pane: &Arc<dyn Pane>) {
+        let dims = pane.get_dimensions();
+        let (_, lines) = pane.get_lines(
+            dims.scrollback_top..dims.physical_top + dims.viewport_rows as StableRowIndex,
+        );
+
+        let (overlay, future) = start_overlay_pane(self, pane, move |_pane_id, term| {
+            crate::overlay::filter::show_scrollback_filter(term, lines)
+        });
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_glyph_atlas(&mut self, pane: &Arc<dyn Pane>) {
+        let (side, sprites) = match self.render_state.as_ref() {
+            Some(render_state) => {
+                let glyph_cache = render_state.glyph_cache.borrow();
+                (glyph_cache.atlas.size(), glyph_cache.sprite_coords())
+            }
+            None => return,
+        };
+
+        let (overlay, future) = start_overlay_pane(self, pane, move |_pane_id, term| {
+            crate::overlay::glyph_atlas::show_glyph_atlas(term, side, sprites)
+        });
+        self.assign_overlay_for_pane(pane.pane_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    /// Copies the text in the viewport as plain text, without any
+    /// trailing whitespace on each line or blank lines at the end
+    fn copy_visible_text(&mut self, pane: &Arc<dyn Pane>) {
+        let dims = pane.get_dimensions();
+        let top = self
+            .get_viewport(pane.pane_id())
+            .unwrap_or(dims.physical_top);
+        let (_, lines) = pane.get_lines(top..top + dims.viewport_rows as StableRowIndex);
+        let mut text: Vec<String> = lines
+            .iter()
+            .map(|line| line.as_str().trim_end().to_string())
+            .collect();
+        while text.last().map_or(false, |line| line.is_empty()) {
+            text.pop();
+        }
+        if !text.is_empty() {
+            self.copy_to_clipboard(
+                ClipboardCopyDestination::ClipboardAndPrimarySelection,
+                text.join("\n"),
+            );
+        }
+    }
+
+    /// Sends a tab to the shell to complete the partial command at the
+    /// prompt. Shells that have several candidates list them below the
+    /// prompt; once the shell has had a moment to do that, the listed
+    /// candidates are offered in a menu.
+    fn complete_command(&mut self, pane: &Arc<dyn Pane>) {
+        use std::io::Write;
+
+        let partial = match partial_command_at_cursor(pane) {
+            Some(partial) => partial,
+            None => return,
+        };
+        let word = match last_word(&partial) {
+            Some(word) => word.to_string(),
+            None => return,
+        };
+        let cursor_row = pane.get_cursor_position().y;
+
+        if let Err(err) = pane.writer().write_all(b"\t") {
+            log::error!("Failed to send tab for completion: {err:#}");
+            return;
+        }
+
+        let window = match self.window.clone() {
+            Some(window) => window,
+            None => return,
+        };
+        let pane_id = pane.pane_id();
+        promise::spawn::spawn(async move {
+            Timer::after(Duration::from_millis(250)).await;
+            window.notify(TermWindowNotif::Apply(Box::new(move |myself| {
+                myself.show_completion_candidates(pane_id, cursor_row, &word);
+            })));
+        })
+        .detach();
+    }
+
+    /// Offers the words that the shell listed between `prompt_row` and
+    /// the redrawn prompt as completions of `word`
+    fn show_completion_candidates(
+        &mut self,
+        pane_id: PaneId,
+        prompt_row: StableRowIndex,
+        word: &str,
+    ) {
+        let pane = match Mux::get().get_pane(pane_id) {
+            Some(pane) => pane,
+            None => return,
+        };
+        let cursor_row = pane.get_cursor_position().y;
+        if cursor_row <= prompt_row + 1 {
+            // Nothing was listed; the shell either completed the
+            // word in place or had nothing to offer
+            return;
+        }
+
+        let (_, lines) = pane.get_lines(prompt_row + 1..cursor_row);
+        let mut candidates: Vec<String> = vec![];
+        for line in &lines {
+            for candidate in line.as_str().split_whitespace() {
+                if candidate.len() > word.len()
+                    && candidate.starts_with(word)
+                    && !candidates.iter().any(|c| c == candidate)
+                {
+                    candidates.push(candidate.to_string());
+                }
+            }
+        }
+        if candidates.len() < 2 {
+            return;
+        }
+
+        // The shell may have extended the word in place, eg: to the
+        // prefix that the candidates have in common, so only send what
+        // goes beyond the word as it is now
+        let current_word = partial_command_at_cursor(&pane)
+            .and_then(|partial| last_word(&partial).map(str::to_string))
+            .unwrap_or_default();
+        let items: Vec<MenuItemDef> = candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                let suffix = candidate.strip_prefix(current_word.as_str())?.to_string();
+                Some(MenuItemDef {
+                    action: KeyAssignment::SendString(suffix),
+                    label: candidate,
+                    icon: None,
+                    badge: None,
+                })
+            })
+            .collect();
+        if items.is_empty() {
+            return;
+        }
+        self.open_context_menu_for_pane(pane_id, items);
+    }
+
+    fn snapshot_pane(&mut self, pane: &Arc<dyn Pane>) {
+        let dims = pane.get_dimensions();
+        let top = self
+            .get_viewport(pane.pane_id())
+            .unwrap_or(dims.physical_top);
+        let (_, lines) = pane.get_lines(top..top + dims.viewport_rows as StableRowIndex);
+        self.pane_snapshots.push(PaneSnapshot {
+            name: pane.get_title(),
+            taken: chrono::Local::now(),
+            lines,
+        });
+    }
+
+    pub fn pane_snapshots(&self) -> &[PaneSnapshot] {
+        &self.pane_snapshots
+    }
+
+    /// Shows a snapshot in a new tab, which closes again when
+    /// the user presses ESC
+    fn restore_pane_snapshot(&mut self, idx: usize) {
+        let lines = match self.pane_snapshots.get(idx) {
+            Some(snapshot) => snapshot.lines.clone(),
+            None => return,
+        };
+        promise::spawn::spawn(mux::termwiztermtab::run(
+            self.terminal_size,
+            Some(self.mux_window_id),
+            move |term| crate::overlay::snapshot::show_pane_snapshot(term, lines),
+            None,
+        ))
+        .detach();
+    }
+
+    /// The renderer is chosen when a window is created, so rather than
+    /// switching this window over, this overrides front_end and opens a
+    /// new window that uses it.
+    /// The window crate reads front_end from the global config when it
+    /// sets up OpenGL, so the override can't be limited to that window:
+    /// it reloads the config of every window, and applies to each window
+    /// opened afterwards too, until it is toggled back.
+    fn toggle_software_renderer(&mut self) -> anyhow::Result<()> {
+        let mut overrides = config::get_config_overrides();
+        let had_override = overrides.iter().any(|(key, _)| key == "front_end");
+        overrides.retain(|(key, _)| key != "front_end");
+        if !had_override {
+            let front_end = if configuration().front_end == FrontEndSelection::Software {
+                "OpenGL"
+            } else {
+                "Software"
+            };
+            overrides.push(("front_end".to_string(), format!("{front_end:?}")));
+        }
+        config::set_config_overrides(&overrides)?;
+        config::reload();
+        if had_override {
+            log::warn!(
+                "Removed the front_end override; new windows use {:?} again",
+                configuration().front_end
+            );
+        } else {
+            log::warn!(
+                "front_end is now overridden to {:?} for every window opened \
+                 from now on, until ToggleSoftwareRenderer is used again",
+                configuration().front_end
+            );
+        }
+
+        self.spawn_command(&SpawnCommand::default(), SpawnWhere::NewWindow);
+        Ok(())
+    }
+
+    fn show_debug_overlay(&mut self) {
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let gui_win = GuiWin::new(self);
+
+        let opengl_info = self.opengl_info.as_deref().unwrap_or("Unknown").to_string();
+        let connection_info = self.connection_name.clone();
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::show_debug_overlay(term, gui_win, opengl_info, connection_info)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_tab_navigator(&mut self) {
+        let mux = Mux::get();
+        let active_tab_idx = match mux.get_window(self.mux_window_id) {
+            Some(mux_window) => mux_window.get_active_idx(),
+            None => return,
+        };
+        let title = "Tab Navigator".to_string();
+        let args = LauncherActionArgs {
+            title: Some(title),
+            flags: LauncherFlags::TABS,
+            help_text: None,
+            fuzzy_help_text: None,
+            alphabet: None,
+        };
+        self.show_launcher_impl(args, active_tab_idx);
+    }
+
+    fn show_tab_gallery(&mut self) {
+        let mux = Mux::get();
+        let (tabs, active_idx) = match mux.get_window(self.mux_window_id) {
+            Some(mux_window) => (
+                mux_window
+                    .iter()
+                    .filter_map(|tab| {
+                        let pane = tab.get_active_pane()?;
+                        let dims = pane.get_dimensions();
+                        let (_, lines) = pane.get_lines(
+                            dims.physical_top
+                                ..dims.physical_top + dims.viewport_rows as StableRowIndex,
+                        );
+                        let mut title = tab.get_title();
+                        if title.is_empty() {
+                            title = pane.get_title();
+                        }
+                        Some(crate::overlay::tab_gallery::GalleryTab {
+                            title,
+                            cols: dims.cols,
+                            lines,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+                mux_window.get_active_idx(),
+            ),
+            None => return,
+        };
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+        let pane_id = match self.get_active_pane_or_overlay() {
+            Some(pane) => pane.pane_id(),
+            None => return,
+        };
+
+        let window = self.window.clone().unwrap();
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::tab_gallery::show_tab_gallery(term, tabs, active_idx, window, pane_id)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    /// Runs some quick self-diagnostics and shows the results
+    /// in an overlay, to help when troubleshooting
+    fn run_health_check(&mut self) {
+        use crate::overlay::health_check::HealthCheck;
+
+        let mut checks = vec![];
+
+        checks.push(match self.fonts.default_font() {
+            Ok(_) => HealthCheck {
+                name: "Fonts",
+                ok: true,
+                detail: format!(
+                    "default font loaded, cells are {}x{} pixels",
+                    self.render_metrics.cell_size.width, self.render_metrics.cell_size.height
+                ),
+            },
+            Err(err) => HealthCheck {
+                name: "Fonts",
+                ok: false,
+                detail: format!("failed to load the default font: {err:#}"),
+            },
+        });
+
+        checks.push(match &self.opengl_info {
+            Some(info) if self.render_state.is_some() => HealthCheck {
+                name: "GPU",
+                ok: true,
+                detail: info.clone(),
+            },
+            _ => HealthCheck {
+                name: "GPU",
+                ok: false,
+                detail: "no renderer has been initialized".to_string(),
+            },
+        });
+
+        let warnings = config::configuration_warnings_and_errors();
+        checks.push(match config::configuration_result() {
+            Ok(_) if warnings.is_empty() => HealthCheck {
+                name: "Config",
+                ok: true,
+                detail: "loaded without errors or warnings".to_string(),
+            },
+            Ok(_) => HealthCheck {
+                name: "Config",
+                ok: false,
+                detail: format!(
+                    "loaded with {} warning(s): {}",
+                    warnings.len(),
+                    warnings[0].lines().next().unwrap_or("")
+                ),
+            },
+            Err(err) => HealthCheck {
+                name: "Config",
+                ok: false,
+                detail: format!("using defaults: {err:#}"),
+            },
+        });
+
+        let mux = Mux::get();
+        for domain in mux.iter_domains() {
+            if !domain.spawnable() {
+                continue;
+            }
+            let attached = domain.state() == DomainState::Attached;
+            checks.push(HealthCheck {
+                name: "Mux",
+                ok: attached,
+                detail: format!(
+                    "domain {} is {}",
+                    domain.domain_name(),
+                    if attached { "attached" } else { "detached" }
+                ),
+            });
+        }
+
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            crate::overlay::health_check::show_health_check(term, checks)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_launcher(&mut self) {
+        let title = "Launcher".to_string();
+        let args = LauncherActionArgs {
+            title: Some(title),
+            flags: LauncherFlags::LAUNCH_MENU_ITEMS
+                | LauncherFlags::WORKSPACES
+                | LauncherFlags::DOMAINS
+                | LauncherFlags::KEY_ASSIGNMENTS
+                | LauncherFlags::COMMANDS,
+            help_text: None,
+            fuzzy_help_text: None,
+            alphabet: None,
+        };
+        self.show_launcher_impl(args, 0);
+    }
+
+    fn show_launcher_impl(&mut self, args: LauncherActionArgs, initial_choice_idx: usize) {
+        let mux_window_id = self.mux_window_id;
+        let window = self.window.as_ref().unwrap().clone();
+
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let pane = match self.get_active_pane_or_overlay() {
+            Some(pane) => pane,
+            None => return,
+        };
+
+        let domain_id_of_current_pane = tab
+            .get_active_pane()
+            .expect("tab has no panes!")
+            .domain_id();
+        let pane_id = pane.pane_id();
+        let tab_id = tab.tab_id();
+        let title = args.title.unwrap();
+        let flags = args.flags;
+        let help_text = args.help_text.unwrap_or(
+            "Select an item and press Enter=launch  \
+             Esc=cancel  /=filter"
+                .to_string(),
+        );
+        let fuzzy_help_text = args
+            .fuzzy_help_text
+            .unwrap_or("Fuzzy matching: ".to_string());
+
+        let config = &self.config;
+        let alphabet = args.alphabet.unwrap_or(config.launcher_alphabet.clone());
+
+        promise::spawn::spawn(async move {
+            let args = LauncherArgs::new(
+                &title,
+                flags,
+                mux_window_id,
+                pane_id,
+                domain_id_of_current_pane,
+                &help_text,
+                &fuzzy_help_text,
+                &alphabet,
+            )
+            .await;
+
+            let win = window.clone();
+            win.notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+                let mux = Mux::get();
+                if let Some(tab) = mux.get_tab(tab_id) {
+                    let window = window.clone();
+                    let (overlay, future) =
+                        start_overlay(term_window, &tab, move |_tab_id, term| {
+                            launcher(args, term, window, initial_choice_idx)
+                        });
+
+                    term_window.assign_overlay(tab_id, overlay);
+                    promise::spawn::spawn(future).detach();
+                }
+            })));
+        })
+        .detach();
+    }
+
+    /// Returns the Prompt semantic zones
+    fn get_semantic_prompt_zones(&mut self, pane: &Arc<dyn Pane>) -> &[StableRowIndex] {
+        let cache = self
+            .semantic_zones
+            .entry(pane.pane_id())
+            .or_insert_with(SemanticZoneCache::default);
+
+        let seqno = pane.get_current_seqno();
+        if cache.seqno != seqno {
+            let zones = pane.get_semantic_zones().unwrap_or_else(|_| vec![]);
+            let mut zones: Vec<StableRowIndex> = zones
+                .into_iter()
+                .filter_map(|zone| {
+                    if zone.semantic_type == wezterm_term::SemanticType::Prompt {
+                        Some(zone.start_y)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            // dedup to avoid issues where both left and right prompts are
+            // defined: we only care if there were 1+ prompts on a line,
+            // not about how many prompts are on a line.
+            // <https://github.com/wezterm/wezterm/issues/1121>
+            zones.dedup();
+            cache.zones = zones;
+            cache.seqno = seqno;
+        }
+        &cache.zones
+    }
+
+    fn scroll_to_prompt(&mut self, amount: isize, pane: &Arc<dyn Pane>) -> anyhow::Result<()> {
+        let dims = pane.get_dimensions();
+        let position = self
+            .get_viewport(pane.pane_id())
+            .unwrap_or(dims.physical_top);
+        let zone = {
+            let zones = self.get_semantic_prompt_zones(&pane);
+            let idx = match zones.binary_search(&position) {
+                Ok(idx) | Err(idx) => idx,
+            };
+            let idx = ((idx as isize) + amount).max(0) as usize;
+            zones.get(idx).cloned()
+        };
+        if let Some(zone) = zone {
+            self.set_viewport(pane.pane_id(), Some(zone), dims);
+        }
+
+        if let Some(win) = self.window.as_ref() {
+            win.invalidate();
+        }
+        Ok(())
+    }
+
+    /// The language of the selected code, going by the files that the
+    /// program in the pane was given, or else by the code itself
+    pub fn selection_language(&self, pane: &Arc<dyn Pane>) -> Option<highlight::Language> {
+        highlight::Language::from_pane(pane)
+            .or_else(|| highlight::Language::detect(&self.selection_text(pane)))
+    }
+
+    /// Whether mouse events are reported to the application in the
+    /// pane: it must have asked for them, and SetPaneMouseReporting
+    /// must not have disabled that
+    pub fn pane_reports_mouse(&self, pane: &Arc<dyn Pane>) -> bool {
+        pane.is_mouse_grabbed() && !self.pane_state(pane.pane_id()).mouse_reporting_disabled
+    }
+
+    pub fn is_pane_mouse_reporting_disabled(&self, pane_id: PaneId) -> bool {
+        self.pane_state(pane_id).mouse_reporting_disabled
+    }
+
+    /// The line that FlagCurrentLine applies to: the one under
+    /// the mouse, or else the one containing the cursor
+    pub fn flag_target_row(&self, pane: &Arc<dyn Pane>) -> StableRowIndex {
+        match self.pane_state(pane.pane_id()).mouse_terminal_coords {
+            Some((_, row)) => row,
+            None => pane.get_dimensions().physical_top + pane.get_cursor_position().y,
+        }
+    }
+
+    pub fn flagged_lines(&self, pane_id: PaneId) -> Vec<StableRowIndex> {
+        self.pane_state(pane_id).flagged_lines.clone()
+    }
+
+    fn toggle_line_flag(&mut self, pane: &Arc<dyn Pane>) {
+        let row = self.flag_target_row(pane);
+        let mut state = self.pane_state(pane.pane_id());
+        match state.flagged_lines.binary_search(&row) {
+            Ok(idx) => {
+                state.flagged_lines.remove(idx);
+            }
+            Err(idx) => state.flagged_lines.insert(idx, row),
+        }
+        drop(state);
+        if let Some(win) = self.window.as_ref() {
+            win.invalidate();
+        }
+    }
+
+    pub fn column_rulers(&self, pane_id: PaneId) -> Vec<usize> {
+        self.pane_state(pane_id).column_rulers.clone()
+    }
+
+    fn toggle_column_ruler(&mut self, pane_id: PaneId, column: usize) {
+        let mut state = self.pane_state(pane_id);
+        match state.column_rulers.binary_search(&column) {
+            Ok(idx) => {
+                state.column_rulers.remove(idx);
+            }
+            Err(idx) => state.column_rulers.insert(idx, column),
+        }
+        drop(state);
+        if let Some(win) = self.window.as_ref() {
+            win.invalidate();
+        }
+    }
+
+    /// Returns the column of the ruler drawn within a quarter of
+    /// a cell of a click at `x_pixel_offset` into `column`.
+    /// Rulers are drawn along the left edge of their column.
+    pub fn column_ruler_at(
+        &self,
+        pane_id: PaneId,
+        column: usize,
+        x_pixel_offset: isize,
+    ) -> Option<usize> {
+        let cell_width = self.render_metrics.cell_size.width;
+        let ruler = if x_pixel_offset * 4 < cell_width {
+            column
+        } else if x_pixel_offset * 4 >= cell_width * 3 {
+            column + 1
+        } else {
+            return None;
+        };
+        self.pane_state(pane_id)
+            .column_rulers
+            .contains(&ruler)
+            .then_some(ruler)
+    }
+
+    /// Scrolls so that the `amount`th flagged line above (if negative)
+    /// or below the top of the viewport is at the top of the viewport
+    fn scroll_to_flag(&mut self, amount: isize, pane: &Arc<dyn Pane>) {
+        let dims = pane.get_dimensions();
+        let position = self
+            .get_viewport(pane.pane_id())
+            .unwrap_or(dims.physical_top);
+        let flags = self.flagged_lines(pane.pane_id());
+        if let Some(row) = nth_row_from(&flags, position, amount) {
+            self.set_viewport(pane.pane_id(), Some(row), dims);
+            if let Some(win) = self.window.as_ref() {
+                win.invalidate();
+            }
+        }
+    }
+
+    fn scroll_to_semantic_zone(
+        &mut self,
+        zone_type: SemanticType,
+        amount: isize,
+        pane: &Arc<dyn Pane>,
+    ) -> anyhow::Result<()> {
+        let dims = pane.get_dimensions();
+        let position = self
+            .get_viewport(pane.pane_id())
+            .unwrap_or(dims.physical_top);
+        let mut starts: Vec<StableRowIndex> = pane
+            .get_semantic_zones()?
+            .iter()
+            .filter(|zone| zone.semantic_type == zone_type)
+            .map(|zone| zone.start_y)
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+        if let Some(row) = nth_row_from(&starts, position, amount) {
+            self.set_viewport(pane.pane_id(), Some(row), dims);
+            if let Some(win) = self.window.as_ref() {
+                win.invalidate();
+            }
+        }
+        Ok(())
+    }
+
+    fn scroll_by_page(&mut self, amount: f64, pane: &Arc<dyn Pane>) -> anyhow::Result<()> {
+        let dims = pane.get_dimensions();
+        let position = self
+            .get_viewport(pane.pane_id())
             .unwrap_or(dims.physical_top) as f64
             + (amount * dims.viewport_rows as f64);
         self.set_viewport(pane.pane_id(), Some(position as isize), dims);
@@ -2576,7 +3840,7 @@ impl TermWindow {
     ) -> anyhow::Result<PerformAssignmentResult> {
         use KeyAssignment::*;
 
-        if let Some(modal) = self.get_modal() {
+        for modal in self.get_modals().into_iter().rev() {
             if modal.perform_assignment(assignment, self) {
                 return Ok(PerformAssignmentResult::Handled);
             }
@@ -2632,6 +3896,7 @@ impl TermWindow {
             SpawnWindow => {
                 self.spawn_command(&SpawnCommand::default(), SpawnWhere::NewWindow);
             }
+            PromptSshHost => self.prompt_ssh_host(),
             SpawnCommandInNewTab(spawn) => {
                 self.spawn_command(spawn, SpawnWhere::NewTab);
             }
@@ -2699,6 +3964,21 @@ impl TermWindow {
                 let text = self.selection_text(pane);
                 self.copy_to_clipboard(*dest, text);
             }
+            CopyAsHtmlTo(dest) => {
+                let html = self.selection_html(pane);
+                let text = self.selection_text(pane);
+                self.copy_html_to_clipboard(*dest, html, text);
+            }
+            CopyAsHighlightedHtmlTo(dest) => {
+                // Without a recognizable language, fall back to
+                // the colors that are shown in the terminal
+                let html = match self.selection_language(pane) {
+                    Some(language) => self.selection_highlighted_html(pane, language),
+                    None => self.selection_html(pane),
+                };
+                let text = self.selection_text(pane);
+                self.copy_html_to_clipboard(*dest, html, text);
+            }
             CopyTextTo { text, destination } => {
                 self.copy_to_clipboard(*destination, text.clone());
             }
@@ -2815,7 +4095,9 @@ impl TermWindow {
                 self.clear_selection(pane);
             }
             StartWindowDrag => {
-                self.window_drag_position = self.current_mouse_event.clone();
+                if !self.window_position_locked {
+                    self.window_drag_position = self.current_mouse_event.clone();
+                }
             }
             OpenLinkAtMouseCursor => {
                 self.do_open_link_at_mouse_cursor(pane);
@@ -2975,6 +4257,35 @@ impl TermWindow {
                     tab.activate_pane_direction(*direction);
                 }
             }
+            MaximizePaneInDirection { direction } => {
+                let mux = Mux::get();
+                let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+                    Some(tab) => tab,
+                    None => return Ok(PerformAssignmentResult::Handled),
+                };
+
+                let tab_id = tab.tab_id();
+
+                if self.tab_state(tab_id).overlay.is_none() {
+                    tab.maximize_pane_in_direction(match direction {
+                        Axis::Horizontal => SplitDirection::Horizontal,
+                        Axis::Vertical => SplitDirection::Vertical,
+                    });
+                }
+            }
+            ApplyPaneLayout(layout) => {
+                let mux = Mux::get();
+                let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+                    Some(tab) => tab,
+                    None => return Ok(PerformAssignmentResult::Handled),
+                };
+
+                let tab_id = tab.tab_id();
+
+                if self.tab_state(tab_id).overlay.is_none() {
+                    tab.apply_pane_layout(*layout);
+                }
+            }
             TogglePaneZoomState => {
                 let mux = Mux::get();
                 let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
@@ -3130,11 +4441,11 @@ impl TermWindow {
             }
             PaneSelect(args) => {
                 let modal = crate::termwindow::paneselect::PaneSelector::new(self, args);
-                self.set_modal(Rc::new(modal));
+                self.push_modal(Rc::new(modal));
             }
             CharSelect(args) => {
                 let modal = crate::termwindow::charselect::CharSelector::new(self, args);
-                self.set_modal(Rc::new(modal));
+                self.push_modal(Rc::new(modal));
             }
             ResetTerminal => {
                 pane.perform_actions(vec![termwiz::escape::Action::Esc(
@@ -3146,18 +4457,128 @@ impl TermWindow {
             }
             ActivateCommandPalette => {
                 let modal = crate::termwindow::palette::CommandPalette::new(self);
-                self.set_modal(Rc::new(modal));
+                self.push_modal(Rc::new(modal));
             }
             ShowContextMenu => {
-                // Get the mouse position from the current event, or use a default position
-                let (mouse_x, mouse_y) = self
-                    .current_mouse_event
-                    .as_ref()
-                    .map(|e| (e.coords.x, e.coords.y))
-                    .unwrap_or((100, 100));
-                let modal =
-                    crate::termwindow::contextmenu::ContextMenu::new(self, mouse_x, mouse_y);
-                self.set_modal(Rc::new(modal));
+                let (mouse_x, mouse_y) = self.context_menu_position();
+                let modal = crate::termwindow::contextmenu::ContextMenu::new(
+                    self,
+                    mouse_x,
+                    mouse_y,
+                    Some(pane.pane_id()),
+                );
+                self.push_modal(Rc::new(modal));
+            }
+            ShowNamedContextMenu(name) => {
+                let (mouse_x, mouse_y) = self.context_menu_position();
+                match crate::termwindow::contextmenu::ContextMenu::from_config(
+                    self, mouse_x, mouse_y, name,
+                ) {
+                    Some(modal) => self.push_modal(Rc::new(modal)),
+                    None => log::error!("context_menus has no menu named {name}"),
+                }
+            }
+            PromptRenameTab => self.show_rename_tab_prompt(),
+            ToggleAutoNameTab => {
+                let mux = Mux::get();
+                if let Some(tab) = mux.get_active_tab_for_window(self.mux_window_id) {
+                    tab.set_auto_name(!tab.get_auto_name());
+                    self.update_title();
+                }
+            }
+            PromptRenameWorkspace => self.show_rename_workspace_prompt(),
+            PasteToAllPanes => self.paste_to_all_panes(),
+            SendInputToPane { pane_id } => self.show_send_input_prompt(*pane_id),
+            StartReadOnlyShare => self.start_read_only_share(),
+            StopReadOnlyShare => self.stop_read_only_share(),
+            ToggleSecureInput => self.toggle_secure_input(),
+            ShowScrollbackFilter => self.show_scrollback_filter(pane),
+            FocusPreviousPaneInHistory => {
+                let active = self.get_active_pane_no_overlay().map(|pane| pane.pane_id());
+                if let Some(pane_id) = self
+                    .recently_focused_panes()
+                    .into_iter()
+                    .find(|&pane_id| Some(pane_id) != active)
+                {
+                    self.activate_pane_by_id(pane_id);
+                }
+            }
+            ActivatePaneById { pane_id } => self.activate_pane_by_id(*pane_id),
+            SwapActivePaneWithIndex(index) => {
+                let mux = Mux::get();
+                if let Some(tab) = mux.get_active_tab_for_window(self.mux_window_id) {
+                    tab.swap_active_with_index(*index, false);
+                }
+            }
+            StartSplitDrag(index) => self.start_split_drag(*index),
+            ExportEffectiveConfig => self.show_export_config_prompt(),
+            PipeSelectionToCommand => self.show_pipe_selection_prompt(pane),
+            ToggleDimOnUnfocus => {
+                self.dim_on_unfocus = !self.dim_on_unfocus;
+                // Only has a visible effect if we are unfocused
+                self.quad_generation += 1;
+                if let Some(window) = self.window.as_ref() {
+                    window.invalidate();
+                }
+            }
+            FlagCurrentLine => self.toggle_line_flag(pane),
+            ScrollToFlag(n) => self.scroll_to_flag(*n, pane),
+            ToggleColumnRuler => {
+                let column = pane.get_cursor_position().x;
+                self.toggle_column_ruler(pane.pane_id(), column);
+            }
+            ShowFontMetricsOverlay => {
+                self.show_font_metrics = !self.show_font_metrics;
+                self.quad_generation += 1;
+                if let Some(window) = self.window.as_ref() {
+                    window.invalidate();
+                }
+            }
+            SetScrollbackLines { lines } => {
+                let lines = (*lines).min(config::MAX_SCROLLBACK_LINES);
+                self.pane_state(pane.pane_id())
+                    .scrollback_lines
+                    .replace(lines);
+                let term_config = TermConfig::with_config(self.config.clone());
+                term_config.set_scrollback_lines(lines);
+                pane.set_config(Arc::new(term_config));
+            }
+            SetCursorBlinkRate { rate_ms } => self.set_cursor_blink_rate(*rate_ms),
+            SetWindowOpacity(opacity) => self.set_window_opacity(**opacity as f32),
+            SnapshotPane => self.snapshot_pane(pane),
+            RestorePaneSnapshot(idx) => self.restore_pane_snapshot(*idx),
+            ToggleSoftwareRenderer => self.toggle_software_renderer()?,
+            TogglePerformanceMode => self.toggle_performance_mode(),
+            ToggleWindowPositionLock => self.toggle_window_position_lock(),
+            ScrollToSemanticZone { zone_type, amount } => {
+                self.scroll_to_semantic_zone(*zone_type, *amount, pane)?
+            }
+            ShowGlyphAtlas => self.show_glyph_atlas(pane),
+            CloseAllOtherPanes => self.close_all_other_panes(),
+            ShowTabGallery => self.show_tab_gallery(),
+            CopyVisibleText => self.copy_visible_text(pane),
+            PasteWithPrefix => self.show_paste_with_prefix_prompt(pane),
+            CompleteCommand => self.complete_command(pane),
+            RunHealthCheck => self.run_health_check(),
+            SetPaneMouseReporting { enabled } => {
+                self.pane_state(pane.pane_id()).mouse_reporting_disabled = !enabled;
+            }
+            SetContextMenuTheme(name) => {
+                if let Err(err) = crate::menutheme::set_theme(name) {
+                    log::error!("Failed to set the context menu theme: {err:#}");
+                }
+            }
+            SpawnTabFromTemplate { name } => {
+                let build = self
+                    .config
+                    .tab_templates
+                    .iter()
+                    .find(|template| template.name == *name)
+                    .map(|template| template.build.clone());
+                match build {
+                    Some(build) => self.emit_window_event(&build, Some(pane.pane_id())),
+                    None => log::error!("tab_templates has no template named {name}"),
+                }
             }
             PromptInputLine(args) => self.show_prompt_input_line(args),
             InputSelector(args) => self.show_input_selector(args),
@@ -3234,6 +4655,37 @@ impl TermWindow {
         }
     }
 
+    fn close_all_other_panes(&mut self) {
+        let mux_window_id = self.mux_window_id;
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+        let pane = match tab.get_active_pane() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let pane_id = pane.pane_id();
+        let others: Vec<PaneId> = tab
+            .iter_panes_ignoring_zoom()
+            .into_iter()
+            .map(|pos| pos.pane.pane_id())
+            .filter(|&id| id != pane_id)
+            .collect();
+        if others.is_empty() {
+            return;
+        }
+
+        let window = self.window.clone().unwrap();
+        let (overlay, future) = start_overlay_pane(self, &pane, move |pane_id, term| {
+            confirm_close_other_panes(pane_id, others, term, mux_window_id, window)
+        });
+        self.assign_overlay_for_pane(pane_id, overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
     fn close_specific_tab(&mut self, tab_idx: usize, confirm: bool) {
         let mux = Mux::get();
         let mux_window_id = self.mux_window_id;
@@ -3617,10 +5069,160 @@ impl TermWindow {
     }
 }
 
+/// Returns the text of the Input zone from its start up to the
+/// cursor, if the cursor is in one and it holds more than whitespace.
+/// Input zones are only present when shell integration is set up.
+/// Returns the word at the end of `text`, which is empty if `text`
+/// ends with whitespace
+fn last_word(text: &str) -> Option<&str> {
+    text.rsplit(char::is_whitespace).next()
+}
+
+fn partial_command_at_cursor(pane: &Arc<dyn Pane>) -> Option<String> {
+    let cursor = pane.get_cursor_position();
+    let zone = pane.get_semantic_zones().ok()?.into_iter().find(|zone| {
+        zone.semantic_type == SemanticType::Input
+            && (zone.start_y, zone.start_x) <= (cursor.y, cursor.x)
+            && (cursor.y, cursor.x) <= (zone.end_y, zone.end_x + 1)
+    })?;
+
+    let (first_row, lines) = pane.get_lines(zone.start_y..cursor.y + 1);
+    let text = lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let row = first_row + idx as StableRowIndex;
+            let start = if row == zone.start_y { zone.start_x } else { 0 };
+            let end = if row == cursor.y {
+                cursor.x
+            } else {
+                line.len()
+            };
+            line.columns_as_str(start..end.max(start))
+        })
+        .collect::<String>();
+
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Picks the `amount`th of the sorted `rows` after `position`, or
+/// before it when `amount` is negative
+fn nth_row_from(
+    rows: &[StableRowIndex],
+    position: StableRowIndex,
+    amount: isize,
+) -> Option<StableRowIndex> {
+    if amount > 0 {
+        rows.iter()
+            .filter(|&&row| row > position)
+            .nth(amount as usize - 1)
+            .copied()
+    } else if amount < 0 {
+        rows.iter()
+            .rev()
+            .filter(|&&row| row < position)
+            .nth(amount.unsigned_abs() - 1)
+            .copied()
+    } else {
+        None
+    }
+}
+
+/// Runs `command` via the shell, with `input` as its stdin,
+/// and returns its stdout. When `timeout` passes before the output
+/// is complete, the command is killed and an error is returned.
+fn pipe_through_command(
+    command: &str,
+    input: &str,
+    timeout: Option<Duration>,
+) -> anyhow::Result<String> {
+    use std::io::{Read, Write};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::{channel, RecvTimeoutError};
+
+    #[cfg(unix)]
+    let mut cmd = {
+        let mut cmd = Command::new("/bin/sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd.exe");
+        cmd.arg("/C").arg(command);
+        cmd
+    };
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("spawning {command}"))?;
+
+    // Write the input from another thread so that a command that
+    // produces a lot of output before reading all of its input
+    // cannot deadlock with us
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    // Likewise read the output from another thread, so that
+    // we can stop waiting for it
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let (tx, rx) = channel();
+    std::thread::spawn(move || {
+        let mut output = vec![];
+        tx.send(stdout.read_to_end(&mut output).map(|_| output))
+            .ok();
+    });
+
+    let output = match timeout {
+        Some(timeout) => match rx.recv_timeout(timeout) {
+            Ok(output) => output,
+            Err(RecvTimeoutError::Timeout) => {
+                child.kill().ok();
+                child.wait().ok();
+                anyhow::bail!("{command} did not finish within {timeout:?}");
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                anyhow::bail!("reading from {command} panicked")
+            }
+        },
+        None => rx
+            .recv()
+            .map_err(|_| anyhow!("reading from {command} panicked"))?,
+    }?;
+    let status = child.wait()?;
+    writer
+        .join()
+        .map_err(|_| anyhow!("writing to {command} panicked"))?
+        .ok();
+    if !status.success() {
+        anyhow::bail!("{command} exited with {status}");
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
 impl Drop for TermWindow {
     fn drop(&mut self) {
         self.clear_all_overlays();
+        self.stop_read_only_share();
+        if let Some(mux) = Mux::try_get() {
+            for pane_id in self.secure_input_panes.drain(..) {
+                if let Some(pane) = mux.get_pane(pane_id) {
+                    pane.set_retain_scrollback(true);
+                }
+            }
+        }
         if let Some(window) = self.window.take() {
+            if self.secure_input_held {
+                window.set_secure_input(false);
+            }
             if let Some(fe) = try_front_end() {
                 fe.forget_known_window(&window);
             }
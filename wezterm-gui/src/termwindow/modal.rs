@@ -5,6 +5,25 @@ use downcast_rs::{impl_downcast, Downcast};
 use std::cell::Ref;
 use wezterm_term::{KeyCode, KeyModifiers, MouseEvent};
 
+/// Whether a mouse event offered to a modal should also be
+/// passed on to whatever is underneath it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPropagation {
+    Consumed,
+    Propagate,
+}
+
+/// A modal UI element, such as the command palette or a context menu,
+/// that takes over input for the window while it is active.
+///
+/// `TermWindow` holds a stack of `Rc<dyn Modal>`, with input offered
+/// to the topmost first, so this trait must remain object safe: methods
+/// take `&self` and have no generic parameters. Implementations use
+/// interior mutability for their state. Sharing rather than boxing lets
+/// `TermWindow::get_modal` hand out a clone that can be called with
+/// `&mut TermWindow` without holding a borrow of the stack, which is
+/// what allows a modal to push another or cancel itself from inside
+/// its own handlers.
 pub trait Modal: Downcast {
     fn perform_assignment(
         &self,
@@ -13,7 +32,11 @@ pub trait Modal: Downcast {
     ) -> bool {
         false
     }
-    fn mouse_event(&self, event: MouseEvent, term_window: &mut TermWindow) -> anyhow::Result<()>;
+    fn mouse_event(
+        &self,
+        event: MouseEvent,
+        term_window: &mut TermWindow,
+    ) -> anyhow::Result<EventPropagation>;
     fn key_down(
         &self,
         key: KeyCode,
@@ -25,5 +48,10 @@ pub trait Modal: Downcast {
         term_window: &mut TermWindow,
     ) -> anyhow::Result<Ref<'_, [ComputedElement]>>;
     fn reconfigure(&self, term_window: &mut TermWindow);
+    /// Serializes the state of the modal, for `wezterm cli dump-modal`.
+    /// Modals that don't implement this are dumped as `null`.
+    fn dump(&self) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::Value::Null)
+    }
 }
 impl_downcast!(Modal);
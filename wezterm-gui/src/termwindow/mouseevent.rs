@@ -1,14 +1,15 @@
 use crate::tabbar::TabBarItem;
+use crate::termwindow::modal::EventPropagation;
 use crate::termwindow::{
     GuiWin, MouseCapture, PositionedSplit, ScrollHit, TermWindowNotif, UIItem, UIItemType, TMB,
 };
 use ::window::{
-    MouseButtons as WMB, MouseCursor, MouseEvent, MouseEventKind as WMEK, MousePress,
+    MouseButtons as WMB, MouseCursor, MouseEvent, MouseEventKind as WMEK, MousePress, Point,
     WindowDecorations, WindowOps, WindowState,
 };
 use config::keyassignment::{KeyAssignment, MouseEventTrigger, SpawnTabDomain};
 use config::MouseEventAltScreen;
-use mux::pane::{Pane, WithPaneLines};
+use mux::pane::{Pane, PaneId, WithPaneLines};
 use mux::tab::SplitDirection;
 use mux::Mux;
 use mux_lua::MuxPane;
@@ -16,7 +17,7 @@ use std::convert::TryInto;
 use std::ops::Sub;
 use std::rc::Rc;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use termwiz::hyperlink::Hyperlink;
 use termwiz::surface::Line;
 use wezterm_dynamic::ToDynamic;
@@ -34,6 +35,57 @@ impl super::TermWindow {
             .cloned()
     }
 
+    /// Arrange to show the context menu for `pane_id` at `coords` if
+    /// the left button is held there for context_menu_long_press_ms
+    fn start_long_press(&mut self, coords: Point, pane_id: PaneId) {
+        let delay = self.config.context_menu_long_press_ms;
+        let window = match self.window.clone() {
+            Some(window) if delay > 0 => window,
+            _ => {
+                self.long_press = None;
+                return;
+            }
+        };
+
+        let started = Instant::now();
+        self.long_press = Some((started, coords, pane_id));
+
+        promise::spawn::spawn(async move {
+            smol::Timer::after(Duration::from_millis(delay)).await;
+            window.notify(TermWindowNotif::Apply(Box::new(move |myself| {
+                myself.complete_long_press(started);
+            })));
+        })
+        .detach();
+    }
+
+    fn complete_long_press(&mut self, started: Instant) {
+        let (coords, pane_id) = match self.long_press {
+            Some((when, coords, pane_id)) if when == started => (coords, pane_id),
+            // Released, moved or superseded by a later press
+            _ => return,
+        };
+        self.long_press = None;
+
+        if self.get_modal().is_some() || !self.current_mouse_buttons.contains(&MousePress::Left) {
+            return;
+        }
+
+        // The release of this press will be routed to the menu, so
+        // stop tracking the button here
+        self.current_mouse_buttons
+            .retain(|p| *p != MousePress::Left);
+        self.current_mouse_capture = None;
+
+        let modal = crate::termwindow::contextmenu::ContextMenu::new(
+            self,
+            coords.x,
+            coords.y,
+            Some(pane_id),
+        );
+        self.push_modal(Rc::new(modal));
+    }
+
     fn leave_ui_item(&mut self, item: &UIItem) {
         match item.item_type {
             UIItemType::TabBar(_) => {
@@ -62,7 +114,7 @@ impl super::TermWindow {
         log::trace!("{:?}", event);
 
         // Route mouse events to modal if one is active
-        if let Some(modal) = self.get_modal() {
+        if self.get_modal().is_some() {
             self.current_mouse_event.replace(event.clone());
             let term_event = wezterm_term::MouseEvent {
                 kind: match event.kind {
@@ -75,7 +127,21 @@ impl super::TermWindow {
                 },
                 button: match event.kind {
                     WMEK::Press(ref press) | WMEK::Release(ref press) => mouse_press_to_tmb(press),
-                    _ => TMB::None,
+                    WMEK::VertWheel(amount) => {
+                        if amount > 0 {
+                            TMB::WheelUp(amount as usize)
+                        } else {
+                            TMB::WheelDown((-amount) as usize)
+                        }
+                    }
+                    WMEK::HorzWheel(amount) => {
+                        if amount > 0 {
+                            TMB::WheelLeft(amount as usize)
+                        } else {
+                            TMB::WheelRight((-amount) as usize)
+                        }
+                    }
+                    WMEK::Move => TMB::None,
                 },
                 x: event.coords.x as usize,
                 y: event.coords.y as i64,
@@ -83,11 +149,28 @@ impl super::TermWindow {
                 y_pixel_offset: 0,
                 modifiers: event.modifiers,
             };
-            if let Err(err) = modal.mouse_event(term_event, self) {
-                log::error!("Error dispatching mouse to modal: {err:#}");
-            }
             context.invalidate();
-            return;
+            // Offer the event to the topmost modal.  If it propagates the
+            // event after closing itself, offer it to the one that is now
+            // on top, otherwise pass it on to the rest of the window.
+            while let Some(modal) = self.get_modal() {
+                match modal.mouse_event(term_event, self) {
+                    Ok(EventPropagation::Consumed) => return,
+                    Ok(EventPropagation::Propagate) => {
+                        let still_on_top = self
+                            .get_modal()
+                            .map(|top| Rc::ptr_eq(&top, &modal))
+                            .unwrap_or(false);
+                        if still_on_top {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        log::error!("Error dispatching mouse to modal: {err:#}");
+                        return;
+                    }
+                }
+            }
         }
 
         let pane = match self.get_active_pane_or_overlay() {
@@ -121,7 +204,7 @@ impl super::TermWindow {
             .sub((padding_left + border.left.get() as f32) as isize)
             .max(0) as f32)
             / self.render_metrics.cell_size.width as f32;
-        let x = if !pane.is_mouse_grabbed() {
+        let x = if !self.pane_reports_mouse(&pane) {
             // Round the x coordinate so that we're a bit more forgiving of
             // the horizontal position when selecting cells
             x.round()
@@ -155,6 +238,9 @@ impl super::TermWindow {
             WMEK::Release(ref press) => {
                 self.current_mouse_capture = None;
                 self.current_mouse_buttons.retain(|p| p != press);
+                if press == &MousePress::Left {
+                    self.long_press = None;
+                }
                 if press == &MousePress::Left && self.window_drag_position.take().is_some() {
                     // Completed a window drag
                     return;
@@ -185,9 +271,44 @@ impl super::TermWindow {
                 self.last_mouse_click = Some(click);
                 self.current_mouse_buttons.retain(|p| p != press);
                 self.current_mouse_buttons.push(*press);
+
+                if press == &MousePress::Left && self.dragging.is_some() {
+                    // A split is being moved after StartSplitDrag; this
+                    // press only serves to end that when it is released
+                    return;
+                }
+
+                if press == &MousePress::Left && self.resolve_ui_item(&event).is_none() {
+                    // Like a right-click, the menu belongs to the pane
+                    // under the press rather than the active pane
+                    let pane_id = self
+                        .get_panes_to_render()
+                        .into_iter()
+                        .find(|pos| {
+                            y >= pos.top as i64
+                                && y <= (pos.top + pos.height) as i64
+                                && x >= pos.left
+                                && x <= pos.left + pos.width
+                        })
+                        .map(|pos| pos.pane.pane_id())
+                        .unwrap_or_else(|| pane.pane_id());
+                    self.start_long_press(event.coords, pane_id);
+                } else {
+                    self.long_press = None;
+                }
             }
 
             WMEK::Move => {
+                if let Some((_, start, _)) = self.long_press {
+                    // Moving by more than a cell means this is a drag
+                    // rather than a long-press
+                    if (event.coords.x - start.x).abs() > self.render_metrics.cell_size.width
+                        || (event.coords.y - start.y).abs() > self.render_metrics.cell_size.height
+                    {
+                        self.long_press = None;
+                    }
+                }
+
                 if let Some(start) = self.window_drag_position.as_ref() {
                     // Dragging the window
                     // Compute the distance since the initial event
@@ -507,9 +628,19 @@ impl super::TermWindow {
     pub fn mouse_event_tab_bar(
         &mut self,
         item: TabBarItem,
-        event: MouseEvent,
+        mut event: MouseEvent,
         context: &dyn WindowOps,
     ) {
+        // A control-click is the usual secondary click on macOS; treat
+        // it as a right click so that it opens our context menu for the
+        // tab bar rather than activating the tab
+        if cfg!(target_os = "macos")
+            && matches!(event.kind, WMEK::Press(MousePress::Left))
+            && event.modifiers == ::window::Modifiers::CTRL
+        {
+            event.kind = WMEK::Press(MousePress::Right);
+        }
+
         match event.kind {
             WMEK::Press(MousePress::Left) => match item {
                 TabBarItem::Tab { tab_idx, .. } => {
@@ -536,10 +667,12 @@ impl super::TermWindow {
                         }
                     }
                     // Potentially starting a drag by the tab bar
-                    if !maximized {
-                        self.window_drag_position.replace(event.clone());
+                    if !self.window_position_locked {
+                        if !maximized {
+                            self.window_drag_position.replace(event.clone());
+                        }
+                        context.request_drag_move();
                     }
-                    context.request_drag_move();
                 }
                 TabBarItem::WindowButton(button) => {
                     use window::IntegratedTitleButton as Button;
@@ -574,20 +707,38 @@ impl super::TermWindow {
                 | TabBarItem::WindowButton(_) => {}
             },
             WMEK::Press(MousePress::Right) => match item {
-                TabBarItem::Tab { .. } => {
-                    self.show_tab_navigator();
+                TabBarItem::Tab { tab_idx, .. } => {
+                    let tab_id = Mux::get()
+                        .get_window(self.mux_window_id)
+                        .and_then(|window| window.get_by_idx(tab_idx).map(|tab| tab.tab_id()));
+                    if let Some(tab_id) = tab_id {
+                        let modal = crate::termwindow::contextmenu::ContextMenu::for_tab(
+                            self,
+                            tab_id,
+                            event.coords.x,
+                            event.coords.y,
+                        );
+                        self.push_modal(Rc::new(modal));
+                    }
                 }
                 TabBarItem::NewTabButton { .. } => {
                     self.do_new_tab_button_click(MousePress::Right);
                 }
-                TabBarItem::None
-                | TabBarItem::LeftStatus
-                | TabBarItem::RightStatus
-                | TabBarItem::WindowButton(_) => {}
+                TabBarItem::None => {
+                    let modal = crate::termwindow::contextmenu::ContextMenu::for_tab_list(
+                        self,
+                        event.coords.x,
+                        event.coords.y,
+                    );
+                    self.push_modal(Rc::new(modal));
+                }
+                TabBarItem::LeftStatus | TabBarItem::RightStatus | TabBarItem::WindowButton(_) => {}
             },
             WMEK::Move => match item {
                 TabBarItem::None | TabBarItem::LeftStatus | TabBarItem::RightStatus => {
-                    context.set_window_drag_position(event.screen_coords);
+                    if !self.window_position_locked {
+                        context.set_window_drag_position(event.screen_coords);
+                    }
                 }
                 TabBarItem::WindowButton(window::IntegratedTitleButton::Maximize) => {
                     let item = self.last_ui_item.clone().unwrap();
@@ -693,7 +844,35 @@ impl super::TermWindow {
             SplitDirection::Vertical => MouseCursor::SizeUpDown,
         }));
 
-        if event.kind == WMEK::Press(MousePress::Left) {
+        match event.kind {
+            WMEK::Press(MousePress::Left) => {
+                self.dragging.replace((item, event));
+            }
+            WMEK::Press(MousePress::Right) => {
+                let modal = crate::termwindow::contextmenu::ContextMenu::for_split(
+                    self,
+                    &split,
+                    event.coords.x,
+                    event.coords.y,
+                );
+                self.push_modal(Rc::new(modal));
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the split with the topological `index` along with the
+    /// mouse, as if its divider had been grabbed, until the left
+    /// button is next released
+    pub fn start_split_drag(&mut self, index: usize) {
+        let item = self
+            .ui_items
+            .iter()
+            .find(
+                |item| matches!(&item.item_type, UIItemType::Split(split) if split.index == index),
+            )
+            .cloned();
+        if let (Some(item), Some(event)) = (item, self.current_mouse_event.clone()) {
             self.dragging.replace((item, event));
         }
     }
@@ -890,12 +1069,20 @@ impl super::TermWindow {
             // When hovering over a hyperlink, show an appropriate
             // mouse cursor to give the cue that it is clickable
             MouseCursor::Hand
-        } else if pane.is_mouse_grabbed() || outside_window {
+        } else if self.pane_reports_mouse(&pane) || outside_window {
             MouseCursor::Arrow
         } else {
             MouseCursor::Text
         }));
 
+        // Clicking on a column ruler removes it
+        if allow_action && matches!(&event.kind, WMEK::Press(MousePress::Left)) {
+            if let Some(ruler) = self.column_ruler_at(pane.pane_id(), column, x_pixel_offset) {
+                self.toggle_column_ruler(pane.pane_id(), ruler);
+                return;
+            }
+        }
+
         let event_trigger_type = match &event.kind {
             WMEK::Press(press) => {
                 let press = mouse_press_to_tmb(press);
@@ -974,7 +1161,7 @@ impl super::TermWindow {
 
                 // Since we use shift to force assessing the mouse bindings, pretend
                 // that shift is not one of the mods when the mouse is grabbed.
-                let mut mouse_reporting = pane.is_mouse_grabbed();
+                let mut mouse_reporting = self.pane_reports_mouse(&pane);
                 if mouse_reporting {
                     if modifiers.contains(self.config.bypass_mouse_reporting_modifiers) {
                         modifiers.remove(self.config.bypass_mouse_reporting_modifiers);
@@ -1082,7 +1269,12 @@ impl super::TermWindow {
             modifiers: event.modifiers,
         };
 
+        // The application asked for mouse events, but they were
+        // disabled for this pane by SetPaneMouseReporting
+        let reporting_disabled = pane.is_mouse_grabbed() && !self.pane_reports_mouse(&pane);
+
         if allow_action
+            && !reporting_disabled
             && !(self.config.swallow_mouse_click_on_pane_focus && is_click_to_focus_pane)
         {
             pane.mouse_event(mouse_event).ok();
@@ -1,5 +1,5 @@
 use crate::termwindow::box_model::*;
-use crate::termwindow::modal::Modal;
+use crate::termwindow::modal::{EventPropagation, Modal};
 use crate::termwindow::render::corners::{
     BOTTOM_LEFT_ROUNDED_CORNER, BOTTOM_RIGHT_ROUNDED_CORNER, TOP_LEFT_ROUNDED_CORNER,
     TOP_RIGHT_ROUNDED_CORNER,
@@ -234,8 +234,12 @@ impl Modal for PaneSelector {
         false
     }
 
-    fn mouse_event(&self, _event: MouseEvent, _term_window: &mut TermWindow) -> anyhow::Result<()> {
-        Ok(())
+    fn mouse_event(
+        &self,
+        _event: MouseEvent,
+        _term_window: &mut TermWindow,
+    ) -> anyhow::Result<EventPropagation> {
+        Ok(EventPropagation::Consumed)
     }
 
     fn key_down(
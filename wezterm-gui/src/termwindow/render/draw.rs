@@ -18,6 +18,7 @@ impl crate::TermWindow {
     }
 
     fn call_draw_webgpu(&mut self) -> anyhow::Result<()> {
+        use crate::termwindow::blur::BlurKind;
         use crate::termwindow::webgpu::WebGpuTexture;
 
         let webgpu = self.webgpu.as_mut().unwrap();
@@ -88,6 +89,29 @@ impl crate::TermWindow {
         .to_arrays_transposed();
 
         for layer in render_state.layers.borrow().iter() {
+            if let Some(blur_pass) = &webgpu.blur_pass {
+                for region in render_state
+                    .blur_regions
+                    .borrow()
+                    .iter()
+                    .filter(|region| region.zindex == layer.zindex())
+                {
+                    if !cleared && matches!(region.kind, BlurKind::Backdrop) {
+                        // Nothing has been drawn for it to blur yet
+                        continue;
+                    }
+                    blur_pass.draw(
+                        &webgpu.device,
+                        &mut encoder,
+                        &output.texture,
+                        &view,
+                        region,
+                        !cleared,
+                    );
+                    cleared = true;
+                }
+            }
+
             for idx in 0..3 {
                 let vb = &layer.vb.borrow()[idx];
                 let (vertex_count, index_count) = vb.vertex_index_count();
@@ -0,0 +1,92 @@
+use crate::termwindow::render::TripleLayerQuadAllocator;
+use anyhow::Context;
+use window::color::LinearRgba;
+
+impl crate::TermWindow {
+    /// Draws guide lines over each pane to show how the default font
+    /// is laid out in the cell grid: the cell boundaries, which are
+    /// spaced by the advance width and line height, together with the
+    /// baseline, cap height and underline position within each row.
+    /// The cell top and bottom are the ascent and descent lines.
+    pub fn paint_font_metrics(
+        &mut self,
+        layers: &mut TripleLayerQuadAllocator,
+    ) -> anyhow::Result<()> {
+        let metrics = self
+            .fonts
+            .default_font_metrics()
+            .context("default_font_metrics")?;
+
+        let grid_color = LinearRgba::with_components(0.5, 0.5, 0.5, 0.35);
+        let baseline_color = LinearRgba::with_components(1.0, 0.2, 0.2, 0.8);
+        let cap_height_color = LinearRgba::with_components(0.2, 1.0, 0.2, 0.8);
+        let underline_color = LinearRgba::with_components(0.3, 0.5, 1.0, 0.8);
+
+        let cell_width = self.render_metrics.cell_size.width as f32;
+        let cell_height = self.render_metrics.cell_size.height as f32;
+        let (padding_left, padding_top) = self.padding_left_top();
+        let tab_bar_height = if self.show_tab_bar && !self.config.tab_bar_at_bottom {
+            self.tab_bar_pixel_height()?
+        } else {
+            0.
+        };
+        let border = self.get_os_border();
+        let left_pixel_x = padding_left + border.left.get() as f32;
+        let top_pixel_y = tab_bar_height + padding_top + border.top.get() as f32;
+
+        // Offsets from the top of a row; descender is negative
+        // and measured up from the bottom of the cell
+        let baseline = cell_height + metrics.descender.get() as f32;
+        let cap_height = metrics
+            .cap_height
+            .map(|cap_height| baseline - cap_height.get() as f32);
+        let underline = self.render_metrics.descender_row as f32;
+
+        for pos in self.get_panes_to_render() {
+            let x = left_pixel_x + pos.left as f32 * cell_width;
+            let y = top_pixel_y + pos.top as f32 * cell_height;
+            let width = pos.width as f32 * cell_width;
+            let height = pos.height as f32 * cell_height;
+
+            for col in 0..=pos.width {
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(x + col as f32 * cell_width, y, 1., height),
+                    grid_color,
+                )?;
+            }
+
+            for row in 0..=pos.height {
+                let row_y = y + row as f32 * cell_height;
+                self.filled_rectangle(layers, 2, euclid::rect(x, row_y, width, 1.), grid_color)?;
+                if row == pos.height {
+                    break;
+                }
+
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(x, row_y + baseline, width, 1.),
+                    baseline_color,
+                )?;
+                if let Some(cap_height) = cap_height {
+                    self.filled_rectangle(
+                        layers,
+                        2,
+                        euclid::rect(x, row_y + cap_height, width, 1.),
+                        cap_height_color,
+                    )?;
+                }
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(x, row_y + underline, width, 1.),
+                    underline_color,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
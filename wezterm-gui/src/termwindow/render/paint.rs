@@ -144,7 +144,7 @@ impl crate::TermWindow {
     }
 
     pub fn paint_modal(&mut self) -> anyhow::Result<()> {
-        if let Some(modal) = self.get_modal() {
+        for modal in self.get_modals() {
             for computed in modal.computed_element(self)?.iter() {
                 let mut ui_items = computed.ui_items();
 
@@ -164,6 +164,7 @@ impl crate::TermWindow {
             for layer in gl_state.layers.borrow().iter() {
                 layer.clear_quad_allocation();
             }
+            gl_state.blur_regions.borrow_mut().clear();
         }
 
         // Clear out UI item positions; we'll rebuild these as we render
@@ -172,7 +173,7 @@ impl crate::TermWindow {
         let panes = self.get_panes_to_render();
         let focused = self.focused.is_some();
         let window_is_transparent =
-            !self.window_background.is_empty() || self.config.window_background_opacity != 1.0;
+            !self.window_background.is_empty() || self.window_background_opacity() != 1.0;
 
         let start = Instant::now();
         let gl_state = self.render_state.as_ref().unwrap();
@@ -230,7 +231,7 @@ impl crate::TermWindow {
                 self.palette().background
             }
             .to_linear()
-            .mul_alpha(self.config.window_background_opacity);
+            .mul_alpha(self.window_background_opacity());
 
             self.filled_rectangle(
                 &mut layers,
@@ -269,6 +270,11 @@ impl crate::TermWindow {
             self.paint_tab_bar(&mut layers).context("paint_tab_bar")?;
         }
 
+        if self.show_font_metrics {
+            self.paint_font_metrics(&mut layers)
+                .context("paint_font_metrics")?;
+        }
+
         self.paint_window_borders(&mut layers)
             .context("paint_window_borders")?;
         drop(layers);
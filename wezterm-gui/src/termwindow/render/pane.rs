@@ -58,6 +58,7 @@ impl crate::TermWindow {
         let global_cursor_fg = self.palette().cursor_fg;
         let global_cursor_bg = self.palette().cursor_bg;
         let config = self.config.clone();
+        let window_background_opacity = self.window_background_opacity();
         let palette = pos.pane.palette();
 
         let (padding_left, padding_top) = self.padding_left_top();
@@ -94,7 +95,7 @@ impl crate::TermWindow {
         let filled_box = gl_state.util_sprites.filled_box.texture_coords();
 
         let window_is_transparent =
-            !self.window_background.is_empty() || config.window_background_opacity != 1.0;
+            !self.window_background.is_empty() || window_background_opacity != 1.0;
 
         let default_bg = palette
             .resolve_bg(ColorAttribute::Default)
@@ -162,7 +163,7 @@ impl crate::TermWindow {
                     palette
                         .background
                         .to_linear()
-                        .mul_alpha(config.window_background_opacity),
+                        .mul_alpha(window_background_opacity),
                 )
                 .context("filled_rectangle")?;
             quad.set_hsv(if pos.is_active {
@@ -198,7 +199,7 @@ impl crate::TermWindow {
                     let (r1, g1, b1, a) = palette
                         .background
                         .to_linear()
-                        .mul_alpha(config.window_background_opacity)
+                        .mul_alpha(window_background_opacity)
                         .tuple();
                     LinearRgba::with_components(
                         r1 + (r - r1) * intensity,
@@ -286,6 +287,96 @@ impl crate::TermWindow {
             } else {
                 Some(config.inactive_pane_hsb)
             });
+
+            // Mark flagged lines in the scrollbar, in proportion to
+            // their position in the scrollback
+            let flag_color = palette.colors.0[11].to_linear();
+            let scroll_size = dims.scrollback_rows.max(1) as f32;
+            for row in self.flagged_lines(pane_id) {
+                if row < dims.scrollback_top {
+                    continue;
+                }
+                let fraction = (row - dims.scrollback_top) as f32 / scroll_size;
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(
+                        thumb_x,
+                        pane_pixel_top + (fraction * pane_pixel_height).min(pane_pixel_height - 2.),
+                        scrollbar_width,
+                        2.,
+                    ),
+                    flag_color,
+                )
+                .context("scrollbar flag filled_rectangle")?;
+            }
+        }
+
+        // Show an indicator alongside any flagged lines that are visible
+        {
+            let flag_color = palette.colors.0[11].to_linear();
+            let top = current_viewport.unwrap_or(dims.physical_top);
+            let visible = top..top + dims.viewport_rows as StableRowIndex;
+            let left_pixel_x =
+                padding_left + border.left.get() as f32 + (pos.left as f32 * cell_width);
+            for row in self.flagged_lines(pane_id) {
+                if !visible.contains(&row) {
+                    continue;
+                }
+                let y = top_pixel_y + ((pos.top as isize + row - top) as f32 * cell_height);
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(left_pixel_x, y, (cell_width / 4.).max(2.), cell_height),
+                    flag_color,
+                )
+                .context("line flag filled_rectangle")?;
+            }
+        }
+
+        // Draw the column rulers down the full height of the pane
+        {
+            let ruler_color = palette.colors.0[8].to_linear();
+            let left_pixel_x =
+                padding_left + border.left.get() as f32 + (pos.left as f32 * cell_width);
+            let y = top_pixel_y + (pos.top as f32 * cell_height);
+            for column in self.column_rulers(pane_id) {
+                if column >= pos.width {
+                    continue;
+                }
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(
+                        left_pixel_x + (column as f32 * cell_width),
+                        y,
+                        1.,
+                        pos.height as f32 * cell_height,
+                    ),
+                    ruler_color,
+                )
+                .context("column ruler filled_rectangle")?;
+            }
+        }
+
+        // Dim the panes that a previewed context menu action leaves alone
+        if let Some(highlight) = self.preview_pane_highlight() {
+            if highlight != pane_id {
+                let left_pixel_x =
+                    padding_left + border.left.get() as f32 + (pos.left as f32 * cell_width);
+                self.filled_rectangle(
+                    layers,
+                    2,
+                    euclid::rect(
+                        left_pixel_x,
+                        top_pixel_y + (pos.top as f32 * cell_height),
+                        pos.width as f32 * cell_width,
+                        pos.height as f32 * cell_height,
+                    ),
+                    LinearRgba::with_components(0., 0., 0., 0.5),
+                )
+                .context("pane preview filled_rectangle")?;
+            }
         }
 
         let (selrange, rectangular) = {
@@ -665,13 +756,14 @@ impl crate::TermWindow {
             border: PixelDimension::default(),
             border_rect: background_rect,
             border_corners: None,
+            shadow: None,
             colors: ElementColors {
                 border: BorderColor::default(),
                 bg: if self.window_background.is_empty() {
                     palette
                         .background
                         .to_linear()
-                        .mul_alpha(self.config.window_background_opacity)
+                        .mul_alpha(self.window_background_opacity())
                         .into()
                 } else {
                     InheritableColor::Inherited
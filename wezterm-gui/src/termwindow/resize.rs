@@ -64,7 +64,7 @@ impl super::TermWindow {
         } else {
             self.scaling_changed(dimensions, self.fonts.get_font_scale(), window);
         }
-        if let Some(modal) = self.get_modal() {
+        for modal in self.get_modals() {
             modal.reconfigure(self);
         }
         self.emit_window_event("window-resized", None);
@@ -1,10 +1,12 @@
 use crate::selection::{Selection, SelectionCoordinate, SelectionMode, SelectionRange, SelectionX};
+use crate::termwindow::highlight::{tokenize, Language, TokenKind};
 use ::window::WindowOps;
 use mux::pane::{Pane, PaneId};
 use std::cell::RefMut;
 use std::sync::Arc;
 use termwiz::surface::Line;
-use wezterm_term::StableRowIndex;
+use wezterm_term::color::ColorPalette;
+use wezterm_term::{CellAttributes, ColorAttribute, Intensity, StableRowIndex, Underline};
 
 impl super::TermWindow {
     pub fn selection(&self, pane_id: PaneId) -> RefMut<'_, Selection> {
@@ -62,6 +64,77 @@ impl super::TermWindow {
         result
     }
 
+    /// Returns the selection region as an HTML fragment, with the
+    /// colors and text attributes of each cell expressed as inline styles
+    pub fn selection_html(&self, pane: &Arc<dyn Pane>) -> String {
+        let palette = pane.palette();
+        let mut html = format!(
+            "<pre style=\"font-family: monospace; color: {}; background-color: {};\">",
+            palette.foreground.to_rgb_string(),
+            palette.background.to_rgb_string()
+        );
+
+        for (idx, line) in self.selection_lines(pane).iter().enumerate() {
+            if idx > 0 {
+                html.push('\n');
+            }
+            let mut current_style: Option<String> = None;
+            for cell in line.visible_cells() {
+                let style = html_style(cell.attrs(), &palette);
+                if current_style.as_ref() != Some(&style) {
+                    if current_style.is_some() {
+                        html.push_str("</span>");
+                    }
+                    html.push_str(&format!("<span style=\"{style}\">"));
+                    current_style = Some(style);
+                }
+                html_escape(cell.str(), &mut html);
+            }
+            if current_style.is_some() {
+                html.push_str("</span>");
+            }
+        }
+
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Returns the selection text as an HTML fragment, syntax highlighted
+    /// as `language` using the ANSI colors of the pane's palette
+    pub fn selection_highlighted_html(&self, pane: &Arc<dyn Pane>, language: Language) -> String {
+        let palette = pane.palette();
+        let text = self.selection_text(pane);
+        let mut html = format!(
+            "<pre style=\"font-family: monospace; color: {}; background-color: {};\">",
+            palette.foreground.to_rgb_string(),
+            palette.background.to_rgb_string()
+        );
+
+        for (kind, token) in tokenize(&text, language) {
+            let (color, style) = match kind {
+                TokenKind::Plain => {
+                    html_escape(token, &mut html);
+                    continue;
+                }
+                TokenKind::Keyword => (5, " font-weight: bold;"),
+                TokenKind::String => (2, ""),
+                TokenKind::Number => (3, ""),
+                TokenKind::Comment => (8, " font-style: italic;"),
+            };
+            html.push_str(&format!(
+                "<span style=\"color: {};{style}\">",
+                palette
+                    .resolve_fg(ColorAttribute::PaletteIndex(color))
+                    .to_rgb_string()
+            ));
+            html_escape(token, &mut html);
+            html.push_str("</span>");
+        }
+
+        html.push_str("</pre>");
+        html
+    }
+
     /// Returns the selection text only
     pub fn selection_text(&self, pane: &Arc<dyn Pane>) -> String {
         let mut s = String::new();
@@ -281,3 +354,47 @@ impl super::TermWindow {
         self.window.as_ref().unwrap().invalidate();
     }
 }
+
+/// Computes the inline CSS that represents the given attributes
+fn html_style(attrs: &CellAttributes, palette: &ColorPalette) -> String {
+    let mut fg = palette.resolve_fg(attrs.foreground());
+    let mut bg = palette.resolve_bg(attrs.background());
+    let mut has_bg = attrs.background() != ColorAttribute::Default;
+    if attrs.reverse() {
+        std::mem::swap(&mut fg, &mut bg);
+        has_bg = true;
+    }
+
+    let mut style = format!("color: {};", fg.to_rgb_string());
+    if has_bg {
+        style.push_str(&format!(" background-color: {};", bg.to_rgb_string()));
+    }
+    match attrs.intensity() {
+        Intensity::Bold => style.push_str(" font-weight: bold;"),
+        Intensity::Half => style.push_str(" opacity: 0.5;"),
+        Intensity::Normal => {}
+    }
+    if attrs.italic() {
+        style.push_str(" font-style: italic;");
+    }
+    let underline = attrs.underline() != Underline::None;
+    match (underline, attrs.strikethrough()) {
+        (true, true) => style.push_str(" text-decoration: underline line-through;"),
+        (true, false) => style.push_str(" text-decoration: underline;"),
+        (false, true) => style.push_str(" text-decoration: line-through;"),
+        (false, false) => {}
+    }
+    style
+}
+
+fn html_escape(text: &str, html: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            c => html.push(c),
+        }
+    }
+}
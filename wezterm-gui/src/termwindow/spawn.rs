@@ -1,6 +1,13 @@
+use crate::overlay::prompt::show_host_prompt_overlay;
+use crate::overlay::start_overlay;
 use crate::spawn::SpawnWhere;
+use crate::termwindow::TermWindowNotif;
 use config::keyassignment::{SpawnCommand, SpawnTabDomain};
-use config::TermConfig;
+use config::{SshDomain, SshMultiplexing, SshParameters, TermConfig};
+use mux::domain::Domain;
+use mux::ssh::RemoteSshDomain;
+use mux::Mux;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
 impl super::TermWindow {
@@ -33,4 +40,119 @@ impl super::TermWindow {
             SpawnWhere::NewTab,
         );
     }
+
+    /// Prompts for `host` or `user@host`, completing host names from
+    /// `~/.ssh/known_hosts`, and opens a new tab connected to it
+    pub fn prompt_ssh_host(&mut self) {
+        let mux = Mux::get();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let known_hosts = config::HOME_DIR.join(".ssh").join("known_hosts");
+        let hosts = std::fs::read_to_string(&known_hosts)
+            .map(|text| known_host_names(&text))
+            .unwrap_or_default();
+        let window = self.window.clone().unwrap();
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            show_host_prompt_overlay(
+                term,
+                "Enter the host to connect to, as host or user@host; Tab completes",
+                hosts,
+                move |target| {
+                    window.notify(TermWindowNotif::Apply(Box::new(move |myself| {
+                        myself.ssh_to_host(&target);
+                    })));
+                },
+            )
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    /// Opens a new tab connected to `target` over ssh, without
+    /// multiplexing, in the same way as `wezterm ssh`
+    fn ssh_to_host(&mut self, target: &str) {
+        let params: SshParameters = match target.parse() {
+            Ok(params) => params,
+            Err(err) => {
+                log::error!("Invalid ssh host {target}: {err:#}");
+                return;
+            }
+        };
+
+        let name = format!("SSH to {params}");
+        let mux = Mux::get();
+        if mux.get_domain_by_name(&name).is_none() {
+            let dom = SshDomain {
+                name: name.clone(),
+                remote_address: params.host_and_port.clone(),
+                username: params.username.clone(),
+                multiplexing: SshMultiplexing::None,
+                ..Default::default()
+            };
+            match RemoteSshDomain::with_ssh_domain(&dom) {
+                Ok(domain) => {
+                    let domain: Arc<dyn Domain> = Arc::new(domain);
+                    mux.add_domain(&domain);
+                }
+                Err(err) => {
+                    log::error!("Failed to set up ssh to {params}: {err:#}");
+                    return;
+                }
+            }
+        }
+
+        self.spawn_tab(&SpawnTabDomain::DomainName(name));
+    }
+}
+
+/// Returns the host names listed in a known_hosts file, sorted and
+/// without duplicates. Hashed entries and wildcard patterns can't
+/// be completed, so they are left out.
+fn known_host_names(text: &str) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let hosts = match fields.next() {
+            Some(marker) if marker.starts_with('@') => fields.next(),
+            Some(comment) if comment.starts_with('#') => None,
+            hosts => hosts,
+        };
+        for host in hosts.into_iter().flat_map(|hosts| hosts.split(',')) {
+            if host.starts_with('|') || host.contains(|c: char| matches!(c, '*' | '?' | '!')) {
+                continue;
+            }
+            // Non-default ports are written as [host]:port
+            let host = match host.strip_prefix('[').and_then(|h| h.split_once("]:")) {
+                Some((host, port)) => format!("{host}:{port}"),
+                None => host.to_string(),
+            };
+            names.insert(host);
+        }
+    }
+    names.into_iter().collect()
+}
+
+#[cfg(test)]
+mod spawn_test {
+    use super::*;
+
+    #[test]
+    fn known_hosts() {
+        let text = "\
+# a comment
+github.com,140.82.121.4 ssh-ed25519 AAAA
+[build.local]:2222 ssh-rsa AAAA
+@cert-authority *.example.com ssh-rsa AAAA
+|1|JfKTdBh7rNbXkVAQCRp4OQoPfmI=|USECr3SWf1JUPsms5AqfD5QfxkM= ssh-rsa AAAA
+github.com ecdsa-sha2-nistp256 AAAA
+";
+        assert_eq!(
+            known_host_names(text),
+            vec!["140.82.121.4", "build.local:2222", "github.com"]
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use crate::quad::Vertex;
+use crate::termwindow::blur::BlurPass;
 use anyhow::anyhow;
 use config::{ConfigHandle, GpuInfo, WebGpuPowerPreference};
 use std::cell::RefCell;
@@ -34,6 +35,9 @@ pub struct WebGpuState {
     pub texture_bind_group_layout: wgpu::BindGroupLayout,
     pub texture_nearest_sampler: wgpu::Sampler,
     pub texture_linear_sampler: wgpu::Sampler,
+    /// Used to draw blurred shadows; None if the adapter
+    /// cannot run compute shaders
+    pub blur_pass: Option<BlurPass>,
     pub handle: RawHandlePair,
 }
 
@@ -359,8 +363,18 @@ impl WebGpuState {
             vec![]
         };
 
+        // Reading back from the surface is needed to blur the backdrop
+        // of the context menu. It can stop the driver from optimizing
+        // the surface, so only ask for it when that blur is enabled.
+        let surface_readable = config.context_menu_blur_background
+            && caps.usages.contains(wgpu::TextureUsages::COPY_SRC);
+
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: if surface_readable {
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+            } else {
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+            },
             format,
             width: dimensions.pixel_width as u32,
             height: dimensions.pixel_height as u32,
@@ -492,6 +506,15 @@ impl WebGpuState {
             cache: None,
         });
 
+        let blur_pass = if downlevel_caps
+            .flags
+            .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        {
+            Some(BlurPass::new(&device, config.format, surface_readable))
+        } else {
+            None
+        };
+
         Ok(Self {
             adapter_info,
             downlevel_caps,
@@ -506,6 +529,7 @@ impl WebGpuState {
             texture_bind_group_layout,
             texture_nearest_sampler,
             texture_linear_sampler,
+            blur_pass,
         })
     }
 
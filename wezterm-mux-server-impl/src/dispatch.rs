@@ -3,7 +3,9 @@ use anyhow::Context;
 use async_ossl::AsyncSslStream;
 use codec::{DecodedPdu, Pdu};
 use futures::FutureExt;
+use mux::window::WindowId;
 use mux::{Mux, MuxNotification};
+use smol::channel::Receiver;
 use smol::prelude::*;
 use smol::Async;
 use wezterm_uds::UnixStream;
@@ -21,6 +23,7 @@ enum Item {
     Notif(MuxNotification),
     WritePdu(DecodedPdu),
     Readable,
+    Stopped,
 }
 
 pub async fn process<T>(stream: T) -> anyhow::Result<()>
@@ -36,7 +39,42 @@ where
     process_async(stream).await
 }
 
-pub async fn process_async<T>(mut stream: Async<T>) -> anyhow::Result<()>
+/// Like `process`, but the client may only observe the panes of
+/// `window_id`; any other request is refused.
+/// The client is disconnected once `stopped` is closed.
+pub async fn process_read_only<T>(
+    stream: T,
+    window_id: WindowId,
+    stopped: Option<Receiver<()>>,
+) -> anyhow::Result<()>
+where
+    T: 'static,
+    T: std::io::Read,
+    T: std::io::Write,
+    T: AsRawDesc,
+    T: std::fmt::Debug,
+    T: async_io::IoSafe,
+{
+    let stream = smol::Async::new(stream)?;
+    process_async_impl(stream, Some(window_id), stopped).await
+}
+
+pub async fn process_async<T>(stream: Async<T>) -> anyhow::Result<()>
+where
+    T: 'static,
+    T: std::io::Read,
+    T: std::io::Write,
+    T: std::fmt::Debug,
+    T: async_io::IoSafe,
+{
+    process_async_impl(stream, None, None).await
+}
+
+async fn process_async_impl<T>(
+    mut stream: Async<T>,
+    read_only: Option<WindowId>,
+    stopped: Option<Receiver<()>>,
+) -> anyhow::Result<()>
 where
     T: 'static,
     T: std::io::Read,
@@ -56,7 +94,10 @@ where
                 .map_err(|e| anyhow::anyhow!("{:?}", e))
         }
     });
-    let mut handler = SessionHandler::new(pdu_sender);
+    let mut handler = match read_only {
+        Some(window_id) => SessionHandler::new_read_only(pdu_sender, window_id),
+        None => SessionHandler::new(pdu_sender),
+    };
 
     {
         let mux = Mux::get();
@@ -67,8 +108,18 @@ where
     loop {
         let rx_msg = item_rx.recv();
         let wait_for_read = stream.readable().map(|_| Ok(Item::Readable));
+        let wait_for_stop = async {
+            match &stopped {
+                // Nothing is ever sent; this resolves once the sender is dropped
+                Some(stopped) => {
+                    stopped.recv().await.ok();
+                    Ok(Item::Stopped)
+                }
+                None => smol::future::pending().await,
+            }
+        };
 
-        match smol::future::or(rx_msg, wait_for_read).await {
+        match smol::future::or(smol::future::or(rx_msg, wait_for_read), wait_for_stop).await {
             Ok(Item::Readable) => {
                 let decoded = match Pdu::decode_async(&mut stream, None).await {
                     Ok(data) => data,
@@ -84,6 +135,10 @@ where
                 };
                 handler.process_one(decoded);
             }
+            Ok(Item::Stopped) => {
+                log::trace!("listener stopped; disconnecting client");
+                return Ok(());
+            }
             Ok(Item::WritePdu(decoded)) => {
                 match decoded.pdu.encode_async(&mut stream, decoded.serial).await {
                     Ok(()) => {}
@@ -109,7 +164,9 @@ where
                 }
             }
             Ok(Item::Notif(MuxNotification::PaneOutput(pane_id))) => {
-                handler.schedule_pane_push(pane_id);
+                if handler.can_observe_pane(pane_id) {
+                    handler.schedule_pane_push(pane_id);
+                }
             }
             Ok(Item::Notif(MuxNotification::PaneAdded(_pane_id))) => {}
             Ok(Item::Notif(MuxNotification::PaneRemoved(pane_id))) => {
@@ -119,6 +176,9 @@ where
                 stream.flush().await.context("flushing PDU to client")?;
             }
             Ok(Item::Notif(MuxNotification::Alert { pane_id, alert })) => {
+                if !handler.can_observe_pane(pane_id) {
+                    continue;
+                }
                 {
                     let per_pane = handler.per_pane(pane_id);
                     let mut per_pane = per_pane.lock().unwrap();
@@ -132,6 +192,10 @@ where
                 selection,
                 clipboard,
             })) => {
+                if handler.is_read_only() {
+                    // Observers don't get to see what was copied
+                    continue;
+                }
                 Pdu::SetClipboard(codec::SetClipboard {
                     pane_id,
                     clipboard,
@@ -142,6 +206,9 @@ where
                 stream.flush().await.context("flushing PDU to client")?;
             }
             Ok(Item::Notif(MuxNotification::TabAddedToWindow { tab_id, window_id })) => {
+                if !handler.can_observe_window(window_id) {
+                    continue;
+                }
                 Pdu::TabAddedToWindow(codec::TabAddedToWindow { tab_id, window_id })
                     .encode_async(&mut stream, 0)
                     .await?;
@@ -151,6 +218,9 @@ where
             Ok(Item::Notif(MuxNotification::WindowCreated(_window_id))) => {}
             Ok(Item::Notif(MuxNotification::WindowInvalidated(_window_id))) => {}
             Ok(Item::Notif(MuxNotification::WindowWorkspaceChanged(window_id))) => {
+                if !handler.can_observe_window(window_id) {
+                    continue;
+                }
                 let workspace = {
                     let mux = Mux::get();
                     mux.get_window(window_id)
@@ -167,24 +237,36 @@ where
                 }
             }
             Ok(Item::Notif(MuxNotification::PaneFocused(pane_id))) => {
+                if !handler.can_observe_pane(pane_id) {
+                    continue;
+                }
                 Pdu::PaneFocused(codec::PaneFocused { pane_id })
                     .encode_async(&mut stream, 0)
                     .await?;
                 stream.flush().await.context("flushing PDU to client")?;
             }
             Ok(Item::Notif(MuxNotification::TabResized(tab_id))) => {
+                if !handler.can_observe_tab(tab_id) {
+                    continue;
+                }
                 Pdu::TabResized(codec::TabResized { tab_id })
                     .encode_async(&mut stream, 0)
                     .await?;
                 stream.flush().await.context("flushing PDU to client")?;
             }
             Ok(Item::Notif(MuxNotification::TabTitleChanged { tab_id, title })) => {
+                if !handler.can_observe_tab(tab_id) {
+                    continue;
+                }
                 Pdu::TabTitleChanged(codec::TabTitleChanged { tab_id, title })
                     .encode_async(&mut stream, 0)
                     .await?;
                 stream.flush().await.context("flushing PDU to client")?;
             }
             Ok(Item::Notif(MuxNotification::WindowTitleChanged { window_id, title })) => {
+                if !handler.can_observe_window(window_id) {
+                    continue;
+                }
                 Pdu::WindowTitleChanged(codec::WindowTitleChanged { window_id, title })
                     .encode_async(&mut stream, 0)
                     .await?;
@@ -194,6 +276,13 @@ where
                 old_workspace,
                 new_workspace,
             })) => {
+                // The notification can arrive before or after the shared
+                // window has moved to the new name
+                if !handler.can_observe_workspace(&old_workspace)
+                    && !handler.can_observe_workspace(&new_workspace)
+                {
+                    continue;
+                }
                 Pdu::RenameWorkspace(codec::RenameWorkspace {
                     old_workspace,
                     new_workspace,
@@ -204,6 +293,9 @@ where
             }
             Ok(Item::Notif(MuxNotification::ActiveWorkspaceChanged(_))) => {}
             Ok(Item::Notif(MuxNotification::Empty)) => {}
+            Ok(Item::Notif(MuxNotification::ShowContextMenu { .. })) => {}
+            Ok(Item::Notif(MuxNotification::DescribeContextMenu { .. })) => {}
+            Ok(Item::Notif(MuxNotification::DumpModal { .. })) => {}
             Err(err) => {
                 log::error!("process_async Err {}", err);
                 return Ok(());
@@ -1,15 +1,61 @@
 use anyhow::{anyhow, Context as _};
 use config::{create_user_owned_dirs, UnixDomain};
+use mux::window::WindowId;
 use promise::spawn::spawn_into_main_thread;
-use wezterm_uds::UnixListener;
+use smol::channel::{Receiver, Sender};
+use std::path::{Path, PathBuf};
+use wezterm_uds::{UnixListener, UnixStream};
 
 pub struct LocalListener {
     listener: UnixListener,
+    read_only: Option<WindowId>,
+    stopped: Option<Receiver<()>>,
+}
+
+/// Returned by `LocalListener::stop_handle`; stops that listener
+/// and disconnects its clients
+pub struct StopHandle {
+    socket_path: PathBuf,
+    sender: Sender<()>,
+}
+
+impl StopHandle {
+    pub fn stop(self) {
+        let Self {
+            socket_path,
+            sender,
+        } = self;
+        // Closing the channel disconnects the clients, and the
+        // connection below wakes up the listener so that it notices
+        drop(sender);
+        UnixStream::connect(&socket_path).ok();
+    }
 }
 
 impl LocalListener {
     pub fn new(listener: UnixListener) -> Self {
-        Self { listener }
+        Self {
+            listener,
+            read_only: None,
+            stopped: None,
+        }
+    }
+
+    /// Only allow clients of this listener to observe the panes of
+    /// `window_id`, or lift that restriction when it is None
+    pub fn set_read_only(&mut self, window_id: Option<WindowId>) {
+        self.read_only = window_id;
+    }
+
+    /// Returns a handle that stops this listener, which is
+    /// listening on `socket_path`
+    pub fn stop_handle(&mut self, socket_path: &Path) -> StopHandle {
+        let (sender, receiver) = smol::channel::bounded(1);
+        self.stopped.replace(receiver);
+        StopHandle {
+            socket_path: socket_path.to_path_buf(),
+            sender,
+        }
     }
 
     pub fn with_domain(unix_dom: &UnixDomain) -> anyhow::Result<Self> {
@@ -21,8 +67,21 @@ impl LocalListener {
         for stream in self.listener.incoming() {
             match stream {
                 Ok(stream) => {
+                    if let Some(stopped) = &self.stopped {
+                        if stopped.is_closed() {
+                            return;
+                        }
+                    }
+                    let read_only = self.read_only;
+                    let stopped = self.stopped.clone();
                     spawn_into_main_thread(async move {
-                        crate::dispatch::process(stream).await.map_err(|e| {
+                        let result = match read_only {
+                            Some(window_id) => {
+                                crate::dispatch::process_read_only(stream, window_id, stopped).await
+                            }
+                            None => crate::dispatch::process(stream).await,
+                        };
+                        result.map_err(|e| {
                             log::error!("{:#}", e);
                             e
                         })
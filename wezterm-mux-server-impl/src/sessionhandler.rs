@@ -7,6 +7,7 @@ use mux::domain::SplitSource;
 use mux::pane::{CachePolicy, Pane, PaneId};
 use mux::renderable::{RenderableDimensions, StableCursorPosition};
 use mux::tab::TabId;
+use mux::window::WindowId;
 use mux::{Mux, MuxNotification};
 use promise::spawn::spawn_into_main_thread;
 use std::collections::HashMap;
@@ -201,6 +202,8 @@ pub struct SessionHandler {
     per_pane: HashMap<TabId, Arc<Mutex<PerPane>>>,
     client_id: Option<Arc<ClientId>>,
     proxy_client_id: Option<ClientId>,
+    /// When set, the session may only observe the panes of this window
+    read_only: Option<WindowId>,
 }
 
 impl Drop for SessionHandler {
@@ -219,6 +222,83 @@ impl SessionHandler {
             per_pane: HashMap::new(),
             client_id: None,
             proxy_client_id: None,
+            read_only: None,
+        }
+    }
+
+    /// Create a handler for a session that may observe the panes of
+    /// `window_id`, but that refuses any other request
+    pub fn new_read_only(to_write_tx: PduSender, window_id: WindowId) -> Self {
+        Self {
+            read_only: Some(window_id),
+            ..Self::new(to_write_tx)
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.is_some()
+    }
+
+    /// Returns true if this session may see the content of `pane_id`
+    pub fn can_observe_pane(&self, pane_id: PaneId) -> bool {
+        match self.read_only {
+            None => true,
+            Some(window_id) => Mux::get()
+                .resolve_pane_id(pane_id)
+                .map(|(_domain_id, pane_window_id, _tab_id)| pane_window_id == window_id)
+                .unwrap_or(false),
+        }
+    }
+
+    /// Returns true if this session may see events about `window_id`
+    pub fn can_observe_window(&self, window_id: WindowId) -> bool {
+        match self.read_only {
+            None => true,
+            Some(shared_window_id) => shared_window_id == window_id,
+        }
+    }
+
+    /// Returns true if this session may see events about `tab_id`
+    pub fn can_observe_tab(&self, tab_id: TabId) -> bool {
+        match self.read_only {
+            None => true,
+            Some(window_id) => Mux::get().window_containing_tab(tab_id) == Some(window_id),
+        }
+    }
+
+    /// Returns true if this session may see events about `workspace`
+    pub fn can_observe_workspace(&self, workspace: &str) -> bool {
+        match self.read_only {
+            None => true,
+            Some(window_id) => Mux::get()
+                .get_window(window_id)
+                .map(|window| window.get_workspace() == workspace)
+                .unwrap_or(false),
+        }
+    }
+
+    /// Returns true if this session is allowed to make the request `pdu`
+    fn permits(&self, pdu: &Pdu) -> bool {
+        let window_id = match self.read_only {
+            None => return true,
+            Some(window_id) => window_id,
+        };
+        if !pdu.is_observation() {
+            return false;
+        }
+        match pdu {
+            Pdu::GetLines(GetLines { pane_id, .. })
+            | Pdu::GetPaneRenderChanges(GetPaneRenderChanges { pane_id, .. })
+            | Pdu::GetPaneRenderableDimensions(GetPaneRenderableDimensions { pane_id })
+            | Pdu::GetImageCell(GetImageCell { pane_id, .. })
+            | Pdu::SearchScrollbackRequest(SearchScrollbackRequest { pane_id, .. })
+            | Pdu::GetContextMenuItems(GetContextMenuItems { pane_id }) => {
+                self.can_observe_pane(*pane_id)
+            }
+            Pdu::DumpModal(DumpModal {
+                window_id: modal_window_id,
+            }) => *modal_window_id == window_id,
+            _ => true,
         }
     }
 
@@ -274,6 +354,21 @@ impl SessionHandler {
             send_response(f());
         }
 
+        if self.is_read_only() {
+            if let Pdu::SetClientId(_) = &decoded.pdu {
+                // Every client sends this when it connects; accept it without
+                // registering, so that observers don't take part in the focus
+                // and idle tracking of the mux clients
+                send_response(Ok(Pdu::UnitResponse(UnitResponse {})));
+                return;
+            }
+        }
+
+        if !self.permits(&decoded.pdu) {
+            send_response(Err(anyhow!("this session is read-only")));
+            return;
+        }
+
         match decoded.pdu {
             Pdu::Ping(Ping {}) => send_response(Ok(Pdu::Pong(Pong {}))),
             Pdu::SetWindowWorkspace(SetWindowWorkspace {
@@ -386,6 +481,7 @@ impl SessionHandler {
                 .detach();
             }
             Pdu::ListPanes(ListPanes {}) => {
+                let shared_window_id = self.read_only;
                 spawn_into_main_thread(async move {
                     catch(
                         move || {
@@ -394,6 +490,9 @@ impl SessionHandler {
                             let mut tab_titles = vec![];
                             let mut window_titles = HashMap::new();
                             for window_id in mux.iter_windows().into_iter() {
+                                if shared_window_id.map_or(false, |shared| shared != window_id) {
+                                    continue;
+                                }
                                 let window = mux.get_window(window_id).unwrap();
                                 window_titles.insert(window_id, window.get_title().to_string());
                                 for tab in window.iter() {
@@ -987,6 +1086,77 @@ impl SessionHandler {
                 .detach();
             }
 
+            Pdu::ShowContextMenu(ShowContextMenu {
+                pane_id,
+                items_json,
+            }) => {
+                spawn_into_main_thread(async move {
+                    catch(
+                        move || {
+                            let mux = Mux::get();
+                            mux.get_pane(pane_id)
+                                .ok_or_else(|| anyhow!("pane_id {} invalid", pane_id))?;
+                            let items =
+                                config::keyassignment::MenuItemDef::list_from_json_str(&items_json)
+                                    .context("parsing context menu items")?;
+                            anyhow::ensure!(!items.is_empty(), "no context menu items");
+
+                            mux.notify(MuxNotification::ShowContextMenu { pane_id, items });
+                            Ok(Pdu::UnitResponse(UnitResponse {}))
+                        },
+                        send_response,
+                    )
+                })
+                .detach();
+            }
+
+            Pdu::GetContextMenuItems(GetContextMenuItems { pane_id }) => {
+                async fn get_items(pane_id: PaneId) -> anyhow::Result<Pdu> {
+                    let mux = Mux::get();
+                    mux.get_pane(pane_id)
+                        .ok_or_else(|| anyhow!("pane_id {} invalid", pane_id))?;
+                    let (reply, items) = smol::channel::bounded(1);
+                    mux.notify(MuxNotification::DescribeContextMenu { pane_id, reply });
+                    let items_json = items
+                        .recv()
+                        .await
+                        .map_err(|_| anyhow!("pane {} is not shown in a gui window", pane_id))?;
+                    Ok(Pdu::GetContextMenuItemsResponse(
+                        GetContextMenuItemsResponse { items_json },
+                    ))
+                }
+
+                spawn_into_main_thread(async move {
+                    promise::spawn::spawn(async move {
+                        send_response(get_items(pane_id).await);
+                    })
+                    .detach();
+                })
+                .detach();
+            }
+
+            Pdu::DumpModal(DumpModal { window_id }) => {
+                async fn dump_modal(window_id: WindowId) -> anyhow::Result<Pdu> {
+                    let mux = Mux::get();
+                    mux.get_window(window_id)
+                        .ok_or_else(|| anyhow!("window_id {} invalid", window_id))?;
+                    let (reply, modal) = smol::channel::bounded(1);
+                    mux.notify(MuxNotification::DumpModal { window_id, reply });
+                    let modal_json = modal.recv().await.map_err(|_| {
+                        anyhow!("window {} is not shown in a gui window", window_id)
+                    })?;
+                    Ok(Pdu::DumpModalResponse(DumpModalResponse { modal_json }))
+                }
+
+                spawn_into_main_thread(async move {
+                    promise::spawn::spawn(async move {
+                        send_response(dump_modal(window_id).await);
+                    })
+                    .detach();
+                })
+                .detach();
+            }
+
             Pdu::Invalid { .. } => send_response(Err(anyhow!("invalid PDU {:?}", decoded.pdu))),
             Pdu::Pong { .. }
             | Pdu::ListPanesResponse { .. }
@@ -997,6 +1167,8 @@ impl SessionHandler {
             | Pdu::UnitResponse { .. }
             | Pdu::LivenessResponse { .. }
             | Pdu::GetPaneDirectionResponse { .. }
+            | Pdu::GetContextMenuItemsResponse { .. }
+            | Pdu::DumpModalResponse { .. }
             | Pdu::SearchScrollbackResponse { .. }
             | Pdu::GetLinesResponse { .. }
             | Pdu::GetCodecVersionResponse { .. }
@@ -0,0 +1,54 @@
+use anyhow::Context;
+use clap::{Parser, ValueHint};
+use codec::ShowContextMenu;
+use config::keyassignment::MenuItemDef;
+use mux::pane::PaneId;
+use std::io::Read;
+use std::path::PathBuf;
+use wezterm_client::client::Client;
+
+#[derive(Debug, Parser, Clone)]
+pub struct ContextMenu {
+    /// Specify the target pane.
+    /// The default is to use the current pane based on the
+    /// environment variable WEZTERM_PANE.
+    #[arg(long)]
+    pane_id: Option<PaneId>,
+
+    /// A file holding a JSON array of menu items, each with a
+    /// `label`, an optional `icon` and an `action`.
+    /// Use `-` to read the items from stdin.
+    #[arg(long, value_parser, value_hint=ValueHint::FilePath)]
+    json: PathBuf,
+}
+
+impl ContextMenu {
+    pub async fn run(self, client: Client) -> anyhow::Result<()> {
+        let pane_id = client.resolve_pane_id(self.pane_id).await?;
+
+        let items_json = if self.json.as_os_str() == "-" {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .context("reading stdin")?;
+            text
+        } else {
+            std::fs::read_to_string(&self.json)
+                .with_context(|| format!("reading {}", self.json.display()))?
+        };
+
+        // Catch mistakes here, where they can be reported to the user,
+        // rather than in the log of the gui
+        let items = MenuItemDef::list_from_json_str(&items_json)
+            .with_context(|| format!("parsing menu items from {}", self.json.display()))?;
+        anyhow::ensure!(!items.is_empty(), "no menu items were specified");
+
+        client
+            .show_context_menu(ShowContextMenu {
+                pane_id,
+                items_json,
+            })
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use clap::Parser;
+use mux::window::WindowId;
+use serde_json::Value;
+use wezterm_client::client::Client;
+
+#[derive(Debug, Parser, Clone)]
+pub struct DumpModal {
+    /// Specify the target window by its id
+    #[arg(long)]
+    window_id: WindowId,
+}
+
+impl DumpModal {
+    pub async fn run(self, client: Client) -> anyhow::Result<()> {
+        let response = client
+            .dump_modal(codec::DumpModal {
+                window_id: self.window_id,
+            })
+            .await?;
+        let modal = match response.modal_json {
+            Some(modal_json) => serde_json::from_str(&modal_json)?,
+            None => Value::Null,
+        };
+        println!("{}", serde_json::to_string_pretty(&modal)?);
+        Ok(())
+    }
+}
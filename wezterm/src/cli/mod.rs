@@ -7,6 +7,8 @@ mod activate_pane;
 mod activate_pane_direction;
 mod activate_tab;
 mod adjust_pane_size;
+mod context_menu;
+mod dump_modal;
 mod get_pane_direction;
 mod get_text;
 mod kill_pane;
@@ -18,6 +20,7 @@ mod rename_workspace;
 mod send_text;
 mod set_tab_title;
 mod set_window_title;
+mod show_context_menu_items;
 mod spawn_command;
 mod split_pane;
 mod tls_creds;
@@ -163,6 +166,21 @@ Outputs the pane-id for the newly created pane on success"
     /// Zoom, unzoom, or toggle zoom state
     #[command(name = "zoom-pane", rename_all = "kebab")]
     ZoomPane(zoom_pane::ZoomPane),
+
+    /// Show a context menu over a pane.
+    /// The action of the item that is chosen is performed by the gui.
+    #[command(name = "context-menu", rename_all = "kebab")]
+    ContextMenu(context_menu::ContextMenu),
+
+    /// List the items of the context menu that the gui would
+    /// show over a pane, to check what the config adds to it
+    #[command(name = "show-context-menu-items", rename_all = "kebab")]
+    ShowContextMenuItems(show_context_menu_items::ShowContextMenuItems),
+
+    /// Print the state of the modal, such as a context menu, that is
+    /// open in a gui window, as JSON
+    #[command(name = "dump-modal", rename_all = "kebab")]
+    DumpModal(dump_modal::DumpModal),
 }
 
 async fn run_cli_async(opts: &crate::Opt, cli: CliCommand) -> anyhow::Result<()> {
@@ -199,6 +217,9 @@ async fn run_cli_async(opts: &crate::Opt, cli: CliCommand) -> anyhow::Result<()>
         CliSubCommand::SetWindowTitle(cmd) => cmd.run(client).await,
         CliSubCommand::RenameWorkspace(cmd) => cmd.run(client).await,
         CliSubCommand::ZoomPane(cmd) => cmd.run(client).await,
+        CliSubCommand::ContextMenu(cmd) => cmd.run(client).await,
+        CliSubCommand::ShowContextMenuItems(cmd) => cmd.run(client).await,
+        CliSubCommand::DumpModal(cmd) => cmd.run(client).await,
     }
 }
 
@@ -0,0 +1,104 @@
+use crate::cli::CliOutputFormatKind;
+use clap::Parser;
+use mux::pane::PaneId;
+use serde_json::Value;
+use tabout::{tabulate_output, Alignment, Column};
+use wezterm_client::client::Client;
+
+#[derive(Debug, Parser, Clone)]
+pub struct ShowContextMenuItems {
+    /// Specify the target pane.
+    /// The default is to use the current pane based on the
+    /// environment variable WEZTERM_PANE.
+    #[arg(long)]
+    pane_id: Option<PaneId>,
+
+    /// Controls the output format.
+    /// "table" and "json" are possible formats.
+    #[arg(long = "format", default_value = "table")]
+    format: CliOutputFormatKind,
+}
+
+/// Appends a row for each of `items`, with the items of
+/// nested menus indented below the entry that opens them
+fn collect_rows(items: &[Value], depth: usize, rows: &mut Vec<Vec<String>>) {
+    let indent = "  ".repeat(depth);
+    for item in items {
+        let (kind, fields) = match item {
+            Value::String(kind) => (kind.as_str(), None),
+            Value::Object(obj) => match obj.iter().next() {
+                Some((kind, fields)) => (kind.as_str(), Some(fields)),
+                None => continue,
+            },
+            _ => continue,
+        };
+        let field = |name: &str| fields.and_then(|fields| fields.get(name));
+        let label = field("label").and_then(Value::as_str).unwrap_or("");
+        let icon = field("icon").and_then(Value::as_str).unwrap_or("");
+
+        match kind {
+            "Entry" | "Checkmark" => {
+                // Just the variant name, as the arguments can be long
+                let action = match field("action") {
+                    Some(Value::String(action)) => action.to_string(),
+                    Some(Value::Object(action)) => {
+                        action.keys().next().cloned().unwrap_or_default()
+                    }
+                    _ => String::new(),
+                };
+                let checked = field("checked").and_then(Value::as_bool) == Some(true);
+                let icon = if checked { "✓" } else { icon };
+                rows.push(vec![format!("{indent}{label}"), icon.to_string(), action]);
+            }
+            "SubMenu" => {
+                rows.push(vec![
+                    format!("{indent}{label}"),
+                    icon.to_string(),
+                    "(menu)".to_string(),
+                ]);
+                if let Some(Value::Array(items)) = field("items") {
+                    collect_rows(items, depth + 1, rows);
+                }
+            }
+            _ => rows.push(vec![format!("{indent}──"), String::new(), String::new()]),
+        }
+    }
+}
+
+impl ShowContextMenuItems {
+    pub async fn run(self, client: Client) -> anyhow::Result<()> {
+        let pane_id = client.resolve_pane_id(self.pane_id).await?;
+        let response = client
+            .get_context_menu_items(codec::GetContextMenuItems { pane_id })
+            .await?;
+        let items: Value = serde_json::from_str(&response.items_json)?;
+
+        match self.format {
+            CliOutputFormatKind::Json => {
+                println!("{}", serde_json::to_string_pretty(&items)?);
+            }
+            CliOutputFormatKind::Table => {
+                let cols = vec![
+                    Column {
+                        name: "LABEL".to_string(),
+                        alignment: Alignment::Left,
+                    },
+                    Column {
+                        name: "ICON".to_string(),
+                        alignment: Alignment::Left,
+                    },
+                    Column {
+                        name: "ACTION".to_string(),
+                        alignment: Alignment::Left,
+                    },
+                ];
+                let mut rows = vec![];
+                if let Value::Array(items) = &items {
+                    collect_rows(items, 0, &mut rows);
+                }
+                tabulate_output(&cols, &rows, &mut std::io::stdout().lock())?;
+            }
+        }
+        Ok(())
+    }
+}
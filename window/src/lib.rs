@@ -308,6 +308,13 @@ pub trait WindowOps {
     /// windows to move themselves (not Wayland).
     fn set_window_position(&self, _coords: ScreenPoint) {}
 
+    /// Allows or prevents the user from moving the window by
+    /// dragging its title bar.
+    ///
+    /// This is only implemented on macOS; elsewhere the title
+    /// bar is managed by the window manager.
+    fn set_movable(&self, _movable: bool) {}
+
     /// inform the windowing system of the current textual
     /// cursor input location.  This is used primarily for
     /// the platform specific input method editor
@@ -319,6 +326,14 @@ pub trait WindowOps {
     /// Set some text in the clipboard
     fn set_clipboard(&self, clipboard: Clipboard, text: String);
 
+    /// Set some html in the clipboard, along with `text` as the plain
+    /// text alternative for applications that don't accept html.
+    /// Where html isn't supported, only the text is set.
+    fn set_clipboard_html(&self, clipboard: Clipboard, html: String, text: String) {
+        let _ = html;
+        self.set_clipboard(clipboard, text);
+    }
+
     /// Set window level. Depending on the environment and user preferences
     fn set_window_level(&self, _level: WindowLevel) {}
 
@@ -333,6 +348,11 @@ pub trait WindowOps {
 
     fn toggle_fullscreen(&self) {}
 
+    /// Ask the system to prevent other applications from observing
+    /// keyboard input, eg: while a password is being entered.
+    /// This is only supported on macOS.
+    fn set_secure_input(&self, _enable: bool) {}
+
     fn config_did_change(&self, _config: &config::ConfigHandle) {}
 
     /// Configure the Window so that the desktop environment
@@ -1,5 +1,7 @@
 use crate::macos::{nsstring, nsstring_to_str};
-use cocoa::appkit::{NSFilenamesPboardType, NSPasteboard, NSStringPboardType};
+use cocoa::appkit::{
+    NSFilenamesPboardType, NSPasteboard, NSPasteboardTypeHTML, NSStringPboardType,
+};
 use cocoa::base::*;
 use cocoa::foundation::NSArray;
 
@@ -48,4 +50,22 @@ impl Clipboard {
             Ok(())
         }
     }
+
+    /// Write `html` along with `text` as its plain text alternative
+    pub fn write_html(&mut self, html: String, text: String) -> anyhow::Result<()> {
+        unsafe {
+            self.pasteboard.clearContents();
+            let wrote_text: BOOL = self
+                .pasteboard
+                .setString_forType(*nsstring(&text), NSStringPboardType);
+            let wrote_html: BOOL = self
+                .pasteboard
+                .setString_forType(*nsstring(&html), NSPasteboardTypeHTML);
+            anyhow::ensure!(
+                wrote_text == YES && wrote_html == YES,
+                "pasteboard write returned false"
+            );
+            Ok(())
+        }
+    }
 }
@@ -68,6 +68,12 @@ extern "C" {
     ) -> i32;
 }
 
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn EnableSecureEventInput() -> i32;
+    fn DisableSecureEventInput() -> i32;
+}
+
 fn round_away_from_zerof(value: f64) -> f64 {
     if value > 0. {
         value.max(1.).round()
@@ -812,6 +818,13 @@ impl WindowOps for Window {
         });
     }
 
+    fn set_movable(&self, movable: bool) {
+        Connection::with_window_inner(self.id, move |inner| {
+            inner.set_movable(movable);
+            Ok(())
+        });
+    }
+
     fn set_text_cursor_position(&self, cursor: Rect) {
         Connection::with_window_inner(self.id, move |inner| {
             inner.set_text_cursor_position(cursor);
@@ -831,6 +844,10 @@ impl WindowOps for Window {
         ClipboardContext::new().write(text).ok();
     }
 
+    fn set_clipboard_html(&self, _clipboard: Clipboard, html: String, text: String) {
+        ClipboardContext::new().write_html(html, text).ok();
+    }
+
     fn toggle_fullscreen(&self) {
         Connection::with_window_inner(self.id, move |inner| {
             inner.toggle_fullscreen();
@@ -838,6 +855,19 @@ impl WindowOps for Window {
         });
     }
 
+    fn set_secure_input(&self, enable: bool) {
+        let status = unsafe {
+            if enable {
+                EnableSecureEventInput()
+            } else {
+                DisableSecureEventInput()
+            }
+        };
+        if status != 0 {
+            log::error!("set_secure_input({enable}) failed with status {status}");
+        }
+    }
+
     fn maximize(&self) {
         Connection::with_window_inner(self.id, move |inner| {
             inner.maximize();
@@ -1273,6 +1303,12 @@ impl WindowInner {
         set_window_position(*self.window, coords);
     }
 
+    fn set_movable(&mut self, movable: bool) {
+        unsafe {
+            let () = msg_send![*self.window, setMovable: if movable { YES } else { NO }];
+        }
+    }
+
     fn set_text_cursor_position(&mut self, cursor: Rect) {
         if let Some(window_view) = WindowView::get_this(unsafe { &**self.view }) {
             window_view.inner.borrow_mut().text_cursor_position = cursor;
@@ -2444,6 +2480,12 @@ impl WindowView {
         Self::mouse_common(this, nsevent, MouseEventKind::Press(MousePress::Right));
     }
 
+    /// AppKit asks for a menu before delivering control-clicks and
+    /// right-clicks; we draw our own context menus, so never offer one
+    extern "C" fn menu_for_event(_this: &mut Object, _sel: Sel, _nsevent: id) -> id {
+        nil
+    }
+
     extern "C" fn other_mouse_down(this: &mut Object, _sel: Sel, nsevent: id) {
         // Safety: See `other_mouse_up`
         unsafe {
@@ -3313,6 +3355,10 @@ impl WindowView {
                 sel!(rightMouseUp:),
                 Self::right_mouse_up as extern "C" fn(&mut Object, Sel, id),
             );
+            cls.add_method(
+                sel!(menuForEvent:),
+                Self::menu_for_event as extern "C" fn(&mut Object, Sel, id) -> id,
+            );
             cls.add_method(
                 sel!(otherMouseDragged:),
                 Self::mouse_moved_or_dragged as extern "C" fn(&mut Object, Sel, id),
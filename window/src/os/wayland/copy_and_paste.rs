@@ -15,6 +15,46 @@ use crate::{Clipboard, ConnectionOps};
 use super::data_device::TEXT_MIME_TYPE;
 use super::state::WaylandState;
 
+pub(super) const HTML_MIME_TYPE: &str = "text/html";
+
+/// The data that we offer while we own a selection
+pub(super) struct ClipboardData {
+    text: String,
+    html: Option<String>,
+}
+
+impl ClipboardData {
+    pub(super) fn text(text: String) -> Self {
+        Self { text, html: None }
+    }
+
+    pub(super) fn html(html: String, text: String) -> Self {
+        Self {
+            text,
+            html: Some(html),
+        }
+    }
+
+    fn mime_types(&self) -> Vec<&'static str> {
+        let mut mime_types = vec![TEXT_MIME_TYPE];
+        if self.html.is_some() {
+            mime_types.push(HTML_MIME_TYPE);
+        }
+        mime_types
+    }
+
+    /// Returns the data to send to a client that asked for `mime`
+    pub(super) fn for_mime(&self, mime: &str) -> Option<&str> {
+        if mime == TEXT_MIME_TYPE {
+            Some(&self.text)
+        } else if mime == HTML_MIME_TYPE {
+            self.html.as_deref()
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct CopyAndPaste {
     data_offer: Option<SelectionOffer>,
@@ -62,7 +102,7 @@ impl CopyAndPaste {
         }
     }
 
-    pub(super) fn set_clipboard_data(&mut self, clipboard: Clipboard, data: String) {
+    pub(super) fn set_clipboard_data(&mut self, clipboard: Clipboard, data: ClipboardData) {
         let conn = crate::Connection::get().unwrap().wayland();
         let qh = conn.event_queue.borrow().handle();
         let mut wayland_state = conn.wayland_state.borrow_mut();
@@ -77,7 +117,7 @@ impl CopyAndPaste {
         match primary_selection {
             Some(primary_selection) => {
                 let manager = wayland_state.primary_selection_manager.as_ref().unwrap();
-                let source = manager.create_selection_source(&qh, [TEXT_MIME_TYPE]);
+                let source = manager.create_selection_source(&qh, data.mime_types());
                 source.set_selection(&primary_selection, last_serial);
                 wayland_state
                     .primary_selection_source
@@ -87,7 +127,7 @@ impl CopyAndPaste {
                 let data_device = &wayland_state.data_device;
                 let source = wayland_state
                     .data_device_manager_state
-                    .create_copy_paste_source(&qh, vec![TEXT_MIME_TYPE]);
+                    .create_copy_paste_source(&qh, data.mime_types());
                 source.set_selection(data_device.as_ref().unwrap(), last_serial);
                 wayland_state.copy_paste_source.replace((source, data));
             }
@@ -171,15 +211,13 @@ impl PrimarySelectionSourceHandler for WaylandState {
         mime: String,
         write_pipe: toolkit::data_device_manager::WritePipe,
     ) {
-        if mime != TEXT_MIME_TYPE {
-            return;
-        };
-
         if let Some((ps_source, data)) = &self.primary_selection_source {
             if ps_source.inner() != source {
                 return;
             }
-            write_selection_to_pipe(write_pipe, data);
+            if let Some(data) = data.for_mime(&mime) {
+                write_selection_to_pipe(write_pipe, data);
+            }
         }
     }
 
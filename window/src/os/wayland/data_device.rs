@@ -184,15 +184,13 @@ impl DataSourceHandler for WaylandState {
         mime: String,
         fd: WritePipe,
     ) {
-        if mime != TEXT_MIME_TYPE {
-            return;
-        }
-
         if let Some((cp_source, data)) = &self.copy_paste_source {
             if cp_source.inner() != source {
                 return;
             }
-            write_selection_to_pipe(fd, data);
+            if let Some(data) = data.for_mime(&mime) {
+                write_selection_to_pipe(fd, data);
+            }
         }
     }
 
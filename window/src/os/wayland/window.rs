@@ -81,7 +81,7 @@ impl WaylandDimensions for Dimensions {
     }
 }
 
-use super::copy_and_paste::CopyAndPaste;
+use super::copy_and_paste::{ClipboardData, CopyAndPaste};
 use super::pointer::{PendingMouse, PointerUserData};
 use super::state::WaylandState;
 
@@ -479,7 +479,18 @@ impl WindowOps for WaylandWindow {
                 .copy_and_paste
                 .lock()
                 .unwrap()
-                .set_clipboard_data(clipboard, text);
+                .set_clipboard_data(clipboard, ClipboardData::text(text));
+            Ok(())
+        });
+    }
+
+    fn set_clipboard_html(&self, clipboard: Clipboard, html: String, text: String) {
+        WaylandConnection::with_window_inner(self.0, move |inner| {
+            inner
+                .copy_and_paste
+                .lock()
+                .unwrap()
+                .set_clipboard_data(clipboard, ClipboardData::html(html, text));
             Ok(())
         });
     }
@@ -136,6 +136,32 @@ fn wuicolor_to_linearrgba(color: WUIColor) -> LinearRgba {
     LinearRgba::with_srgba(color.R, color.G, color.B, 255)
 }
 
+/// Wrap the html fragment `html` in a document with the header of
+/// the CF_HTML clipboard format, which holds the byte offsets of the
+/// document and of the fragment within it
+fn cf_html(html: &str) -> String {
+    let header = |start_html: usize, end_html: usize, start_frag: usize, end_frag: usize| {
+        format!(
+            "Version:0.9\r\nStartHTML:{start_html:010}\r\nEndHTML:{end_html:010}\r\n\
+             StartFragment:{start_frag:010}\r\nEndFragment:{end_frag:010}\r\n"
+        )
+    };
+    let prefix = "<html><body>\r\n<!--StartFragment-->";
+    let suffix = "<!--EndFragment-->\r\n</body></html>";
+
+    // The offsets are zero padded, so the header length doesn't
+    // depend on their values
+    let start_html = header(0, 0, 0, 0).len();
+    let start_frag = start_html + prefix.len();
+    let end_frag = start_frag + html.len();
+    let end_html = end_frag + suffix.len();
+
+    format!(
+        "{}{prefix}{html}{suffix}",
+        header(start_html, end_html, start_frag, end_frag)
+    )
+}
+
 fn rect_width(r: &RECT) -> i32 {
     r.right - r.left
 }
@@ -970,6 +996,23 @@ impl WindowOps for Window {
         clipboard_win::set_clipboard_string(&text).ok();
     }
 
+    fn set_clipboard_html(&self, _clipboard: Clipboard, html: String, text: String) {
+        let result = (|| -> anyhow::Result<()> {
+            let format = clipboard_win::raw::register_format("HTML Format")
+                .ok_or_else(|| anyhow::anyhow!("unable to register HTML Format"))?;
+            let clipboard = clipboard_win::Clipboard::new()?;
+            clipboard.empty()?;
+            clipboard.set_string(&text)?;
+            let mut data = cf_html(&html).into_bytes();
+            data.push(0);
+            clipboard.set(format, &data)?;
+            Ok(())
+        })();
+        if let Err(err) = result {
+            log::error!("Error setting html in the clipboard: {err:#}");
+        }
+    }
+
     fn set_window_drag_position(&self, coords: ScreenPoint) {
         Connection::with_window_inner(self.0, move |inner| {
             inner.window_drag_position = Some(coords);
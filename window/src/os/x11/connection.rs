@@ -62,6 +62,7 @@ pub struct XConnection {
     pub atom_xsel_data: Atom,
     pub atom_targets: Atom,
     pub atom_clipboard: Atom,
+    pub atom_texthtml: Atom,
     pub atom_texturilist: Atom,
     pub atom_xmozurl: Atom,
     pub atom_xdndaware: Atom,
@@ -679,6 +680,7 @@ impl XConnection {
         let atom_xsel_data = Self::intern_atom(&conn, "XSEL_DATA")?;
         let atom_targets = Self::intern_atom(&conn, "TARGETS")?;
         let atom_clipboard = Self::intern_atom(&conn, "CLIPBOARD")?;
+        let atom_texthtml = Self::intern_atom(&conn, "text/html")?;
         let atom_texturilist = Self::intern_atom(&conn, "text/uri-list")?;
         let atom_xmozurl = Self::intern_atom(&conn, "text/x-moz-url")?;
         let atom_xdndaware = Self::intern_atom(&conn, "XdndAware")?;
@@ -816,6 +818,7 @@ impl XConnection {
             xrm: RefCell::new(xrm),
             atom_protocols,
             atom_clipboard,
+            atom_texthtml,
             atom_texturilist,
             atom_xmozurl,
             atom_xdndaware,
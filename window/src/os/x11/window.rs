@@ -33,6 +33,10 @@ use xcb::{Event, Xid};
 struct CopyAndPaste {
     clipboard_owned: Option<String>,
     primary_selection_owned: Option<String>,
+    /// The html alternative of clipboard_owned, if any
+    clipboard_html: Option<String>,
+    /// The html alternative of primary_selection_owned, if any
+    primary_selection_html: Option<String>,
     clipboard_request: Option<Promise<String>>,
     selection_request: Option<Promise<String>>,
     time: u32,
@@ -53,6 +57,20 @@ impl CopyAndPaste {
         }
     }
 
+    fn html(&self, clipboard: Clipboard) -> &Option<String> {
+        match clipboard {
+            Clipboard::PrimarySelection => &self.primary_selection_html,
+            Clipboard::Clipboard => &self.clipboard_html,
+        }
+    }
+
+    fn html_mut(&mut self, clipboard: Clipboard) -> &mut Option<String> {
+        match clipboard {
+            Clipboard::PrimarySelection => &mut self.primary_selection_html,
+            Clipboard::Clipboard => &mut self.clipboard_html,
+        }
+    }
+
     fn request_mut(&mut self, clipboard: Clipboard) -> &mut Option<Promise<String>> {
         match clipboard {
             Clipboard::PrimarySelection => &mut self.selection_request,
@@ -964,6 +982,7 @@ impl XWindowInner {
         log::debug!("SEL: window_id={window_id:?} {:?}", request);
         if let Some(clipboard) = self.selection_atom_to_clipboard(request.selection()) {
             self.copy_and_paste.clipboard_mut(clipboard).take();
+            self.copy_and_paste.html_mut(clipboard).take();
             self.copy_and_paste.request_mut(clipboard).take();
             self.update_selection_owner(clipboard)?;
         }
@@ -987,14 +1006,19 @@ impl XWindowInner {
 
         let selprop = if request.target() == conn.atom_targets {
             // They want to know which targets we support
-            let atoms: [Atom; 1] = [conn.atom_utf8_string];
+            let mut atoms: Vec<Atom> = vec![conn.atom_utf8_string];
+            if let Some(clipboard) = self.selection_atom_to_clipboard(request.selection()) {
+                if self.copy_and_paste.html(clipboard).is_some() {
+                    atoms.push(conn.atom_texthtml);
+                }
+            }
             log::trace!("SEL: window_id={window_id:?} requestor wants supported targets");
             conn.send_request_no_reply(&xcb::x::ChangeProperty {
                 mode: PropMode::Replace,
                 window: request.requestor(),
                 property: request.property(),
                 r#type: xcb::x::ATOM_ATOM,
-                data: &atoms,
+                data: atoms.as_slice(),
             })?;
 
             // let the requestor know that we set their property
@@ -1025,6 +1049,25 @@ impl XWindowInner {
             } else {
                 xcb::x::ATOM_NONE
             }
+        } else if request.target() == conn.atom_texthtml {
+            log::trace!("SEL: window_id={window_id:?} requestor wants html data");
+            match self
+                .selection_atom_to_clipboard(request.selection())
+                .and_then(|clipboard| self.copy_and_paste.html(clipboard).as_ref())
+            {
+                Some(html) => {
+                    conn.send_request_no_reply(&xcb::x::ChangeProperty {
+                        mode: PropMode::Replace,
+                        window: request.requestor(),
+                        property: request.property(),
+                        r#type: request.target(),
+                        data: html.as_bytes(),
+                    })?;
+                    // let the requestor know that we set their property
+                    request.property()
+                }
+                None => xcb::x::ATOM_NONE,
+            }
         } else {
             // We didn't support their request, so there is nothing
             // we can report back to them.
@@ -2164,6 +2207,29 @@ impl WindowOps for XWindow {
                 .copy_and_paste
                 .clipboard_mut(clipboard)
                 .replace(text.clone());
+            inner.copy_and_paste.html_mut(clipboard).take();
+            inner.update_selection_owner(clipboard)?;
+            Ok(())
+        });
+    }
+
+    /// Set some html in the clipboard, offered as text/html
+    /// alongside its plain text alternative
+    fn set_clipboard_html(&self, clipboard: Clipboard, html: String, text: String) {
+        let window_id = self.0;
+        XConnection::with_window_inner(window_id, move |inner| {
+            log::trace!(
+                "SEL: window_id={window_id:?} now owns selection \
+                for {clipboard:?} {text:?} with html"
+            );
+            inner
+                .copy_and_paste
+                .clipboard_mut(clipboard)
+                .replace(text.clone());
+            inner
+                .copy_and_paste
+                .html_mut(clipboard)
+                .replace(html.clone());
             inner.update_selection_owner(clipboard)?;
             Ok(())
         });
@@ -408,4 +408,11 @@ impl WindowOps for Window {
             Self::Wayland(w) => w.set_clipboard(clipboard, text),
         }
     }
+    fn set_clipboard_html(&self, clipboard: Clipboard, html: String, text: String) {
+        match self {
+            Self::X11(x) => x.set_clipboard_html(clipboard, html, text),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(w) => w.set_clipboard_html(clipboard, html, text),
+        }
+    }
 }